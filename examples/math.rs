@@ -1,8 +1,5 @@
 use svg_rs::*;
 
-#[cfg(feature = "math")]
-use svg_rs::math::*;
-
 fn main() {
     #[cfg(feature = "math")]
     {
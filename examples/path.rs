@@ -1,3 +1,4 @@
+#[cfg(feature = "path")]
 use svg_rs::*;
 
 #[cfg(feature = "path")]
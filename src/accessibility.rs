@@ -0,0 +1,396 @@
+//! Accessibility auditing for svg-rs
+//!
+//! `Svg::accessibility_report` inspects a built document and flags a missing
+//! title, interactive elements that have no accessible name or cannot be
+//! reached with the keyboard, text with insufficient contrast against what is
+//! painted beneath it, and font sizes below a threshold.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::css::{Color, Paint};
+use crate::svg::{Element, Svg};
+
+/// Thresholds used when auditing a document
+#[derive(Debug, Clone)]
+pub struct AccessibilityOptions {
+    /// Minimum contrast ratio for normal text (WCAG AA: 4.5)
+    pub min_contrast: f64,
+    /// Minimum contrast ratio for large text (WCAG AA: 3.0)
+    pub min_contrast_large: f64,
+    /// Smallest acceptable font size in user units
+    pub min_font_size: f64,
+}
+
+impl Default for AccessibilityOptions {
+    fn default() -> Self {
+        Self {
+            min_contrast: 4.5,
+            min_contrast_large: 3.0,
+            min_font_size: 12.0,
+        }
+    }
+}
+
+/// A single accessibility problem found in a document
+#[derive(Debug, Clone, PartialEq)]
+pub enum AccessibilityIssue {
+    /// The root `<svg>` has no `<title>`, `aria-label` or `aria-labelledby`
+    MissingTitle,
+    /// An interactive element has no accessible name
+    MissingAccessibleName { element: String },
+    /// An interactive element cannot receive keyboard focus
+    NotKeyboardFocusable { element: String },
+    /// Text contrast against its backdrop is below the required ratio
+    LowContrast { element: String, ratio: f64, required: f64 },
+    /// Text is rendered below the minimum font size
+    SmallFontSize { element: String, size: f64, minimum: f64 },
+}
+
+/// The result of `Svg::accessibility_report`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AccessibilityReport {
+    pub issues: Vec<AccessibilityIssue>,
+}
+
+impl AccessibilityReport {
+    /// True when no issues were found
+    pub fn is_empty(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl fmt::Display for AccessibilityIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AccessibilityIssue::MissingTitle => write!(f, "document has no title"),
+            AccessibilityIssue::MissingAccessibleName { element } => {
+                write!(f, "{} is interactive but has no accessible name", element)
+            }
+            AccessibilityIssue::NotKeyboardFocusable { element } => {
+                write!(f, "{} is interactive but not keyboard focusable", element)
+            }
+            AccessibilityIssue::LowContrast { element, ratio, required } => {
+                write!(f, "{} has contrast {:.2}:1, below {:.1}:1", element, ratio, required)
+            }
+            AccessibilityIssue::SmallFontSize { element, size, minimum } => {
+                write!(f, "{} uses font size {}, below {}", element, size, minimum)
+            }
+        }
+    }
+}
+
+impl fmt::Display for AccessibilityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for issue in &self.issues {
+            writeln!(f, "- {}", issue)?;
+        }
+        Ok(())
+    }
+}
+
+/// Containers whose content is never rendered directly
+const NON_RENDERED: &[&str] = &[
+    "defs", "mask", "clipPath", "pattern", "marker", "symbol", "linearGradient", "radialGradient",
+    "title", "desc", "metadata",
+];
+
+/// Geometry painted below later elements
+enum Area {
+    Rect(f64, f64, f64, f64),
+    Ellipse(f64, f64, f64, f64),
+    Polygon(Vec<(f64, f64)>),
+}
+
+/// A painted area with its fill, or `None` when the fill cannot be resolved to a color
+struct Backdrop {
+    area: Area,
+    fill: Option<(Color, f64)>,
+}
+
+impl Area {
+    fn contains(&self, x: f64, y: f64) -> bool {
+        match self {
+            Area::Rect(rx, ry, w, h) => x >= *rx && x <= rx + w && y >= *ry && y <= ry + h,
+            Area::Ellipse(cx, cy, rx, ry) => {
+                if *rx <= 0.0 || *ry <= 0.0 {
+                    return false;
+                }
+                let (dx, dy) = ((x - cx) / rx, (y - cy) / ry);
+                dx * dx + dy * dy <= 1.0
+            }
+            Area::Polygon(points) => {
+                let mut inside = false;
+                let mut j = points.len().wrapping_sub(1);
+                for i in 0..points.len() {
+                    let (xi, yi) = points[i];
+                    let (xj, yj) = points[j];
+                    if (yi > y) != (yj > y) && x < (xj - xi) * (y - yi) / (yj - yi) + xi {
+                        inside = !inside;
+                    }
+                    j = i;
+                }
+                inside
+            }
+        }
+    }
+}
+
+impl Svg {
+    /// Audit the document with the default thresholds
+    pub fn accessibility_report(&self) -> AccessibilityReport {
+        self.accessibility_report_with(&AccessibilityOptions::default())
+    }
+
+    /// Audit the document with custom thresholds
+    ///
+    /// Contrast is checked at each text anchor against the rectangles,
+    /// circles, ellipses and polygons painted before it (composited over a
+    /// white canvas); text over gradients, patterns or images is skipped.
+    pub fn accessibility_report_with(&self, options: &AccessibilityOptions) -> AccessibilityReport {
+        let mut report = AccessibilityReport::default();
+
+        let has_title = self.elements.iter().any(|e| e.tag == "title" && !text_of(e).trim().is_empty())
+            || ["aria-label", "aria-labelledby"].iter()
+                .any(|name| self.attributes.get(*name).is_some_and(|v| !v.trim().is_empty()));
+        if !has_title {
+            report.issues.push(AccessibilityIssue::MissingTitle);
+        }
+
+        let mut backdrops: Vec<Backdrop> = Vec::new();
+        let mut tag_counts: HashMap<String, usize> = HashMap::new();
+        // (opacity, hidden) of each open ancestor, indexed by depth
+        let mut stack: Vec<(f64, bool)> = Vec::new();
+
+        self.walk_computed_styles(|node, ancestors, element, style| {
+            stack.truncate(ancestors.len());
+            let (parent_opacity, parent_hidden) = stack.last().copied().unwrap_or((1.0, false));
+            let opacity = parent_opacity * style.opacity;
+            let hidden = parent_hidden || style.display == "none" || NON_RENDERED.contains(&node.tag);
+            stack.push((opacity, hidden));
+
+            let Some(element) = element else { return };
+            let count = tag_counts.entry(node.tag.to_string()).or_insert(0);
+            *count += 1;
+            if hidden {
+                return;
+            }
+            let describe = || match node.attributes.get("id") {
+                Some(id) => format!("{}#{}", node.tag, id),
+                None => format!("{}[{}]", node.tag, count),
+            };
+
+            let in_link = ancestors.iter().any(|a| a.tag == "a");
+            let draggable = is_draggable(element);
+            if !in_link && (node.tag == "a" || node.attributes.contains_key("onclick") || draggable) {
+                if !has_accessible_name(element) {
+                    report.issues.push(AccessibilityIssue::MissingAccessibleName { element: describe() });
+                }
+                if !is_focusable(element) {
+                    report.issues.push(AccessibilityIssue::NotKeyboardFocusable { element: describe() });
+                }
+            }
+
+            let Some((dx, dy)) = ancestors.iter()
+                .map(|a| a.attributes.get("transform").map_or(Some((0.0, 0.0)), |t| parse_translation(t)))
+                .try_fold((0.0, 0.0), |acc, t| t.map(|(x, y)| (acc.0 + x, acc.1 + y)))
+            else {
+                return;
+            };
+            let own = element.attributes.get("transform").map_or(Some((0.0, 0.0)), |t| parse_translation(t));
+
+            if node.tag == "text" || node.tag == "tspan" {
+                if text_of(element).trim().is_empty() {
+                    return;
+                }
+                if style.font_size < options.min_font_size {
+                    report.issues.push(AccessibilityIssue::SmallFontSize {
+                        element: describe(),
+                        size: style.font_size,
+                        minimum: options.min_font_size,
+                    });
+                }
+
+                let Some((tx, ty)) = own else { return };
+                let Paint::Color(color) = style.fill else { return };
+                let anchor = |name: &str| {
+                    std::iter::once(node).chain(ancestors.iter().rev())
+                        .take_while(|n| n.tag == "text" || n.tag == "tspan")
+                        .find_map(|n| n.attributes.get(name).and_then(|v| first_number(v)))
+                        .unwrap_or(0.0)
+                };
+                let (x, y) = (anchor("x") + dx + tx, anchor("y") + dy + ty);
+
+                let mut background = Color::new(255, 255, 255);
+                for backdrop in backdrops.iter().filter(|b| b.area.contains(x, y)) {
+                    match backdrop.fill {
+                        Some((fill, alpha)) => background = fill.over(&background, alpha),
+                        None => return,
+                    }
+                }
+                let foreground = color.over(&background, style.fill_opacity * opacity);
+                let ratio = foreground.contrast_ratio(&background);
+                let large = style.font_size >= 24.0 || (style.font_size >= 18.66 && style.font_weight >= 700);
+                let required = if large { options.min_contrast_large } else { options.min_contrast };
+                if ratio + 1e-9 < required {
+                    report.issues.push(AccessibilityIssue::LowContrast { element: describe(), ratio, required });
+                }
+                return;
+            }
+
+            let Some((tx, ty)) = own else { return };
+            let (ox, oy) = (dx + tx, dy + ty);
+            let number = |name: &str| node.attributes.get(name).and_then(|v| first_number(v)).unwrap_or(0.0);
+            let area = match node.tag {
+                "rect" => Area::Rect(number("x") + ox, number("y") + oy, number("width"), number("height")),
+                "circle" => Area::Ellipse(number("cx") + ox, number("cy") + oy, number("r"), number("r")),
+                "ellipse" => Area::Ellipse(number("cx") + ox, number("cy") + oy, number("rx"), number("ry")),
+                "polygon" => Area::Polygon(node.attributes.get("points")
+                    .map(|p| parse_points(p).into_iter().map(|(x, y)| (x + ox, y + oy)).collect())
+                    .unwrap_or_default()),
+                "image" => Area::Rect(number("x") + ox, number("y") + oy, number("width"), number("height")),
+                _ => return,
+            };
+            let fill = match (&style.fill, node.tag) {
+                (_, "image") => None,
+                (Paint::None, _) => return,
+                (Paint::Color(color), _) => Some((*color, style.fill_opacity * opacity)),
+                (Paint::Url(_), _) => None,
+            };
+            backdrops.push(Backdrop { area, fill });
+        });
+
+        report
+    }
+}
+
+#[cfg(feature = "draggable")]
+fn is_draggable(element: &Element) -> bool {
+    element.is_draggable()
+}
+
+#[cfg(not(feature = "draggable"))]
+fn is_draggable(_element: &Element) -> bool {
+    false
+}
+
+fn has_accessible_name(element: &Element) -> bool {
+    let labelled = ["aria-label", "aria-labelledby"].iter()
+        .any(|name| element.attributes.get(*name).is_some_and(|v| !v.trim().is_empty()));
+    labelled
+        || element.children.iter().any(|c| c.tag == "title" && !text_of(c).trim().is_empty())
+        || ((element.tag == "a" || element.tag == "text") && !text_of(element).trim().is_empty())
+}
+
+fn is_focusable(element: &Element) -> bool {
+    if let Some(index) = element.attributes.get("tabindex") {
+        return index.trim().parse::<i32>().is_ok_and(|i| i >= 0);
+    }
+    element.attributes.get("focusable").is_some_and(|v| v == "true")
+        || (element.tag == "a" && element.attributes.contains_key("href"))
+}
+
+/// Text content of an element and its descendants, excluding `<title>` and `<desc>`
+fn text_of(element: &Element) -> String {
+    let mut text = element.attributes.get("text-content").cloned().unwrap_or_default();
+    for child in &element.children {
+        if child.tag != "title" && child.tag != "desc" {
+            text.push_str(&text_of(child));
+        }
+    }
+    text
+}
+
+/// The offset of a transform made only of `translate()` functions
+fn parse_translation(transform: &str) -> Option<(f64, f64)> {
+    let mut offset = (0.0, 0.0);
+    let mut rest = transform.trim();
+    while !rest.is_empty() {
+        let args = rest.strip_prefix("translate(")?;
+        let end = args.find(')')?;
+        let values: Vec<f64> = args[..end]
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse().ok())
+            .collect::<Option<_>>()?;
+        offset.0 += *values.first()?;
+        offset.1 += values.get(1).copied().unwrap_or(0.0);
+        rest = args[end + 1..].trim_start();
+    }
+    Some(offset)
+}
+
+fn first_number(value: &str) -> Option<f64> {
+    value.split(|c: char| c == ',' || c.is_whitespace()).find(|s| !s.is_empty())?.parse().ok()
+}
+
+fn parse_points(points: &str) -> Vec<(f64, f64)> {
+    let numbers: Vec<f64> = points
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter_map(|s| s.parse().ok())
+        .collect();
+    numbers.chunks_exact(2).map(|p| (p[0], p[1])).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_title_and_interactive_elements() {
+        let mut svg = Svg::new(200, 200);
+        svg.rect(20, 20).move_to(50, 50).on_click("go()");
+        svg.rect(20, 20).move_to(80, 50).on_click("go()").aria_label("Next").tabindex(0);
+        svg.link("https://example.com").text("Docs");
+
+        let report = svg.accessibility_report();
+        assert_eq!(report.issues, vec![
+            AccessibilityIssue::MissingTitle,
+            AccessibilityIssue::MissingAccessibleName { element: "rect[1]".to_string() },
+            AccessibilityIssue::NotKeyboardFocusable { element: "rect[1]".to_string() },
+        ]);
+
+        svg.title("Navigation");
+        svg.elements[1].title("Previous").focusable(true);
+        assert!(svg.accessibility_report().is_empty());
+    }
+
+    #[test]
+    fn test_text_contrast_against_backdrop() {
+        let mut svg = Svg::new(200, 200);
+        svg.title("Contrast");
+        svg.rect(100, 100).fill("#222");
+        svg.text("dark on dark").id("low").fill("#444").move_to(10, 50).font_size(14);
+        svg.text("light on dark").fill("white").move_to(10, 80).font_size(14);
+        svg.text("outside").fill("#777").move_to(150, 150).font_size(14);
+        let group = svg.group().translate(100.0, 100.0);
+        group.circle(30).fill("yellow");
+        group.text("big").id("big").fill("#999").font_size(30);
+
+        let report = svg.accessibility_report();
+        let flagged: Vec<&str> = report.issues.iter().filter_map(|issue| match issue {
+            AccessibilityIssue::LowContrast { element, .. } => Some(element.as_str()),
+            _ => None,
+        }).collect();
+        assert_eq!(flagged, vec!["text#low", "text[3]", "text#big"]);
+    }
+
+    #[test]
+    fn test_small_font_size() {
+        let mut svg = Svg::new(200, 200);
+        svg.title("Fonts");
+        svg.style_element(".tiny { font-size: 8px }");
+        svg.text("footnote").class("tiny");
+        svg.text("body").font_size(16);
+
+        let report = svg.accessibility_report_with(&AccessibilityOptions {
+            min_font_size: 10.0,
+            ..Default::default()
+        });
+        assert_eq!(report.issues, vec![AccessibilityIssue::SmallFontSize {
+            element: "text[1]".to_string(),
+            size: 8.0,
+            minimum: 10.0,
+        }]);
+    }
+}
@@ -0,0 +1,866 @@
+//! SMIL animation builder for svg-rs
+//!
+//! `Animation` describes an `<animate>`, `<set>`, `<animateTransform>` or
+//! `<animateMotion>` element with full timing control and is attached to an
+//! element with `Element::animation`.
+//!
+//! ```rust
+//! use svg_rs::*;
+//!
+//! let mut canvas = Svg::new(200, 200);
+//! canvas.circle(10).center(100, 100).animation(
+//!     Animation::animate("r")
+//!         .values(&["10", "40", "10"])
+//!         .key_times(&[0.0, 0.3, 1.0])
+//!         .dur(1.5)
+//!         .begin(Timing::on("click"))
+//!         .repeat(3.0)
+//!         .freeze(),
+//! );
+//! ```
+
+use std::collections::HashMap;
+
+use crate::easing::Easing;
+use crate::svg::{auto_id, Element, Svg};
+
+/// The type of transform animated by `<animateTransform>`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TransformType {
+    Translate,
+    Scale,
+    Rotate,
+    SkewX,
+    SkewY,
+}
+
+impl TransformType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransformType::Translate => "translate",
+            TransformType::Scale => "scale",
+            TransformType::Rotate => "rotate",
+            TransformType::SkewX => "skewX",
+            TransformType::SkewY => "skewY",
+        }
+    }
+}
+
+/// Interpolation mode between values
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CalcMode {
+    Discrete,
+    Linear,
+    Paced,
+    Spline,
+}
+
+impl CalcMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CalcMode::Discrete => "discrete",
+            CalcMode::Linear => "linear",
+            CalcMode::Paced => "paced",
+            CalcMode::Spline => "spline",
+        }
+    }
+}
+
+/// What happens to the animated value once the animation ends
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AnimationFill {
+    Remove,
+    Freeze,
+}
+
+/// Whether the animation adds to or replaces the underlying value
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Additive {
+    Replace,
+    Sum,
+}
+
+/// Whether repeat iterations build upon the previous iteration's result
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Accumulate {
+    None,
+    Sum,
+}
+
+/// Number of iterations
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RepeatCount {
+    Times(f64),
+    Indefinite,
+}
+
+/// Orientation of an element moving along a motion path
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MotionRotate {
+    Auto,
+    AutoReverse,
+    Angle(f64),
+}
+
+/// A `begin` or `end` time
+#[derive(Debug, Clone, PartialEq)]
+pub enum Timing {
+    /// Offset in seconds from the document start
+    Offset(f64),
+    /// Relative to the begin or end of another animation, e.g. `fade.end+0.5s`
+    Syncbase { id: String, end: bool, offset: f64 },
+    /// On a DOM event, e.g. `click` or `button.click+1s`
+    Event { target: Option<String>, event: String, offset: f64 },
+    /// Only started from script or another timing
+    Indefinite,
+}
+
+impl Timing {
+    /// At a fixed time in seconds
+    pub fn at(seconds: f64) -> Self {
+        Timing::Offset(seconds)
+    }
+
+    /// When the animation with the given id begins
+    pub fn with(id: &str) -> Self {
+        Timing::Syncbase { id: id.to_string(), end: false, offset: 0.0 }
+    }
+
+    /// When the animation with the given id ends
+    pub fn after(id: &str) -> Self {
+        Timing::Syncbase { id: id.to_string(), end: true, offset: 0.0 }
+    }
+
+    /// On an event fired at the animated element
+    pub fn on(event: &str) -> Self {
+        Timing::Event { target: None, event: event.to_string(), offset: 0.0 }
+    }
+
+    /// On an event fired at the element with the given id
+    pub fn on_element(id: &str, event: &str) -> Self {
+        Timing::Event { target: Some(id.to_string()), event: event.to_string(), offset: 0.0 }
+    }
+
+    /// Shift the time by a number of seconds (may be negative)
+    pub fn offset(self, seconds: f64) -> Self {
+        match self {
+            Timing::Offset(t) => Timing::Offset(t + seconds),
+            Timing::Syncbase { id, end, offset } => Timing::Syncbase { id, end, offset: offset + seconds },
+            Timing::Event { target, event, offset } => Timing::Event { target, event, offset: offset + seconds },
+            Timing::Indefinite => Timing::Indefinite,
+        }
+    }
+
+    pub fn to_value(&self) -> String {
+        let with_offset = |base: String, offset: f64| {
+            if offset == 0.0 {
+                base
+            } else if offset > 0.0 {
+                format!("{}+{}", base, format_clock(offset))
+            } else {
+                format!("{}-{}", base, format_clock(-offset))
+            }
+        };
+        match self {
+            Timing::Offset(t) => format_clock(*t),
+            Timing::Syncbase { id, end, offset } => {
+                with_offset(format!("{}.{}", id, if *end { "end" } else { "begin" }), *offset)
+            }
+            Timing::Event { target: Some(id), event, offset } => with_offset(format!("{}.{}", id, event), *offset),
+            Timing::Event { target: None, event, offset } => with_offset(event.clone(), *offset),
+            Timing::Indefinite => "indefinite".to_string(),
+        }
+    }
+}
+
+/// Format seconds as a SMIL clock value (`2s`, `1.5s`, `250ms`)
+pub fn format_clock(seconds: f64) -> String {
+    let millis = seconds * 1000.0;
+    if seconds.fract() != 0.0 && seconds.abs() < 1.0 && (millis.round() - millis).abs() < 1e-9 {
+        format!("{}ms", millis.round())
+    } else {
+        format!("{}s", round_value(seconds))
+    }
+}
+
+/// Parse a SMIL clock value (`2s`, `250ms`, `1.5`, `1min`, `00:01.5`) into seconds
+pub(crate) fn parse_clock(value: &str) -> Option<f64> {
+    let value = value.trim();
+    if value.contains(':') {
+        let parts: Vec<f64> = value.split(':').map(|p| p.parse().ok()).collect::<Option<_>>()?;
+        return match parts[..] {
+            [m, s] => Some(m * 60.0 + s),
+            [h, m, s] => Some(h * 3600.0 + m * 60.0 + s),
+            _ => None,
+        };
+    }
+    let units = [("ms", 0.001), ("min", 60.0), ("h", 3600.0), ("s", 1.0)];
+    for (unit, scale) in units {
+        if let Some(number) = value.strip_suffix(unit) {
+            return number.trim().parse::<f64>().ok().map(|n| n * scale);
+        }
+    }
+    value.parse().ok()
+}
+
+/// Interpolate between two lists of numbers such as `"0 5 5"` and `"360 5 5"`
+pub(crate) fn interpolate_numbers(from: &str, to: &str, t: f64) -> Option<String> {
+    let parse = |value: &str| value
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|part| !part.is_empty())
+        .map(|part| part.parse::<f64>().ok())
+        .collect::<Option<Vec<f64>>>();
+    let (from, to) = (parse(from)?, parse(to)?);
+    if from.len() != to.len() || from.is_empty() {
+        return None;
+    }
+    Some(from.iter().zip(&to)
+        .map(|(a, b)| round_value(a + (b - a) * t).to_string())
+        .collect::<Vec<_>>()
+        .join(" "))
+}
+
+/// Round away floating point noise for serialization
+pub(crate) fn round_value(value: f64) -> f64 {
+    let rounded = (value * 1e6).round() / 1e6;
+    if rounded == 0.0 { 0.0 } else { rounded }
+}
+
+/// The kind of SMIL element
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnimationKind {
+    Animate,
+    Set,
+    AnimateTransform(TransformType),
+    AnimateMotion,
+}
+
+/// A SMIL animation description
+#[derive(Debug, Clone, PartialEq)]
+pub struct Animation {
+    pub kind: AnimationKind,
+    pub id: Option<String>,
+    pub attribute: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub by: Option<String>,
+    pub values: Vec<String>,
+    pub key_times: Vec<f64>,
+    pub key_splines: Vec<(f64, f64, f64, f64)>,
+    pub key_points: Vec<f64>,
+    pub calc_mode: Option<CalcMode>,
+    /// Simple duration in seconds; `None` means indefinite
+    pub duration: Option<f64>,
+    pub begin: Vec<Timing>,
+    pub end: Vec<Timing>,
+    pub repeat_count: Option<RepeatCount>,
+    pub repeat_duration: Option<f64>,
+    pub fill: AnimationFill,
+    pub additive: Additive,
+    pub accumulate: Accumulate,
+    pub motion_path: Option<String>,
+    pub mpath: Option<String>,
+    pub rotate: Option<MotionRotate>,
+    pub easing: Option<Easing>,
+}
+
+impl Animation {
+    fn new(kind: AnimationKind, attribute: Option<&str>) -> Self {
+        Self {
+            kind,
+            id: None,
+            attribute: attribute.map(|a| a.to_string()),
+            from: None,
+            to: None,
+            by: None,
+            values: Vec::new(),
+            key_times: Vec::new(),
+            key_splines: Vec::new(),
+            key_points: Vec::new(),
+            calc_mode: None,
+            duration: None,
+            begin: Vec::new(),
+            end: Vec::new(),
+            repeat_count: None,
+            repeat_duration: None,
+            fill: AnimationFill::Remove,
+            additive: Additive::Replace,
+            accumulate: Accumulate::None,
+            motion_path: None,
+            mpath: None,
+            rotate: None,
+            easing: None,
+        }
+    }
+
+    /// Interpolate an attribute (`<animate>`)
+    pub fn animate(attribute: &str) -> Self {
+        Self::new(AnimationKind::Animate, Some(attribute))
+    }
+
+    /// Set an attribute to a value for the active duration (`<set>`)
+    pub fn set(attribute: &str, to: &str) -> Self {
+        Self::new(AnimationKind::Set, Some(attribute)).to(to)
+    }
+
+    /// Animate the `transform` attribute (`<animateTransform>`)
+    pub fn transform(kind: TransformType) -> Self {
+        Self::new(AnimationKind::AnimateTransform(kind), Some("transform"))
+    }
+
+    /// Move the element along a path (`<animateMotion>`)
+    pub fn motion() -> Self {
+        Self::new(AnimationKind::AnimateMotion, None)
+    }
+
+    pub fn id(mut self, id: &str) -> Self {
+        self.id = Some(id.to_string());
+        self
+    }
+
+    pub fn from(mut self, value: &str) -> Self {
+        self.from = Some(value.to_string());
+        self
+    }
+
+    pub fn to(mut self, value: &str) -> Self {
+        self.to = Some(value.to_string());
+        self
+    }
+
+    pub fn by(mut self, value: &str) -> Self {
+        self.by = Some(value.to_string());
+        self
+    }
+
+    /// Keyframe values; take precedence over `from`/`to`/`by`
+    pub fn values(mut self, values: &[&str]) -> Self {
+        self.values = values.iter().map(|v| v.to_string()).collect();
+        self
+    }
+
+    /// Times (0 to 1) at which each value is reached
+    pub fn key_times(mut self, times: &[f64]) -> Self {
+        self.key_times = times.to_vec();
+        self
+    }
+
+    /// Bézier control points (x1, y1, x2, y2) per interval; switches to spline mode
+    pub fn key_splines(mut self, splines: &[(f64, f64, f64, f64)]) -> Self {
+        self.key_splines = splines.to_vec();
+        self.calc_mode = Some(CalcMode::Spline);
+        self
+    }
+
+    pub fn calc_mode(mut self, mode: CalcMode) -> Self {
+        self.calc_mode = Some(mode);
+        self
+    }
+
+    /// Simple duration in seconds (fractions allowed)
+    pub fn dur(mut self, seconds: f64) -> Self {
+        self.duration = Some(seconds);
+        self
+    }
+
+    /// Simple duration in milliseconds
+    pub fn dur_ms(self, millis: u64) -> Self {
+        self.dur(millis as f64 / 1000.0)
+    }
+
+    /// Add a begin time; several begin times are combined
+    pub fn begin(mut self, timing: Timing) -> Self {
+        self.begin.push(timing);
+        self
+    }
+
+    /// Add an end time; several end times are combined
+    pub fn end(mut self, timing: Timing) -> Self {
+        self.end.push(timing);
+        self
+    }
+
+    /// Repeat a finite (possibly fractional) number of times
+    pub fn repeat(mut self, count: f64) -> Self {
+        self.repeat_count = Some(RepeatCount::Times(count));
+        self
+    }
+
+    pub fn repeat_indefinitely(mut self) -> Self {
+        self.repeat_count = Some(RepeatCount::Indefinite);
+        self
+    }
+
+    /// Repeat for a total number of seconds
+    pub fn repeat_dur(mut self, seconds: f64) -> Self {
+        self.repeat_duration = Some(seconds);
+        self
+    }
+
+    /// Keep the final value once the animation ends
+    pub fn freeze(mut self) -> Self {
+        self.fill = AnimationFill::Freeze;
+        self
+    }
+
+    pub fn additive(mut self, additive: Additive) -> Self {
+        self.additive = additive;
+        self
+    }
+
+    pub fn accumulate(mut self, accumulate: Accumulate) -> Self {
+        self.accumulate = accumulate;
+        self
+    }
+
+    /// Motion path data for `<animateMotion>`
+    pub fn path(mut self, d: &str) -> Self {
+        self.motion_path = Some(d.to_string());
+        self
+    }
+
+    /// Reference an existing `<path>` by id through `<mpath>`
+    pub fn mpath(mut self, path_id: &str) -> Self {
+        self.mpath = Some(path_id.to_string());
+        self
+    }
+
+    pub fn rotate(mut self, rotate: MotionRotate) -> Self {
+        self.rotate = Some(rotate);
+        self
+    }
+
+    /// Ease every interval between values with the given timing function
+    pub fn easing(mut self, easing: Easing) -> Self {
+        self.easing = Some(easing);
+        self
+    }
+
+    /// Progress (0 to 1) along the motion path for each key time
+    pub fn key_points(mut self, points: &[f64]) -> Self {
+        self.key_points = points.to_vec();
+        self
+    }
+
+    /// Total active time in seconds from begin, if finite
+    pub fn active_duration(&self) -> Option<f64> {
+        let simple = self.duration?;
+        let repeated = match self.repeat_count {
+            Some(RepeatCount::Times(count)) => Some(simple * count),
+            Some(RepeatCount::Indefinite) => None,
+            None if self.repeat_duration.is_some() => None,
+            None => Some(simple),
+        };
+        match (repeated, self.repeat_duration) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    /// Tag name of the SMIL element
+    pub fn tag(&self) -> &'static str {
+        match self.kind {
+            AnimationKind::Animate => "animate",
+            AnimationKind::Set => "set",
+            AnimationKind::AnimateTransform(_) => "animateTransform",
+            AnimationKind::AnimateMotion => "animateMotion",
+        }
+    }
+
+    /// Attributes of the SMIL element
+    pub fn attributes(&self) -> HashMap<String, String> {
+        if self.easing.is_some() {
+            return self.resolve_easing().attributes();
+        }
+        let mut attrs = HashMap::new();
+        let mut put = |name: &str, value: String| {
+            attrs.insert(name.to_string(), value);
+        };
+        let list = |values: &[f64]| values.iter()
+            .map(|v| round_value(*v).to_string())
+            .collect::<Vec<_>>()
+            .join("; ");
+
+        if let Some(id) = &self.id {
+            put("id", id.clone());
+        }
+        if let Some(attribute) = &self.attribute {
+            put("attributeName", attribute.clone());
+        }
+        if let AnimationKind::AnimateTransform(kind) = self.kind {
+            put("type", kind.as_str().to_string());
+        }
+        if !self.values.is_empty() {
+            put("values", self.values.join("; "));
+        } else {
+            for (name, value) in [("from", &self.from), ("to", &self.to), ("by", &self.by)] {
+                if let Some(value) = value {
+                    put(name, value.clone());
+                }
+            }
+        }
+        if !self.key_times.is_empty() {
+            put("keyTimes", list(&self.key_times));
+        }
+        if !self.key_splines.is_empty() {
+            put("keySplines", self.key_splines.iter()
+                .map(|(a, b, c, d)| format!("{} {} {} {}", round_value(*a), round_value(*b), round_value(*c), round_value(*d)))
+                .collect::<Vec<_>>()
+                .join("; "));
+        }
+        if !self.key_points.is_empty() {
+            put("keyPoints", list(&self.key_points));
+        }
+        if let Some(mode) = self.calc_mode {
+            put("calcMode", mode.as_str().to_string());
+        }
+        if self.kind != AnimationKind::Set || self.duration.is_some() {
+            put("dur", self.duration.map_or("indefinite".to_string(), format_clock));
+        }
+        if !self.begin.is_empty() {
+            put("begin", self.begin.iter().map(|t| t.to_value()).collect::<Vec<_>>().join("; "));
+        }
+        if !self.end.is_empty() {
+            put("end", self.end.iter().map(|t| t.to_value()).collect::<Vec<_>>().join("; "));
+        }
+        match self.repeat_count {
+            Some(RepeatCount::Times(count)) => put("repeatCount", round_value(count).to_string()),
+            Some(RepeatCount::Indefinite) => put("repeatCount", "indefinite".to_string()),
+            None => {}
+        }
+        if let Some(seconds) = self.repeat_duration {
+            put("repeatDur", format_clock(seconds));
+        }
+        if self.fill == AnimationFill::Freeze {
+            put("fill", "freeze".to_string());
+        }
+        if self.additive == Additive::Sum {
+            put("additive", "sum".to_string());
+        }
+        if self.accumulate == Accumulate::Sum {
+            put("accumulate", "sum".to_string());
+        }
+        if let Some(d) = &self.motion_path {
+            put("path", d.clone());
+        }
+        match self.rotate {
+            Some(MotionRotate::Auto) => put("rotate", "auto".to_string()),
+            Some(MotionRotate::AutoReverse) => put("rotate", "auto-reverse".to_string()),
+            Some(MotionRotate::Angle(angle)) => put("rotate", round_value(angle).to_string()),
+            None => {}
+        }
+        attrs
+    }
+
+    /// Express the easing with SMIL timing attributes
+    ///
+    /// Curves within SMIL's limits become `keySplines`. Steps and curves that
+    /// overshoot (the "back" easings) are sampled into `values` when `from` and
+    /// `to` are numeric; otherwise steps fall back to discrete and overshooting
+    /// curves to linear interpolation.
+    pub(crate) fn resolve_easing(&self) -> Animation {
+        let mut resolved = self.clone();
+        let Some(easing) = resolved.easing.take() else {
+            return resolved;
+        };
+        let intervals = resolved.values.len().saturating_sub(1).max(1);
+        if let Some(spline) = easing.key_spline() {
+            if easing != Easing::Linear {
+                if resolved.key_splines.is_empty() {
+                    resolved.key_splines = vec![spline; intervals];
+                }
+                if resolved.key_times.is_empty() {
+                    resolved.key_times = (0..=intervals).map(|i| i as f64 / intervals as f64).collect();
+                }
+                resolved.calc_mode = Some(CalcMode::Spline);
+            }
+            return resolved;
+        }
+
+        let stepped = matches!(easing, Easing::Steps(..));
+        let samples = match easing {
+            // The variant is public, so a count of 0 can get here without `Easing::parse`
+            Easing::Steps(count, _) => count.max(1) as usize,
+            _ => 16,
+        };
+        if let (true, Some(from), Some(to)) = (resolved.values.is_empty(), &resolved.from, &resolved.to) {
+            let times: Vec<f64> = (0..=samples).map(|i| i as f64 / samples as f64).collect();
+            let values: Option<Vec<String>> = times.iter()
+                .map(|t| interpolate_numbers(from, to, easing.evaluate(*t)))
+                .collect();
+            if let Some(values) = values {
+                resolved.values = values;
+                resolved.key_times = times;
+                resolved.key_splines.clear();
+            }
+        }
+        resolved.calc_mode = Some(if stepped { CalcMode::Discrete } else { CalcMode::Linear });
+        resolved
+    }
+
+    /// Build the SMIL element, including `<mpath>` for motion along a referenced path
+    pub(crate) fn to_element(&self) -> Element {
+        let mut element = Element::new(self.tag(), self.attributes());
+        if let Some(path_id) = &self.mpath {
+            let mut attrs = HashMap::new();
+            attrs.insert("href".to_string(), format!("#{}", path_id));
+            element.add_child("mpath", attrs);
+        }
+        element
+    }
+}
+
+impl Element {
+    /// Attach a SMIL animation to this element
+    pub fn animation(&mut self, animation: Animation) -> &mut Self {
+        self.children.push(animation.to_element());
+        self
+    }
+}
+
+/// A step scheduled on a `Timeline`
+#[derive(Debug, Clone)]
+struct Scheduled {
+    id: String,
+    start: Option<f64>,
+    end: Option<f64>,
+}
+
+/// Schedules animations relative to each other and emits the matching
+/// `begin` syncbase references, e.g. `begin="anim-2.end+0.5s"`
+///
+/// Each scheduling method attaches the animation to the element with the
+/// given id and returns the animation id, or `None` if no such element exists.
+pub struct Timeline<'a> {
+    svg: &'a mut Svg,
+    steps: Vec<Scheduled>,
+    group: Vec<Scheduled>,
+    gap: f64,
+}
+
+impl<'a> Timeline<'a> {
+    /// Start after everything scheduled so far has ended
+    pub fn then(&mut self, target: &str, animation: Animation) -> Option<String> {
+        let (begin, start) = self.next_start();
+        let step = self.schedule(target, animation, begin, start)?;
+        self.group = vec![step.clone()];
+        self.gap = 0.0;
+        Some(step.id)
+    }
+
+    /// Start together with the most recent sequential step
+    pub fn with(&mut self, target: &str, animation: Animation) -> Option<String> {
+        let Some(leader) = self.group.first().cloned() else {
+            return self.then(target, animation);
+        };
+        let step = self.schedule(target, animation, Timing::with(&leader.id), leader.start)?;
+        self.group.push(step.clone());
+        Some(step.id)
+    }
+
+    /// Start when the animation with the given id ends, plus any pending pause
+    pub fn after(&mut self, anchor: &str, target: &str, animation: Animation) -> Option<String> {
+        let start = self.find(anchor).and_then(|s| s.end).map(|end| end + self.gap);
+        let step = self.schedule(target, animation, Timing::after(anchor).offset(self.gap), start)?;
+        self.group = vec![step.clone()];
+        self.gap = 0.0;
+        Some(step.id)
+    }
+
+    /// Run the same animation on several elements, each starting `interval`
+    /// seconds after the previous one; the first starts like `then`
+    pub fn stagger(&mut self, targets: &[&str], interval: f64, animation: &Animation) -> Vec<String> {
+        let (begin, start) = self.next_start();
+        let mut group: Vec<Scheduled> = Vec::new();
+        for target in targets {
+            let mut animation = animation.clone();
+            animation.id = None;
+            let (begin, start) = match group.first() {
+                Some(first) => (
+                    Timing::with(&first.id).offset(interval * group.len() as f64),
+                    first.start.map(|s| s + interval * group.len() as f64),
+                ),
+                None => (begin.clone(), start),
+            };
+            if let Some(step) = self.schedule(target, animation, begin, start) {
+                group.push(step);
+            }
+        }
+        let ids = group.iter().map(|s| s.id.clone()).collect();
+        if !group.is_empty() {
+            self.group = group;
+            self.gap = 0.0;
+        }
+        ids
+    }
+
+    /// Wait before the next sequential step
+    pub fn pause(&mut self, seconds: f64) -> &mut Self {
+        self.gap += seconds;
+        self
+    }
+
+    /// Begin time and absolute start of the next sequential step
+    fn next_start(&self) -> (Timing, Option<f64>) {
+        let last = self.group.iter().fold(None::<&Scheduled>, |latest, step| match latest {
+            Some(latest) if latest.end.is_none() => Some(latest),
+            Some(latest) if step.end.is_some() && step.end <= latest.end => Some(latest),
+            _ => Some(step),
+        });
+        match last {
+            Some(step) => (Timing::after(&step.id).offset(self.gap), step.end.map(|end| end + self.gap)),
+            None => (Timing::at(self.gap), Some(self.gap)),
+        }
+    }
+
+    fn find(&self, id: &str) -> Option<&Scheduled> {
+        self.steps.iter().find(|s| s.id == id)
+    }
+
+    fn schedule(&mut self, target: &str, mut animation: Animation, begin: Timing, start: Option<f64>) -> Option<Scheduled> {
+        let element = self.svg.get_element_by_id(target)?;
+        let id = animation.id.clone().unwrap_or_else(|| auto_id("anim"));
+        animation.id = Some(id.clone());
+        animation.begin = vec![begin];
+        let end = start.zip(animation.active_duration()).map(|(s, d)| s + d);
+        element.animation(animation);
+        let step = Scheduled { id, start, end };
+        self.steps.push(step.clone());
+        Some(step)
+    }
+}
+
+impl Svg {
+    /// Schedule animations sequentially, in parallel or staggered
+    pub fn timeline(&mut self) -> Timeline<'_> {
+        Timeline { svg: self, steps: Vec::new(), group: Vec::new(), gap: 0.0 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::svg::Svg;
+
+    #[test]
+    fn test_clock_values() {
+        assert_eq!(format_clock(2.0), "2s");
+        assert_eq!(format_clock(1.5), "1.5s");
+        assert_eq!(format_clock(0.25), "250ms");
+        assert_eq!(Timing::after("fade").offset(0.5).to_value(), "fade.end+500ms");
+        assert_eq!(Timing::on_element("btn", "click").offset(-1.0).to_value(), "btn.click-1s");
+        assert_eq!(Timing::at(3.0).to_value(), "3s");
+    }
+
+    #[test]
+    fn test_animate_attributes() {
+        let animation = Animation::animate("opacity")
+            .id("fade")
+            .values(&["0", "1", "0"])
+            .key_times(&[0.0, 0.25, 1.0])
+            .key_splines(&[(0.42, 0.0, 0.58, 1.0), (0.0, 0.0, 1.0, 1.0)])
+            .dur_ms(1500)
+            .begin(Timing::at(1.0))
+            .begin(Timing::on("click"))
+            .repeat(2.5)
+            .freeze()
+            .additive(Additive::Sum)
+            .accumulate(Accumulate::Sum);
+        let attrs = animation.attributes();
+
+        assert_eq!(attrs["values"], "0; 1; 0");
+        assert_eq!(attrs["keyTimes"], "0; 0.25; 1");
+        assert_eq!(attrs["keySplines"], "0.42 0 0.58 1; 0 0 1 1");
+        assert_eq!(attrs["calcMode"], "spline");
+        assert_eq!(attrs["dur"], "1.5s");
+        assert_eq!(attrs["begin"], "1s; click");
+        assert_eq!(attrs["repeatCount"], "2.5");
+        assert_eq!(attrs["fill"], "freeze");
+        assert_eq!(attrs["additive"], "sum");
+        assert_eq!(attrs["accumulate"], "sum");
+        assert_eq!(animation.active_duration(), Some(3.75));
+    }
+
+    #[test]
+    fn test_set_transform_and_motion() {
+        let mut svg = Svg::new(200, 200);
+        svg.path("M 0 0 L 100 100").id("route");
+        svg.rect(10, 10)
+            .animation(Animation::set("visibility", "hidden").begin(Timing::at(2.0)))
+            .animation(Animation::transform(TransformType::Rotate).from("0 5 5").to("360 5 5").dur(4.0))
+            .animation(Animation::motion().mpath("route").rotate(MotionRotate::Auto).dur(3.0));
+
+        let output = svg.to_string();
+        assert!(output.contains("<set"));
+        assert!(output.contains("type=\"rotate\""));
+        assert!(output.contains("attributeName=\"transform\""));
+        assert!(output.contains("rotate=\"auto\""));
+        assert!(output.contains("<mpath href=\"#route\" />"));
+        assert!(output.contains("</animateMotion>"));
+    }
+
+    #[test]
+    fn test_timeline_sequencing() {
+        let mut svg = Svg::new(200, 200);
+        svg.rect(10, 10).id("a");
+        svg.rect(10, 10).id("b");
+        svg.circle(5).id("c");
+        svg.circle(5).id("d");
+
+        let mut timeline = svg.timeline();
+        let first = timeline.then("a", Animation::animate("x").to("100").dur(1.0).id("slide")).unwrap();
+        let parallel = timeline.with("b", Animation::animate("x").to("100").dur(2.0)).unwrap();
+        let next = timeline.pause(0.5).then("a", Animation::animate("y").to("50").dur(1.0)).unwrap();
+        let chained = timeline.after(&first, "c", Animation::animate("r").to("20").dur(1.0)).unwrap();
+        let staggered = timeline.stagger(&["c", "d"], 0.25, &Animation::animate("opacity").to("0").dur(1.0));
+        // A pause applies to `after` and does not carry over to the next step
+        let delayed = timeline.pause(1.0).after(&first, "d", Animation::animate("r").to("10").dur(1.0)).unwrap();
+        let last = timeline.then("b", Animation::animate("y").to("20").dur(1.0)).unwrap();
+        assert!(timeline.then("missing", Animation::animate("x").dur(1.0)).is_none());
+
+        assert_eq!(first, "slide");
+        let begin = |svg: &mut Svg, target: &str, id: &str| {
+            let element = svg.get_element_by_id(target).unwrap();
+            let animation = element.children.iter()
+                .find(|c| c.attributes.get("id").map(|v| v.as_str()) == Some(id))
+                .unwrap();
+            animation.attributes["begin"].clone()
+        };
+        assert_eq!(begin(&mut svg, "a", &first), "0s");
+        assert_eq!(begin(&mut svg, "b", &parallel), "slide.begin");
+        assert_eq!(begin(&mut svg, "a", &next), format!("{}.end+500ms", parallel));
+        assert_eq!(begin(&mut svg, "c", &chained), "slide.end");
+        assert_eq!(begin(&mut svg, "c", &staggered[0]), format!("{}.end", chained));
+        assert_eq!(begin(&mut svg, "d", &staggered[1]), format!("{}.begin+250ms", staggered[0]));
+        assert_eq!(begin(&mut svg, "d", &delayed), "slide.end+1s");
+        assert_eq!(begin(&mut svg, "b", &last), format!("{}.end", delayed));
+    }
+
+    #[test]
+    fn test_easing_to_smil() {
+        let attrs = Animation::animate("r").values(&["0", "10", "5"]).easing(Easing::EaseIn).dur(1.0).attributes();
+        assert_eq!(attrs["calcMode"], "spline");
+        assert_eq!(attrs["keyTimes"], "0; 0.5; 1");
+        assert_eq!(attrs["keySplines"], "0.42 0 1 1; 0.42 0 1 1");
+
+        let attrs = Animation::animate("x")
+            .from("0")
+            .to("100")
+            .easing(Easing::Steps(4, crate::easing::StepPosition::End))
+            .attributes();
+        assert_eq!(attrs["calcMode"], "discrete");
+        assert_eq!(attrs["values"], "0; 25; 50; 75; 100");
+        assert_eq!(attrs["keyTimes"], "0; 0.25; 0.5; 0.75; 1");
+        let attrs = Animation::animate("x")
+            .from("0")
+            .to("100")
+            .easing(Easing::Steps(0, crate::easing::StepPosition::End))
+            .attributes();
+        assert_eq!(attrs["keyTimes"], "0; 1");
+
+        let attrs = Animation::animate("x").from("0").to("100").easing(Easing::OutBack).attributes();
+        assert_eq!(attrs["calcMode"], "linear");
+        assert_eq!(attrs["values"].split("; ").count(), 17);
+        assert!(!attrs.contains_key("keySplines"));
+    }
+}
@@ -0,0 +1,1363 @@
+//! CSS support for svg-rs
+//!
+//! This module parses the stylesheets added with `Svg::style_element`, matches
+//! their selectors against elements and resolves the cascade. Documents can be
+//! rewritten so that the winning declarations become presentation attributes,
+//! which keeps them readable by renderers that ignore `<style>` and classes.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::svg::{Element, Svg};
+
+/// Properties that can be written as SVG presentation attributes
+pub const PRESENTATION_ATTRIBUTES: &[&str] = &[
+    "alignment-baseline", "baseline-shift", "clip", "clip-path", "clip-rule", "color",
+    "color-interpolation", "color-interpolation-filters", "color-profile", "color-rendering",
+    "cursor", "direction", "display", "dominant-baseline", "enable-background", "fill",
+    "fill-opacity", "fill-rule", "filter", "flood-color", "flood-opacity", "font-family",
+    "font-size", "font-size-adjust", "font-stretch", "font-style", "font-variant",
+    "font-weight", "glyph-orientation-horizontal", "glyph-orientation-vertical",
+    "image-rendering", "kerning", "letter-spacing", "lighting-color", "marker-end",
+    "marker-mid", "marker-start", "mask", "opacity", "overflow", "paint-order",
+    "pointer-events", "shape-rendering", "stop-color", "stop-opacity", "stroke",
+    "stroke-dasharray", "stroke-dashoffset", "stroke-linecap", "stroke-linejoin",
+    "stroke-miterlimit", "stroke-opacity", "stroke-width", "text-anchor", "text-decoration",
+    "text-rendering", "unicode-bidi", "vector-effect", "visibility", "word-spacing",
+    "writing-mode",
+];
+
+/// Properties whose value is inherited from the parent element by default
+pub const INHERITED_PROPERTIES: &[&str] = &[
+    "clip-rule", "color", "color-interpolation", "color-interpolation-filters",
+    "color-rendering", "cursor", "direction", "dominant-baseline", "fill", "fill-opacity",
+    "fill-rule", "font-family", "font-size", "font-size-adjust", "font-stretch", "font-style",
+    "font-variant", "font-weight", "glyph-orientation-horizontal",
+    "glyph-orientation-vertical", "image-rendering", "kerning", "letter-spacing",
+    "marker-end", "marker-mid", "marker-start", "paint-order", "pointer-events",
+    "shape-rendering", "stroke", "stroke-dasharray", "stroke-dashoffset", "stroke-linecap",
+    "stroke-linejoin", "stroke-miterlimit", "stroke-opacity", "stroke-width", "text-anchor",
+    "text-rendering", "visibility", "word-spacing", "writing-mode",
+];
+
+/// Check whether a property can be expressed as a presentation attribute
+pub fn is_presentation_attribute(name: &str) -> bool {
+    PRESENTATION_ATTRIBUTES.contains(&name)
+}
+
+/// Check whether a property is inherited by default
+pub fn is_inherited_property(name: &str) -> bool {
+    INHERITED_PROPERTIES.contains(&name)
+}
+
+/// A single `name: value` declaration
+#[derive(Debug, Clone, PartialEq)]
+pub struct Declaration {
+    pub name: String,
+    pub value: String,
+    pub important: bool,
+}
+
+/// Combinator joining two compound selectors
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Combinator {
+    Descendant,
+    Child,
+}
+
+/// A compound selector such as `rect.primary#main[data-kind="bar"]`
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Compound {
+    tag: Option<String>,
+    id: Option<String>,
+    classes: Vec<String>,
+    attributes: Vec<(String, Option<String>)>,
+}
+
+/// A parsed selector made of compound selectors joined by descendant or child combinators
+#[derive(Debug, Clone, PartialEq)]
+pub struct Selector {
+    compounds: Vec<Compound>,
+    combinators: Vec<Combinator>,
+}
+
+/// A style rule: a selector list with its declarations
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    pub selectors: Vec<Selector>,
+    pub declarations: Vec<Declaration>,
+}
+
+/// A parsed stylesheet
+///
+/// Rules whose selectors cannot be matched statically (pseudo-classes, sibling
+/// combinators) and at-rules such as `@keyframes` or `@media` are kept verbatim
+/// in `unsupported`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Stylesheet {
+    pub rules: Vec<Rule>,
+    pub unsupported: Vec<String>,
+}
+
+/// Options for `Svg::inline_css`
+#[derive(Debug, Clone, Default)]
+pub struct InlineCssOptions {
+    /// Remove `<style>` elements once their rules have been inlined
+    pub remove_style_elements: bool,
+    /// Remove `class` attributes once their rules have been inlined; classes
+    /// still used by rules that could not be inlined are kept
+    pub remove_classes: bool,
+}
+
+/// A view of an element used for selector matching
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Node<'a> {
+    pub(crate) tag: &'a str,
+    pub(crate) attributes: &'a HashMap<String, String>,
+}
+
+/// Where a cascaded value came from, in increasing order of precedence
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Origin {
+    Presentation,
+    Stylesheet,
+    StyleAttribute,
+}
+
+/// The winning declaration for a property
+#[derive(Debug, Clone)]
+pub(crate) struct Cascaded {
+    pub(crate) value: String,
+    pub(crate) origin: Origin,
+    priority: (bool, Origin, (u32, u32, u32), usize),
+}
+
+impl Selector {
+    /// Parse a single selector, returning `None` for unsupported syntax
+    pub fn parse(text: &str) -> Option<Selector> {
+        let chars: Vec<char> = text.trim().chars().collect();
+        let mut compounds = Vec::new();
+        let mut combinators = Vec::new();
+        let mut current = Compound::default();
+        let mut has_current = false;
+        let mut pending: Option<Combinator> = None;
+        let mut i = 0;
+
+        while i < chars.len() {
+            let c = chars[i];
+            if c.is_whitespace() {
+                if has_current {
+                    compounds.push(std::mem::take(&mut current));
+                    has_current = false;
+                }
+                i += 1;
+                continue;
+            }
+            if c == '>' {
+                if has_current {
+                    compounds.push(std::mem::take(&mut current));
+                    has_current = false;
+                }
+                if compounds.is_empty() || pending.is_some() {
+                    return None;
+                }
+                pending = Some(Combinator::Child);
+                i += 1;
+                continue;
+            }
+
+            if !has_current && !compounds.is_empty() {
+                combinators.push(pending.take().unwrap_or(Combinator::Descendant));
+            }
+            has_current = true;
+
+            match c {
+                '.' => {
+                    let (name, next) = read_ident(&chars, i + 1);
+                    if name.is_empty() {
+                        return None;
+                    }
+                    current.classes.push(name);
+                    i = next;
+                }
+                '#' => {
+                    let (name, next) = read_ident(&chars, i + 1);
+                    if name.is_empty() || current.id.is_some() {
+                        return None;
+                    }
+                    current.id = Some(name);
+                    i = next;
+                }
+                '*' => i += 1,
+                '[' => {
+                    let close = chars[i..].iter().position(|&c| c == ']')? + i;
+                    let inner: String = chars[i + 1..close].iter().collect();
+                    let attribute = match inner.split_once('=') {
+                        Some((name, value)) => {
+                            let name = name.trim();
+                            if name.is_empty() || name.ends_with(['~', '|', '^', '$', '*']) {
+                                return None;
+                            }
+                            let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+                            (name.to_string(), Some(value.to_string()))
+                        }
+                        None => (inner.trim().to_string(), None),
+                    };
+                    if attribute.0.is_empty() {
+                        return None;
+                    }
+                    current.attributes.push(attribute);
+                    i = close + 1;
+                }
+                c if is_ident_char(c) && current.tag.is_none() && current == Compound::default() => {
+                    let (name, next) = read_ident(&chars, i);
+                    current.tag = Some(name);
+                    i = next;
+                }
+                _ => return None,
+            }
+        }
+
+        if has_current {
+            compounds.push(current);
+        } else if pending.is_some() {
+            return None;
+        }
+        if compounds.is_empty() {
+            return None;
+        }
+
+        Some(Selector { compounds, combinators })
+    }
+
+    /// Selector specificity as (ids, classes and attributes, type selectors)
+    pub fn specificity(&self) -> (u32, u32, u32) {
+        self.compounds.iter().fold((0, 0, 0), |(a, b, c), compound| {
+            (
+                a + compound.id.is_some() as u32,
+                b + (compound.classes.len() + compound.attributes.len()) as u32,
+                c + compound.tag.is_some() as u32,
+            )
+        })
+    }
+
+    /// Match against a node given its ancestors (outermost first)
+    pub(crate) fn matches(&self, node: &Node, ancestors: &[Node]) -> bool {
+        self.matches_from(self.compounds.len() - 1, node, ancestors)
+    }
+
+    fn matches_from(&self, index: usize, node: &Node, ancestors: &[Node]) -> bool {
+        if !self.compounds[index].matches(node) {
+            return false;
+        }
+        if index == 0 {
+            return true;
+        }
+        match self.combinators[index - 1] {
+            Combinator::Child => match ancestors.split_last() {
+                Some((parent, rest)) => self.matches_from(index - 1, parent, rest),
+                None => false,
+            },
+            Combinator::Descendant => (0..ancestors.len())
+                .rev()
+                .any(|i| self.matches_from(index - 1, &ancestors[i], &ancestors[..i])),
+        }
+    }
+}
+
+impl Compound {
+    fn matches(&self, node: &Node) -> bool {
+        if let Some(tag) = &self.tag {
+            if tag != node.tag {
+                return false;
+            }
+        }
+        if let Some(id) = &self.id {
+            if node.attributes.get("id") != Some(id) {
+                return false;
+            }
+        }
+        if !self.classes.is_empty() {
+            let classes: Vec<&str> = node.attributes.get("class")
+                .map(|c| c.split_whitespace().collect())
+                .unwrap_or_default();
+            if !self.classes.iter().all(|c| classes.contains(&c.as_str())) {
+                return false;
+            }
+        }
+        self.attributes.iter().all(|(name, value)| match (node.attributes.get(name), value) {
+            (Some(actual), Some(expected)) => actual == expected,
+            (Some(_), None) => true,
+            (None, _) => false,
+        })
+    }
+}
+
+impl Stylesheet {
+    /// Parse CSS text into rules
+    pub fn parse(css: &str) -> Stylesheet {
+        let css = strip_comments(css);
+        let mut sheet = Stylesheet::default();
+        let mut rest = css.as_str();
+
+        loop {
+            rest = rest.trim_start();
+            if rest.is_empty() {
+                break;
+            }
+
+            if rest.starts_with('@') {
+                let end = at_rule_end(rest);
+                sheet.unsupported.push(rest[..end].trim().to_string());
+                rest = &rest[end..];
+                continue;
+            }
+
+            let Some(open) = rest.find('{') else { break };
+            let close = matching_brace(rest, open).unwrap_or(rest.len());
+            let prelude = rest[..open].trim();
+            let body = &rest[open + 1..close];
+            rest = &rest[(close + 1).min(rest.len())..];
+
+            let mut selectors = Vec::new();
+            let mut unsupported = Vec::new();
+            for text in prelude.split(',') {
+                match Selector::parse(text) {
+                    Some(selector) => selectors.push(selector),
+                    None => unsupported.push(text.trim()),
+                }
+            }
+            if !unsupported.is_empty() {
+                sheet.unsupported.push(format!("{} {{ {} }}", unsupported.join(", "), body.trim()));
+            }
+            if !selectors.is_empty() {
+                sheet.rules.push(Rule { selectors, declarations: parse_declarations(body) });
+            }
+        }
+
+        sheet
+    }
+
+    /// Append the rules of another stylesheet
+    pub fn extend(&mut self, other: Stylesheet) {
+        self.rules.extend(other.rules);
+        self.unsupported.extend(other.unsupported);
+    }
+
+    /// Resolve the winning declaration per property for a node
+    pub(crate) fn cascade(&self, node: &Node, ancestors: &[Node]) -> HashMap<String, Cascaded> {
+        let mut winners: HashMap<String, Cascaded> = HashMap::new();
+        let mut offer = |name: &str, value: &str, important: bool, origin: Origin, specificity, order| {
+            let priority = (important, origin, specificity, order);
+            if winners.get(name).is_some_and(|w| w.priority > priority) {
+                return;
+            }
+            winners.insert(name.to_string(), Cascaded { value: value.to_string(), origin, priority });
+        };
+
+        for (name, value) in node.attributes {
+            if is_presentation_attribute(name) {
+                offer(name, value, false, Origin::Presentation, (0, 0, 0), 0);
+            }
+        }
+
+        let mut order = 0;
+        for rule in &self.rules {
+            let specificity = rule.selectors.iter()
+                .filter(|s| s.matches(node, ancestors))
+                .map(|s| s.specificity())
+                .max();
+            for declaration in &rule.declarations {
+                order += 1;
+                if let Some(specificity) = specificity {
+                    offer(&declaration.name, &declaration.value, declaration.important,
+                        Origin::Stylesheet, specificity, order);
+                }
+            }
+        }
+
+        if let Some(style) = node.attributes.get("style") {
+            for declaration in parse_declarations(style) {
+                order += 1;
+                offer(&declaration.name, &declaration.value, declaration.important,
+                    Origin::StyleAttribute, (1, 0, 0), order);
+            }
+        }
+
+        winners
+    }
+}
+
+impl std::fmt::Display for Stylesheet {
+    /// Serializes the rules that could not be matched statically
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.unsupported.join("\n"))
+    }
+}
+
+impl Svg {
+    /// Collect the rules of every `<style>` element in document order
+    pub fn stylesheet(&self) -> Stylesheet {
+        fn collect(elements: &[Element], sheet: &mut Stylesheet) {
+            for element in elements {
+                if element.tag == "style" {
+                    if let Some(css) = element.attributes.get("text-content") {
+                        sheet.extend(Stylesheet::parse(css));
+                    }
+                } else {
+                    collect(&element.children, sheet);
+                }
+            }
+        }
+
+        let mut sheet = Stylesheet::default();
+        collect(&self.elements, &mut sheet);
+        sheet
+    }
+
+    /// Compute the effective style of the element with the given id
+    ///
+    /// Applies the SVG/CSS cascade: presentation attributes, stylesheet rules
+    /// by specificity and order, the `style` attribute, `!important`,
+    /// inheritance from ancestors and initial values. The root `<svg>` can be
+    /// queried through its own `id` attribute.
+    pub fn computed_style(&self, id: &str) -> Option<ComputedStyle> {
+        self.find_computed_style(|node, _| node.attributes.get("id").is_some_and(|v| v == id))
+            .map(|(_, style)| style)
+    }
+
+    /// Compute the effective style of the element at `path`, the child
+    /// indices from the document root down, as in `computed_style`
+    ///
+    /// An empty path is the root `<svg>`; `<style>` elements have no style.
+    pub fn computed_style_at(&self, path: &[usize]) -> Option<ComputedStyle> {
+        let target = match path {
+            [] => None,
+            _ => Some(self.element_at(path)?),
+        };
+        self.find_computed_style(|_, element| match (element, target) {
+            (Some(element), Some(target)) => std::ptr::eq(element, target),
+            (element, target) => element.is_none() && target.is_none(),
+        })
+        .map(|(_, style)| style)
+    }
+
+    /// The first node accepted by `matches` with its element (`None` for the
+    /// root `<svg>`) and computed style
+    pub(crate) fn find_computed_style(
+        &self,
+        mut matches: impl FnMut(&Node, Option<&Element>) -> bool,
+    ) -> Option<(Option<&Element>, ComputedStyle)> {
+        let mut found = None;
+        self.walk_computed_styles(|node, _, element, style| {
+            if found.is_none() && matches(node, element) {
+                found = Some((element, style.clone()));
+            }
+        });
+        found
+    }
+
+    /// Visit every node with its ancestors and computed style, root first
+    ///
+    /// The element is `None` for the root `<svg>`.
+    pub(crate) fn walk_computed_styles<'a>(
+        &'a self,
+        mut visit: impl FnMut(&Node<'a>, &[Node<'a>], Option<&'a Element>, &ComputedStyle),
+    ) {
+        fn walk<'a>(
+            sheet: &Stylesheet,
+            elements: &'a [Element],
+            ancestors: &mut Vec<Node<'a>>,
+            parent: &ComputedStyle,
+            viewport: (f64, f64),
+            visit: &mut impl FnMut(&Node<'a>, &[Node<'a>], Option<&'a Element>, &ComputedStyle),
+        ) {
+            for element in elements {
+                if element.tag == "style" {
+                    continue;
+                }
+                let node = Node { tag: &element.tag, attributes: &element.attributes };
+                let style = ComputedStyle::compute(&sheet.cascade(&node, ancestors), Some(parent), viewport);
+                visit(&node, ancestors, Some(element), &style);
+                ancestors.push(node);
+                walk(sheet, &element.children, ancestors, &style, viewport, visit);
+                ancestors.pop();
+            }
+        }
+
+        let sheet = self.stylesheet();
+        let viewport = self.viewport_size();
+        let root = Node { tag: "svg", attributes: &self.attributes };
+        let style = ComputedStyle::compute(&sheet.cascade(&root, &[]), None, viewport);
+        visit(&root, &[], None, &style);
+        walk(&sheet, &self.elements, &mut vec![root], &style, viewport, &mut visit);
+    }
+
+    /// Inline stylesheet rules as presentation attributes
+    ///
+    /// Every element's cascaded declarations (stylesheet rules by specificity
+    /// and order, `!important`, the `style` attribute) are resolved and the
+    /// winners written as presentation attributes. `inherit` is resolved
+    /// against the parent, and properties without a presentation attribute
+    /// are moved into the `style` attribute.
+    pub fn inline_css(&mut self, options: &InlineCssOptions) -> &mut Self {
+        let sheet = self.stylesheet();
+
+        let mut plans = Vec::new();
+        let root = Node { tag: "svg", attributes: &self.attributes };
+        let inherited = plan_inline(&sheet, &root, &[], &HashMap::new(), &mut plans);
+        plan_children(&sheet, &self.elements, &mut vec![root], &inherited, &mut plans);
+
+        let mut plans = plans.into_iter();
+        if let Some(plan) = plans.next() {
+            plan.apply(&mut self.attributes);
+        }
+        apply_children(&mut self.elements, &mut plans);
+
+        if options.remove_style_elements {
+            let mut residual = Some(sheet.to_string()).filter(|css| !css.is_empty());
+            remove_style_elements(&mut self.elements, &mut residual);
+        }
+        if options.remove_classes {
+            let kept = residual_classes(&sheet);
+            remove_classes(&mut self.attributes, &kept);
+            remove_classes_from(&mut self.elements, &kept);
+        }
+        self
+    }
+}
+
+/// Attribute changes computed for one element
+#[derive(Debug, Default)]
+struct InlinePlan {
+    set: Vec<(String, String)>,
+    remove: Vec<String>,
+    style: Option<String>,
+}
+
+impl InlinePlan {
+    fn apply(self, attributes: &mut HashMap<String, String>) {
+        for name in self.remove {
+            attributes.remove(&name);
+        }
+        for (name, value) in self.set {
+            attributes.insert(name, value);
+        }
+        match self.style {
+            Some(style) if style.is_empty() => {
+                attributes.remove("style");
+            }
+            Some(style) => {
+                attributes.insert("style".to_string(), style);
+            }
+            None => {}
+        }
+    }
+}
+
+fn plan_children<'a>(
+    sheet: &Stylesheet,
+    elements: &'a [Element],
+    ancestors: &mut Vec<Node<'a>>,
+    inherited: &HashMap<String, String>,
+    plans: &mut Vec<InlinePlan>,
+) {
+    for element in elements {
+        if element.tag == "style" {
+            continue;
+        }
+        let node = Node { tag: &element.tag, attributes: &element.attributes };
+        let effective = plan_inline(sheet, &node, ancestors, inherited, plans);
+        ancestors.push(node);
+        plan_children(sheet, &element.children, ancestors, &effective, plans);
+        ancestors.pop();
+    }
+}
+
+fn apply_children(elements: &mut [Element], plans: &mut impl Iterator<Item = InlinePlan>) {
+    for element in elements {
+        if element.tag == "style" {
+            continue;
+        }
+        if let Some(plan) = plans.next() {
+            plan.apply(&mut element.attributes);
+        }
+        apply_children(&mut element.children, plans);
+    }
+}
+
+/// Plan the inlining for one node and return the values its children inherit
+fn plan_inline(
+    sheet: &Stylesheet,
+    node: &Node,
+    ancestors: &[Node],
+    inherited: &HashMap<String, String>,
+    plans: &mut Vec<InlinePlan>,
+) -> HashMap<String, String> {
+    let cascaded = sheet.cascade(node, ancestors);
+    let mut plan = InlinePlan::default();
+    let mut style: Vec<Declaration> = node.attributes.get("style")
+        .map(|s| parse_declarations(s))
+        .unwrap_or_default();
+    let original_style = style.clone();
+
+    let mut names: Vec<&String> = cascaded.keys().collect();
+    names.sort();
+    for name in names {
+        let winner = &cascaded[name];
+        if winner.origin == Origin::Presentation {
+            continue;
+        }
+        if winner.origin == Origin::Stylesheet {
+            style.retain(|d| &d.name != name);
+        }
+
+        let value = if winner.value == "inherit" {
+            match inherited.get(name) {
+                Some(value) => value.clone(),
+                None => {
+                    if winner.origin == Origin::Stylesheet && node.attributes.contains_key(name) {
+                        plan.remove.push(name.clone());
+                    }
+                    continue;
+                }
+            }
+        } else {
+            winner.value.clone()
+        };
+
+        if is_presentation_attribute(name) {
+            plan.set.push((name.clone(), value));
+        } else if winner.origin == Origin::Stylesheet {
+            style.push(Declaration { name: name.clone(), value, important: false });
+        }
+    }
+
+    if style != original_style {
+        plan.style = Some(style.iter()
+            .map(|d| format!("{}: {}{}", d.name, d.value, if d.important { " !important" } else { "" }))
+            .collect::<Vec<_>>()
+            .join("; "));
+    }
+
+    let mut effective: HashMap<String, String> = inherited.iter()
+        .filter(|(name, _)| is_inherited_property(name))
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect();
+    for (name, value) in node.attributes {
+        if is_presentation_attribute(name) && !plan.remove.contains(name) {
+            effective.insert(name.clone(), value.clone());
+        }
+    }
+    for (name, value) in &plan.set {
+        effective.insert(name.clone(), value.clone());
+    }
+
+    plans.push(plan);
+    effective
+}
+
+fn remove_style_elements(elements: &mut Vec<Element>, residual: &mut Option<String>) {
+    elements.retain_mut(|element| {
+        if element.tag == "style" {
+            return match residual.take() {
+                Some(css) => {
+                    element.attributes.insert("text-content".to_string(), css);
+                    true
+                }
+                None => false,
+            };
+        }
+        remove_style_elements(&mut element.children, residual);
+        true
+    });
+}
+
+/// Class names used by the rules that could not be inlined
+fn residual_classes(sheet: &Stylesheet) -> HashSet<String> {
+    let mut classes = HashSet::new();
+    for rule in &sheet.unsupported {
+        let chars: Vec<char> = rule.chars().collect();
+        for (index, &c) in chars.iter().enumerate() {
+            // A dot after a digit or name is part of a number or file name
+            let after_name = index > 0 && (chars[index - 1].is_alphanumeric() || matches!(chars[index - 1], '-' | '_'));
+            if c != '.' || after_name {
+                continue;
+            }
+            let name: String = chars[index + 1..].iter().take_while(|c| c.is_alphanumeric() || matches!(c, '-' | '_')).collect();
+            if name.starts_with(|c: char| !c.is_ascii_digit()) {
+                classes.insert(name);
+            }
+        }
+    }
+    classes
+}
+
+/// Drop the classes not in `kept`, and the attribute once none are left
+fn remove_classes(attributes: &mut HashMap<String, String>, kept: &HashSet<String>) {
+    let Some(class) = attributes.get("class") else {
+        return;
+    };
+    let remaining: Vec<&str> = class.split_whitespace().filter(|name| kept.contains(*name)).collect();
+    if remaining.is_empty() {
+        attributes.remove("class");
+    } else {
+        let remaining = remaining.join(" ");
+        attributes.insert("class".to_string(), remaining);
+    }
+}
+
+fn remove_classes_from(elements: &mut [Element], kept: &HashSet<String>) {
+    for element in elements {
+        remove_classes(&mut element.attributes, kept);
+        remove_classes_from(&mut element.children, kept);
+    }
+}
+
+/// Parse a declaration block such as `fill: red; stroke-width: 2 !important`
+pub fn parse_declarations(text: &str) -> Vec<Declaration> {
+    split_top_level(text, ';')
+        .into_iter()
+        .filter_map(|part| {
+            let (name, value) = part.split_once(':')?;
+            let name = name.trim().to_ascii_lowercase();
+            let mut value = value.trim();
+            let mut important = false;
+            if let Some(index) = value.to_ascii_lowercase().rfind("!important") {
+                important = true;
+                value = value[..index].trim_end();
+            }
+            if name.is_empty() || value.is_empty() {
+                return None;
+            }
+            Some(Declaration { name, value: value.to_string(), important })
+        })
+        .collect()
+}
+
+/// Split on a separator outside of parentheses and quotes
+pub(crate) fn split_top_level(text: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"') | (None, '\'') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') => depth -= 1,
+            (None, c) if c == separator && depth == 0 => {
+                parts.push(&text[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&text[start..]);
+    parts
+}
+
+fn strip_comments(css: &str) -> String {
+    let mut result = String::with_capacity(css.len());
+    let mut rest = css;
+    while let Some(start) = rest.find("/*") {
+        result.push_str(&rest[..start]);
+        rest = match rest[start + 2..].find("*/") {
+            Some(end) => &rest[start + 2 + end + 2..],
+            None => "",
+        };
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Byte offset just past the end of an at-rule
+fn at_rule_end(css: &str) -> usize {
+    let semicolon = css.find(';');
+    let open = css.find('{');
+    match (semicolon, open) {
+        (Some(s), Some(o)) if s < o => s + 1,
+        (_, Some(o)) => matching_brace(css, o).map_or(css.len(), |c| c + 1),
+        (Some(s), None) => s + 1,
+        (None, None) => css.len(),
+    }
+}
+
+/// Index of the `}` closing the `{` at `open`
+fn matching_brace(css: &str, open: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in css[open..].char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open + i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '-' || c == '_'
+}
+
+fn read_ident(chars: &[char], start: usize) -> (String, usize) {
+    let mut end = start;
+    while end < chars.len() && (is_ident_char(chars[end]) || chars[end] == '\\') {
+        end += 1;
+    }
+    (chars[start..end].iter().collect(), end)
+}
+
+/// An sRGB color with alpha
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: f64,
+}
+
+impl Color {
+    pub fn new(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b, a: 1.0 }
+    }
+
+    /// Parse a CSS color: hex, `rgb()`/`rgba()`, `hsl()`/`hsla()`, named colors or `transparent`
+    pub fn parse(value: &str) -> Option<Color> {
+        let value = value.trim().to_ascii_lowercase();
+
+        if let Some(hex) = value.strip_prefix('#') {
+            let digits: Vec<u8> = hex.chars()
+                .map(|c| c.to_digit(16).map(|d| d as u8))
+                .collect::<Option<_>>()?;
+            return match digits.len() {
+                3 | 4 => Some(Color {
+                    r: digits[0] * 17,
+                    g: digits[1] * 17,
+                    b: digits[2] * 17,
+                    a: digits.get(3).map_or(1.0, |&a| (a * 17) as f64 / 255.0),
+                }),
+                6 | 8 => {
+                    let byte = |i: usize| digits[i] * 16 + digits[i + 1];
+                    Some(Color {
+                        r: byte(0),
+                        g: byte(2),
+                        b: byte(4),
+                        a: if digits.len() == 8 { byte(6) as f64 / 255.0 } else { 1.0 },
+                    })
+                }
+                _ => None,
+            };
+        }
+
+        if let Some((function, args)) = value.split_once('(') {
+            let args: Vec<&str> = args.strip_suffix(')')?
+                .split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+                .filter(|s| !s.is_empty())
+                .collect();
+            if args.len() != 3 && args.len() != 4 {
+                return None;
+            }
+            let alpha = match args.get(3) {
+                Some(a) => parse_fraction(a)?.clamp(0.0, 1.0),
+                None => 1.0,
+            };
+            return match function.trim() {
+                "rgb" | "rgba" => {
+                    let channel = |s: &str| -> Option<u8> {
+                        let v = match s.strip_suffix('%') {
+                            Some(p) => p.parse::<f64>().ok()? * 2.55,
+                            None => s.parse::<f64>().ok()?,
+                        };
+                        Some(v.round().clamp(0.0, 255.0) as u8)
+                    };
+                    Some(Color { r: channel(args[0])?, g: channel(args[1])?, b: channel(args[2])?, a: alpha })
+                }
+                "hsl" | "hsla" => {
+                    let h = args[0].trim_end_matches("deg").parse::<f64>().ok()?;
+                    let s = parse_fraction(args[1])?.clamp(0.0, 1.0);
+                    let l = parse_fraction(args[2])?.clamp(0.0, 1.0);
+                    let (r, g, b) = hsl_to_rgb(h, s, l);
+                    Some(Color { r, g, b, a: alpha })
+                }
+                _ => None,
+            };
+        }
+
+        if value == "transparent" {
+            return Some(Color { r: 0, g: 0, b: 0, a: 0.0 });
+        }
+        NAMED_COLORS.iter()
+            .find(|(name, _)| *name == value)
+            .map(|&(_, rgb)| Color::new((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8))
+    }
+
+    /// WCAG relative luminance of the color (alpha ignored)
+    pub fn relative_luminance(&self) -> f64 {
+        let channel = |c: u8| {
+            let c = c as f64 / 255.0;
+            if c <= 0.03928 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+        };
+        0.2126 * channel(self.r) + 0.7152 * channel(self.g) + 0.0722 * channel(self.b)
+    }
+
+    /// WCAG contrast ratio between two opaque colors, from 1 to 21
+    pub fn contrast_ratio(&self, other: &Color) -> f64 {
+        let (a, b) = (self.relative_luminance(), other.relative_luminance());
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+
+    /// Composite this color with the given opacity over an opaque backdrop
+    pub fn over(&self, backdrop: &Color, opacity: f64) -> Color {
+        let alpha = (self.a * opacity).clamp(0.0, 1.0);
+        let mix = |f: u8, b: u8| (f as f64 * alpha + b as f64 * (1.0 - alpha)).round() as u8;
+        Color::new(mix(self.r, backdrop.r), mix(self.g, backdrop.g), mix(self.b, backdrop.b))
+    }
+
+    /// Serialize as `#rrggbb`, or `rgba()` when translucent
+    pub fn to_hex(&self) -> String {
+        if self.a >= 1.0 {
+            format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+        } else {
+            format!("rgba({}, {}, {}, {})", self.r, self.g, self.b, (self.a * 1000.0).round() / 1000.0)
+        }
+    }
+}
+
+/// Parse a number or percentage as a fraction
+fn parse_fraction(value: &str) -> Option<f64> {
+    match value.strip_suffix('%') {
+        Some(p) => Some(p.parse::<f64>().ok()? / 100.0),
+        None => value.parse().ok(),
+    }
+}
+
+fn hsl_to_rgb(h: f64, s: f64, l: f64) -> (u8, u8, u8) {
+    let h = h.rem_euclid(360.0) / 360.0;
+    let q = if l < 0.5 { l * (1.0 + s) } else { l + s - l * s };
+    let p = 2.0 * l - q;
+    let channel = |t: f64| {
+        let t = t.rem_euclid(1.0);
+        let v = if t < 1.0 / 6.0 {
+            p + (q - p) * 6.0 * t
+        } else if t < 0.5 {
+            q
+        } else if t < 2.0 / 3.0 {
+            p + (q - p) * (2.0 / 3.0 - t) * 6.0
+        } else {
+            p
+        };
+        (v * 255.0).round() as u8
+    };
+    (channel(h + 1.0 / 3.0), channel(h), channel(h - 1.0 / 3.0))
+}
+
+/// A `fill` or `stroke` value
+#[derive(Debug, Clone, PartialEq)]
+pub enum Paint {
+    None,
+    Color(Color),
+    /// Paint server reference such as `url(#gradient)`, holding the IRI
+    Url(String),
+}
+
+impl Paint {
+    /// Parse a paint value, resolving `currentColor` against the given color
+    pub fn parse(value: &str, current_color: Color) -> Option<Paint> {
+        let value = value.trim();
+        if value == "none" {
+            Some(Paint::None)
+        } else if value.eq_ignore_ascii_case("currentcolor") {
+            Some(Paint::Color(current_color))
+        } else if let Some(rest) = value.strip_prefix("url(") {
+            let end = rest.find(')')?;
+            Some(Paint::Url(rest[..end].trim().trim_matches(|c| c == '"' || c == '\'').to_string()))
+        } else {
+            Color::parse(value).map(Paint::Color)
+        }
+    }
+
+    /// The solid color, if this paint is one
+    pub fn color(&self) -> Option<Color> {
+        match self {
+            Paint::Color(color) => Some(*color),
+            _ => None,
+        }
+    }
+}
+
+/// The effective style of an element after the cascade and inheritance
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComputedStyle {
+    pub color: Color,
+    pub fill: Paint,
+    pub fill_opacity: f64,
+    pub fill_rule: String,
+    pub stroke: Paint,
+    pub stroke_width: f64,
+    pub stroke_opacity: f64,
+    pub stroke_linecap: String,
+    pub stroke_linejoin: String,
+    pub stroke_miterlimit: f64,
+    pub stroke_dasharray: Vec<f64>,
+    pub stroke_dashoffset: f64,
+    pub opacity: f64,
+    pub font_family: String,
+    /// Font size in user units (px)
+    pub font_size: f64,
+    pub font_weight: u16,
+    pub font_style: String,
+    pub text_anchor: String,
+    pub display: String,
+    pub visibility: String,
+}
+
+impl Default for ComputedStyle {
+    /// Initial values as defined by SVG and CSS
+    fn default() -> Self {
+        Self {
+            color: Color::new(0, 0, 0),
+            fill: Paint::Color(Color::new(0, 0, 0)),
+            fill_opacity: 1.0,
+            fill_rule: "nonzero".to_string(),
+            stroke: Paint::None,
+            stroke_width: 1.0,
+            stroke_opacity: 1.0,
+            stroke_linecap: "butt".to_string(),
+            stroke_linejoin: "miter".to_string(),
+            stroke_miterlimit: 4.0,
+            stroke_dasharray: Vec::new(),
+            stroke_dashoffset: 0.0,
+            opacity: 1.0,
+            font_family: "serif".to_string(),
+            font_size: 16.0,
+            font_weight: 400,
+            font_style: "normal".to_string(),
+            text_anchor: "start".to_string(),
+            display: "inline".to_string(),
+            visibility: "visible".to_string(),
+        }
+    }
+}
+
+/// How a property obtains its value
+enum Source<'a> {
+    Parent,
+    Initial,
+    Value(&'a str),
+}
+
+fn source<'a>(values: &'a HashMap<String, Cascaded>, name: &str) -> Option<Source<'a>> {
+    let value = values.get(name)?.value.trim();
+    Some(match value {
+        "inherit" => Source::Parent,
+        "initial" => Source::Initial,
+        "unset" if is_inherited_property(name) => Source::Parent,
+        "unset" => Source::Initial,
+        value => Source::Value(value),
+    })
+}
+
+/// Resolve one property into a field, keeping the current value when parsing fails
+fn resolve<T: Clone>(field: &mut T, source: Option<Source>, parent: &T, initial: &T, parse: impl FnOnce(&str) -> Option<T>) {
+    match source {
+        Some(Source::Parent) => *field = parent.clone(),
+        Some(Source::Initial) => *field = initial.clone(),
+        Some(Source::Value(value)) => {
+            if let Some(parsed) = parse(value) {
+                *field = parsed;
+            }
+        }
+        None => {}
+    }
+}
+
+impl ComputedStyle {
+    /// Compute a style from cascaded values and the parent's computed style
+    pub(crate) fn compute(values: &HashMap<String, Cascaded>, parent: Option<&ComputedStyle>, viewport: (f64, f64)) -> ComputedStyle {
+        let initial = ComputedStyle::default();
+        let parent = parent.unwrap_or(&initial);
+        let mut style = parent.clone();
+        style.opacity = initial.opacity;
+        style.display = initial.display.clone();
+
+        resolve(&mut style.color, source(values, "color"), &parent.color, &initial.color, Color::parse);
+        let color = style.color;
+        let parent_size = parent.font_size;
+        resolve(&mut style.font_size, source(values, "font-size"), &parent.font_size, &initial.font_size,
+            |v| parse_font_size(v, parent_size));
+        let font_size = style.font_size;
+        let diagonal = (viewport.0 * viewport.0 + viewport.1 * viewport.1).sqrt() / std::f64::consts::SQRT_2;
+        let length = |v: &str| parse_length(v, font_size, diagonal);
+        let number = |v: &str| v.parse::<f64>().ok();
+        let keyword = |v: &str| Some(v.to_string());
+
+        resolve(&mut style.fill, source(values, "fill"), &parent.fill, &initial.fill, |v| Paint::parse(v, color));
+        resolve(&mut style.fill_opacity, source(values, "fill-opacity"), &parent.fill_opacity, &initial.fill_opacity, parse_fraction);
+        resolve(&mut style.fill_rule, source(values, "fill-rule"), &parent.fill_rule, &initial.fill_rule, keyword);
+        resolve(&mut style.stroke, source(values, "stroke"), &parent.stroke, &initial.stroke, |v| Paint::parse(v, color));
+        resolve(&mut style.stroke_width, source(values, "stroke-width"), &parent.stroke_width, &initial.stroke_width, length);
+        resolve(&mut style.stroke_opacity, source(values, "stroke-opacity"), &parent.stroke_opacity, &initial.stroke_opacity, parse_fraction);
+        resolve(&mut style.stroke_linecap, source(values, "stroke-linecap"), &parent.stroke_linecap, &initial.stroke_linecap, keyword);
+        resolve(&mut style.stroke_linejoin, source(values, "stroke-linejoin"), &parent.stroke_linejoin, &initial.stroke_linejoin, keyword);
+        resolve(&mut style.stroke_miterlimit, source(values, "stroke-miterlimit"), &parent.stroke_miterlimit, &initial.stroke_miterlimit, number);
+        resolve(&mut style.stroke_dasharray, source(values, "stroke-dasharray"), &parent.stroke_dasharray, &initial.stroke_dasharray, |v| {
+            if v == "none" {
+                return Some(Vec::new());
+            }
+            v.split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|s| !s.is_empty())
+                .map(length)
+                .collect()
+        });
+        resolve(&mut style.stroke_dashoffset, source(values, "stroke-dashoffset"), &parent.stroke_dashoffset, &initial.stroke_dashoffset, length);
+        resolve(&mut style.opacity, source(values, "opacity"), &parent.opacity, &initial.opacity, parse_fraction);
+        resolve(&mut style.font_family, source(values, "font-family"), &parent.font_family, &initial.font_family, keyword);
+        let parent_weight = parent.font_weight;
+        resolve(&mut style.font_weight, source(values, "font-weight"), &parent.font_weight, &initial.font_weight,
+            |v| parse_font_weight(v, parent_weight));
+        resolve(&mut style.font_style, source(values, "font-style"), &parent.font_style, &initial.font_style, keyword);
+        resolve(&mut style.text_anchor, source(values, "text-anchor"), &parent.text_anchor, &initial.text_anchor, keyword);
+        resolve(&mut style.display, source(values, "display"), &parent.display, &initial.display, keyword);
+        resolve(&mut style.visibility, source(values, "visibility"), &parent.visibility, &initial.visibility, keyword);
+
+        style
+    }
+}
+
+/// Parse a length into user units; percentages resolve against `percent_base`
+fn parse_length(value: &str, font_size: f64, percent_base: f64) -> Option<f64> {
+    let value = value.trim();
+    let split = value.find(|c: char| c.is_ascii_alphabetic() || c == '%').unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f64 = number.trim().parse().ok()?;
+    let scale = match unit {
+        "" | "px" => 1.0,
+        "pt" => 4.0 / 3.0,
+        "pc" => 16.0,
+        "in" => 96.0,
+        "cm" => 96.0 / 2.54,
+        "mm" => 96.0 / 25.4,
+        "em" => font_size,
+        "ex" => font_size / 2.0,
+        "rem" => 16.0,
+        "%" => percent_base / 100.0,
+        _ => return None,
+    };
+    Some(number * scale)
+}
+
+fn parse_font_size(value: &str, parent_size: f64) -> Option<f64> {
+    match value {
+        "xx-small" => Some(9.0),
+        "x-small" => Some(10.0),
+        "small" => Some(13.0),
+        "medium" => Some(16.0),
+        "large" => Some(18.0),
+        "x-large" => Some(24.0),
+        "xx-large" => Some(32.0),
+        "smaller" => Some(parent_size / 1.2),
+        "larger" => Some(parent_size * 1.2),
+        value => parse_length(value, parent_size, parent_size),
+    }
+}
+
+fn parse_font_weight(value: &str, parent_weight: u16) -> Option<u16> {
+    match value {
+        "normal" => Some(400),
+        "bold" => Some(700),
+        "bolder" => Some(match parent_weight {
+            0..=349 => 400,
+            350..=549 => 700,
+            _ => 900,
+        }),
+        "lighter" => Some(match parent_weight {
+            0..=549 => 100,
+            550..=749 => 400,
+            _ => 700,
+        }),
+        value => value.parse::<u16>().ok().filter(|w| (1..=1000).contains(w)),
+    }
+}
+
+/// CSS named colors as 0xRRGGBB
+const NAMED_COLORS: &[(&str, u32)] = &[
+    ("aliceblue", 0xf0f8ff), ("antiquewhite", 0xfaebd7), ("aqua", 0x00ffff),
+    ("aquamarine", 0x7fffd4), ("azure", 0xf0ffff), ("beige", 0xf5f5dc), ("bisque", 0xffe4c4),
+    ("black", 0x000000), ("blanchedalmond", 0xffebcd), ("blue", 0x0000ff),
+    ("blueviolet", 0x8a2be2), ("brown", 0xa52a2a), ("burlywood", 0xdeb887),
+    ("cadetblue", 0x5f9ea0), ("chartreuse", 0x7fff00), ("chocolate", 0xd2691e),
+    ("coral", 0xff7f50), ("cornflowerblue", 0x6495ed), ("cornsilk", 0xfff8dc),
+    ("crimson", 0xdc143c), ("cyan", 0x00ffff), ("darkblue", 0x00008b), ("darkcyan", 0x008b8b),
+    ("darkgoldenrod", 0xb8860b), ("darkgray", 0xa9a9a9), ("darkgreen", 0x006400),
+    ("darkgrey", 0xa9a9a9), ("darkkhaki", 0xbdb76b), ("darkmagenta", 0x8b008b),
+    ("darkolivegreen", 0x556b2f), ("darkorange", 0xff8c00), ("darkorchid", 0x9932cc),
+    ("darkred", 0x8b0000), ("darksalmon", 0xe9967a), ("darkseagreen", 0x8fbc8f),
+    ("darkslateblue", 0x483d8b), ("darkslategray", 0x2f4f4f), ("darkslategrey", 0x2f4f4f),
+    ("darkturquoise", 0x00ced1), ("darkviolet", 0x9400d3), ("deeppink", 0xff1493),
+    ("deepskyblue", 0x00bfff), ("dimgray", 0x696969), ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1e90ff), ("firebrick", 0xb22222), ("floralwhite", 0xfffaf0),
+    ("forestgreen", 0x228b22), ("fuchsia", 0xff00ff), ("gainsboro", 0xdcdcdc),
+    ("ghostwhite", 0xf8f8ff), ("gold", 0xffd700), ("goldenrod", 0xdaa520), ("gray", 0x808080),
+    ("green", 0x008000), ("greenyellow", 0xadff2f), ("grey", 0x808080), ("honeydew", 0xf0fff0),
+    ("hotpink", 0xff69b4), ("indianred", 0xcd5c5c), ("indigo", 0x4b0082), ("ivory", 0xfffff0),
+    ("khaki", 0xf0e68c), ("lavender", 0xe6e6fa), ("lavenderblush", 0xfff0f5),
+    ("lawngreen", 0x7cfc00), ("lemonchiffon", 0xfffacd), ("lightblue", 0xadd8e6),
+    ("lightcoral", 0xf08080), ("lightcyan", 0xe0ffff), ("lightgoldenrodyellow", 0xfafad2),
+    ("lightgray", 0xd3d3d3), ("lightgreen", 0x90ee90), ("lightgrey", 0xd3d3d3),
+    ("lightpink", 0xffb6c1), ("lightsalmon", 0xffa07a), ("lightseagreen", 0x20b2aa),
+    ("lightskyblue", 0x87cefa), ("lightslategray", 0x778899), ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xb0c4de), ("lightyellow", 0xffffe0), ("lime", 0x00ff00),
+    ("limegreen", 0x32cd32), ("linen", 0xfaf0e6), ("magenta", 0xff00ff), ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66cdaa), ("mediumblue", 0x0000cd), ("mediumorchid", 0xba55d3),
+    ("mediumpurple", 0x9370db), ("mediumseagreen", 0x3cb371), ("mediumslateblue", 0x7b68ee),
+    ("mediumspringgreen", 0x00fa9a), ("mediumturquoise", 0x48d1cc),
+    ("mediumvioletred", 0xc71585), ("midnightblue", 0x191970), ("mintcream", 0xf5fffa),
+    ("mistyrose", 0xffe4e1), ("moccasin", 0xffe4b5), ("navajowhite", 0xffdead),
+    ("navy", 0x000080), ("oldlace", 0xfdf5e6), ("olive", 0x808000), ("olivedrab", 0x6b8e23),
+    ("orange", 0xffa500), ("orangered", 0xff4500), ("orchid", 0xda70d6),
+    ("palegoldenrod", 0xeee8aa), ("palegreen", 0x98fb98), ("paleturquoise", 0xafeeee),
+    ("palevioletred", 0xdb7093), ("papayawhip", 0xffefd5), ("peachpuff", 0xffdab9),
+    ("peru", 0xcd853f), ("pink", 0xffc0cb), ("plum", 0xdda0dd), ("powderblue", 0xb0e0e6),
+    ("purple", 0x800080), ("rebeccapurple", 0x663399), ("red", 0xff0000),
+    ("rosybrown", 0xbc8f8f), ("royalblue", 0x4169e1), ("saddlebrown", 0x8b4513),
+    ("salmon", 0xfa8072), ("sandybrown", 0xf4a460), ("seagreen", 0x2e8b57),
+    ("seashell", 0xfff5ee), ("sienna", 0xa0522d), ("silver", 0xc0c0c0), ("skyblue", 0x87ceeb),
+    ("slateblue", 0x6a5acd), ("slategray", 0x708090), ("slategrey", 0x708090),
+    ("snow", 0xfffafa), ("springgreen", 0x00ff7f), ("steelblue", 0x4682b4), ("tan", 0xd2b48c),
+    ("teal", 0x008080), ("thistle", 0xd8bfd8), ("tomato", 0xff6347), ("turquoise", 0x40e0d0),
+    ("violet", 0xee82ee), ("wheat", 0xf5deb3), ("white", 0xffffff), ("whitesmoke", 0xf5f5f5),
+    ("yellow", 0xffff00), ("yellowgreen", 0x9acd32),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selector_specificity() {
+        assert_eq!(Selector::parse("rect").unwrap().specificity(), (0, 0, 1));
+        assert_eq!(Selector::parse("g > rect.a.b").unwrap().specificity(), (0, 2, 2));
+        assert_eq!(Selector::parse("#main [data-x=\"1\"]").unwrap().specificity(), (1, 1, 0));
+        assert!(Selector::parse("rect:hover").is_none());
+        assert!(Selector::parse("a + b").is_none());
+    }
+
+    #[test]
+    fn test_stylesheet_parsing() {
+        let sheet = Stylesheet::parse(
+            "/* c */ .a, rect:hover { fill: red !important; stroke: url(#g) }\n@keyframes spin { to { opacity: 0 } }"
+        );
+        assert_eq!(sheet.rules.len(), 1);
+        assert_eq!(sheet.rules[0].declarations[0].value, "red");
+        assert!(sheet.rules[0].declarations[0].important);
+        assert_eq!(sheet.rules[0].declarations[1].value, "url(#g)");
+        assert_eq!(sheet.unsupported.len(), 2);
+        assert!(sheet.unsupported[1].starts_with("@keyframes"));
+    }
+
+    #[test]
+    fn test_inline_specificity_and_style_attribute() {
+        let mut svg = Svg::new(100, 100);
+        svg.style_element("rect { fill: blue; stroke: gray } .hot { fill: red } #main { stroke: black }");
+        svg.rect(10, 10).class("hot").id("main").fill("green");
+        svg.rect(10, 10).class("hot").style("fill: yellow");
+        svg.inline_css(&InlineCssOptions::default());
+
+        let first = &svg.elements[1];
+        assert_eq!(first.attr("fill").unwrap(), "red");
+        assert_eq!(first.attr("stroke").unwrap(), "black");
+        let second = &svg.elements[2];
+        assert_eq!(second.attr("fill").unwrap(), "yellow");
+        assert_eq!(second.attr("stroke").unwrap(), "gray");
+    }
+
+    #[test]
+    fn test_inline_inheritance_and_important() {
+        let mut svg = Svg::new(100, 100);
+        svg.style_element("g.chart { fill: navy } .label { fill: inherit; cursor: pointer; transition: none } .x { stroke: red !important }");
+        let group = svg.group().class("chart");
+        group.text("A").class("label");
+        group.rect(5, 5).class("x").style("stroke: blue; opacity: 0.5");
+        svg.inline_css(&InlineCssOptions::default());
+
+        let group = &svg.elements[1];
+        assert_eq!(group.attr("fill").unwrap(), "navy");
+        let label = &group.children[0];
+        assert_eq!(label.attr("fill").unwrap(), "navy");
+        assert_eq!(label.attr("cursor").unwrap(), "pointer");
+        assert_eq!(label.attr("style").unwrap(), "transition: none");
+        let rect = &group.children[1];
+        assert_eq!(rect.attr("stroke").unwrap(), "red");
+        assert_eq!(rect.attr("style").unwrap(), "opacity: 0.5");
+    }
+
+    #[test]
+    fn test_inline_removes_styles_and_classes() {
+        let mut svg = Svg::new(100, 100);
+        svg.style_element(".a { fill: red }");
+        svg.defs().add_child("style", {
+            let mut attrs = HashMap::new();
+            attrs.insert("text-content".to_string(), ".a:hover { fill: blue }".to_string());
+            attrs
+        });
+        svg.rect(10, 10).class("a").add_class("b");
+        svg.circle(5).class("b");
+        svg.inline_css(&InlineCssOptions { remove_style_elements: true, remove_classes: true });
+
+        let output = svg.to_string();
+        assert!(output.contains("fill=\"red\""));
+        // `a` is still used by the `:hover` rule; `b` is not used at all
+        assert_eq!(output.matches("class=").count(), 1);
+        assert!(output.contains("class=\"a\""));
+        assert_eq!(output.matches("<style").count(), 1);
+        assert!(output.contains(".a:hover { fill: blue }"));
+    }
+
+    #[test]
+    fn test_color_parsing() {
+        assert_eq!(Color::parse("#f06"), Some(Color::new(255, 0, 102)));
+        assert_eq!(Color::parse("rgb(10, 20, 30)"), Some(Color::new(10, 20, 30)));
+        assert_eq!(Color::parse("hsl(120, 100%, 50%)"), Some(Color::new(0, 255, 0)));
+        assert_eq!(Color::parse("CornflowerBlue"), Some(Color::new(100, 149, 237)));
+        assert_eq!(Color::parse("rgba(0, 0, 0, 0.5)").unwrap().a, 0.5);
+        assert!(Color::parse("#red").is_none());
+    }
+
+    #[test]
+    fn test_computed_style_cascade() {
+        let mut svg = Svg::new(100, 100);
+        svg.style_element(".chart { font-size: 20px; stroke: red } #bar { stroke-width: 2pt }");
+        let group = svg.group().class("chart").fill("navy").set_attr("color", "green");
+        group.rect(10, 10).id("bar").stroke("blue").style("fill: currentColor; opacity: 0.5");
+        group.text("label").id("label").set_attr("font-size", "1.5em").set_attr("font-weight", "bolder");
+
+        let bar = svg.computed_style("bar").unwrap();
+        assert_eq!(bar.fill, Paint::Color(Color::new(0, 128, 0)));
+        assert_eq!(bar.stroke, Paint::Color(Color::new(0, 0, 255)));
+        assert!((bar.stroke_width - 8.0 / 3.0).abs() < 1e-9);
+        assert_eq!(bar.opacity, 0.5);
+
+        let label = svg.computed_style("label").unwrap();
+        assert_eq!(label.fill, Paint::Color(Color::new(0, 0, 128)));
+        assert_eq!(label.stroke, Paint::Color(Color::new(255, 0, 0)));
+        assert_eq!(label.font_size, 30.0);
+        assert_eq!(label.font_weight, 700);
+        assert_eq!(label.opacity, 1.0);
+        assert!(svg.computed_style("missing").is_none());
+
+        // Elements without an id are found by position
+        svg.group().fill("teal").circle(5);
+        assert_eq!(svg.computed_style_at(&[2, 0]).unwrap().fill, Paint::Color(Color::new(0, 128, 128)));
+        assert_eq!(svg.computed_style_at(&[1, 0]), Some(bar));
+        assert_eq!(svg.computed_style_at(&[]).unwrap().font_size, 16.0);
+        assert!(svg.computed_style_at(&[0]).is_none());
+        assert!(svg.computed_style_at(&[2, 1]).is_none());
+    }
+}
@@ -1,22 +1,12 @@
 /// Draggable functionality for SVG elements
 #[cfg(feature = "draggable")]
+#[derive(Clone, Default)]
 pub struct DragHandler {
     pub enabled: bool,
     pub constraints: Option<(f32, f32, f32, f32)>, // x, y, width, height
     pub grid_snap: Option<f32>,
 }
 
-#[cfg(feature = "draggable")]
-impl Default for DragHandler {
-    fn default() -> Self {
-        Self {
-            enabled: false,
-            constraints: None,
-            grid_snap: None,
-        }
-    }
-}
-
 #[cfg(feature = "draggable")]
 impl DragHandler {
     pub fn new() -> Self {
@@ -0,0 +1,305 @@
+//! Easing functions for svg-rs
+//!
+//! An `Easing` can be evaluated in Rust and serialized either as a CSS timing
+//! function or as SMIL `calcMode="spline"` + `keySplines`. The Penner easings
+//! are represented by their usual cubic Bézier approximations so that all
+//! three forms describe the same curve.
+
+use crate::animation::round_value;
+
+/// Where the jumps of a `steps()` easing happen
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StepPosition {
+    Start,
+    End,
+    None,
+    Both,
+}
+
+/// A timing function mapping progress (0 to 1) to eased progress
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Easing {
+    Linear,
+    Ease,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    CubicBezier(f64, f64, f64, f64),
+    Steps(u32, StepPosition),
+    InSine,
+    OutSine,
+    InOutSine,
+    InQuad,
+    OutQuad,
+    InOutQuad,
+    InCubic,
+    OutCubic,
+    InOutCubic,
+    InQuart,
+    OutQuart,
+    InOutQuart,
+    InQuint,
+    OutQuint,
+    InOutQuint,
+    InExpo,
+    OutExpo,
+    InOutExpo,
+    InCirc,
+    OutCirc,
+    InOutCirc,
+    InBack,
+    OutBack,
+    InOutBack,
+}
+
+impl Easing {
+    /// Parse a CSS timing function or an easing name such as `ease-in-out`,
+    /// `cubic-bezier(0.1, 0.7, 1, 0.1)`, `steps(4, end)`, `ease-out-back` or `easeOutBack`
+    pub fn parse(value: &str) -> Option<Easing> {
+        let value = value.trim();
+        if let Some(args) = function_args(value, "cubic-bezier") {
+            let numbers: Vec<f64> = args.iter().map(|a| a.parse().ok()).collect::<Option<_>>()?;
+            return match numbers[..] {
+                [x1, y1, x2, y2] if (0.0..=1.0).contains(&x1) && (0.0..=1.0).contains(&x2) => {
+                    Some(Easing::CubicBezier(x1, y1, x2, y2))
+                }
+                _ => None,
+            };
+        }
+        if let Some(args) = function_args(value, "steps") {
+            let count: u32 = args.first()?.parse().ok()?;
+            let position = match args.get(1).map(|s| s.as_str()) {
+                None | Some("end") | Some("jump-end") => StepPosition::End,
+                Some("start") | Some("jump-start") => StepPosition::Start,
+                Some("jump-none") => StepPosition::None,
+                Some("jump-both") => StepPosition::Both,
+                Some(_) => return None,
+            };
+            if count == 0 || (count == 1 && position == StepPosition::None) || args.len() > 2 {
+                return None;
+            }
+            return Some(Easing::Steps(count, position));
+        }
+
+        let name = value.chars().filter(|c| *c != '-' && *c != '_').collect::<String>().to_lowercase();
+        let css = match name.as_str() {
+            "linear" => Some(Easing::Linear),
+            "ease" => Some(Easing::Ease),
+            "easein" => Some(Easing::EaseIn),
+            "easeout" => Some(Easing::EaseOut),
+            "easeinout" => Some(Easing::EaseInOut),
+            "stepstart" => Some(Easing::Steps(1, StepPosition::Start)),
+            "stepend" => Some(Easing::Steps(1, StepPosition::End)),
+            _ => None,
+        };
+        if css.is_some() {
+            return css;
+        }
+        Some(match name.strip_prefix("ease").unwrap_or(&name) {
+            "insine" => Easing::InSine,
+            "outsine" => Easing::OutSine,
+            "inoutsine" => Easing::InOutSine,
+            "inquad" => Easing::InQuad,
+            "outquad" => Easing::OutQuad,
+            "inoutquad" => Easing::InOutQuad,
+            "incubic" => Easing::InCubic,
+            "outcubic" => Easing::OutCubic,
+            "inoutcubic" => Easing::InOutCubic,
+            "inquart" => Easing::InQuart,
+            "outquart" => Easing::OutQuart,
+            "inoutquart" => Easing::InOutQuart,
+            "inquint" => Easing::InQuint,
+            "outquint" => Easing::OutQuint,
+            "inoutquint" => Easing::InOutQuint,
+            "inexpo" => Easing::InExpo,
+            "outexpo" => Easing::OutExpo,
+            "inoutexpo" => Easing::InOutExpo,
+            "incirc" => Easing::InCirc,
+            "outcirc" => Easing::OutCirc,
+            "inoutcirc" => Easing::InOutCirc,
+            "inback" => Easing::InBack,
+            "outback" => Easing::OutBack,
+            "inoutback" => Easing::InOutBack,
+            _ => return None,
+        })
+    }
+
+    /// Control points (x1, y1, x2, y2) of the curve, or `None` for `steps()`
+    pub fn bezier(&self) -> Option<(f64, f64, f64, f64)> {
+        Some(match *self {
+            Easing::Linear => (0.0, 0.0, 1.0, 1.0),
+            Easing::Ease => (0.25, 0.1, 0.25, 1.0),
+            Easing::EaseIn => (0.42, 0.0, 1.0, 1.0),
+            Easing::EaseOut => (0.0, 0.0, 0.58, 1.0),
+            Easing::EaseInOut => (0.42, 0.0, 0.58, 1.0),
+            Easing::CubicBezier(x1, y1, x2, y2) => (x1, y1, x2, y2),
+            Easing::Steps(..) => return None,
+            Easing::InSine => (0.12, 0.0, 0.39, 0.0),
+            Easing::OutSine => (0.61, 1.0, 0.88, 1.0),
+            Easing::InOutSine => (0.37, 0.0, 0.63, 1.0),
+            Easing::InQuad => (0.11, 0.0, 0.5, 0.0),
+            Easing::OutQuad => (0.5, 1.0, 0.89, 1.0),
+            Easing::InOutQuad => (0.45, 0.0, 0.55, 1.0),
+            Easing::InCubic => (0.32, 0.0, 0.67, 0.0),
+            Easing::OutCubic => (0.33, 1.0, 0.68, 1.0),
+            Easing::InOutCubic => (0.65, 0.0, 0.35, 1.0),
+            Easing::InQuart => (0.5, 0.0, 0.75, 0.0),
+            Easing::OutQuart => (0.25, 1.0, 0.5, 1.0),
+            Easing::InOutQuart => (0.76, 0.0, 0.24, 1.0),
+            Easing::InQuint => (0.64, 0.0, 0.78, 0.0),
+            Easing::OutQuint => (0.22, 1.0, 0.36, 1.0),
+            Easing::InOutQuint => (0.83, 0.0, 0.17, 1.0),
+            Easing::InExpo => (0.7, 0.0, 0.84, 0.0),
+            Easing::OutExpo => (0.16, 1.0, 0.3, 1.0),
+            Easing::InOutExpo => (0.87, 0.0, 0.13, 1.0),
+            Easing::InCirc => (0.55, 0.0, 1.0, 0.45),
+            Easing::OutCirc => (0.0, 0.55, 0.45, 1.0),
+            Easing::InOutCirc => (0.85, 0.0, 0.15, 1.0),
+            Easing::InBack => (0.36, 0.0, 0.66, -0.56),
+            Easing::OutBack => (0.34, 1.56, 0.64, 1.0),
+            Easing::InOutBack => (0.68, -0.6, 0.32, 1.6),
+        })
+    }
+
+    /// SMIL `keySplines` entry, if the curve can be expressed as one
+    /// (SMIL requires all control values to lie within 0 to 1)
+    pub fn key_spline(&self) -> Option<(f64, f64, f64, f64)> {
+        let (x1, y1, x2, y2) = self.bezier()?;
+        [x1, y1, x2, y2].iter().all(|v| (0.0..=1.0).contains(v)).then_some((x1, y1, x2, y2))
+    }
+
+    /// Eased progress for linear progress `t` (clamped to 0 to 1)
+    pub fn evaluate(&self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match *self {
+            Easing::Linear => t,
+            Easing::Steps(count, position) => {
+                let count = count as f64;
+                let (mut step, jumps) = match position {
+                    StepPosition::Start => ((t * count).floor() + 1.0, count),
+                    StepPosition::End => ((t * count).floor(), count),
+                    StepPosition::None => ((t * count).floor(), count - 1.0),
+                    StepPosition::Both => ((t * count).floor() + 1.0, count + 1.0),
+                };
+                step = step.min(jumps);
+                if jumps <= 0.0 { t } else { step / jumps }
+            }
+            _ => {
+                let (x1, y1, x2, y2) = self.bezier().unwrap();
+                cubic_bezier(x1, y1, x2, y2, t)
+            }
+        }
+    }
+
+    /// CSS `<easing-function>` value
+    pub fn to_css(&self) -> String {
+        match *self {
+            Easing::Linear => "linear".to_string(),
+            Easing::Ease => "ease".to_string(),
+            Easing::EaseIn => "ease-in".to_string(),
+            Easing::EaseOut => "ease-out".to_string(),
+            Easing::EaseInOut => "ease-in-out".to_string(),
+            Easing::Steps(count, position) => {
+                let position = match position {
+                    StepPosition::Start => "start",
+                    StepPosition::End => "end",
+                    StepPosition::None => "jump-none",
+                    StepPosition::Both => "jump-both",
+                };
+                format!("steps({}, {})", count, position)
+            }
+            _ => {
+                let (x1, y1, x2, y2) = self.bezier().unwrap();
+                format!(
+                    "cubic-bezier({}, {}, {}, {})",
+                    round_value(x1), round_value(y1), round_value(x2), round_value(y2)
+                )
+            }
+        }
+    }
+}
+
+/// Arguments of `name(a, b, ...)`, if `value` is a call to `name`
+fn function_args(value: &str, name: &str) -> Option<Vec<String>> {
+    let inner = value.strip_prefix(name)?.trim_start().strip_prefix('(')?.strip_suffix(')')?;
+    Some(inner.split(',').map(|a| a.trim().to_string()).collect())
+}
+
+/// Evaluate a CSS cubic Bézier timing function at progress `x`
+pub(crate) fn cubic_bezier(x1: f64, y1: f64, x2: f64, y2: f64, x: f64) -> f64 {
+    if x <= 0.0 || x >= 1.0 {
+        return x.clamp(0.0, 1.0);
+    }
+    let sample = |a: f64, b: f64, s: f64| {
+        let inv = 1.0 - s;
+        3.0 * inv * inv * s * a + 3.0 * inv * s * s * b + s * s * s
+    };
+    let slope = |a: f64, b: f64, s: f64| {
+        let inv = 1.0 - s;
+        3.0 * inv * inv * a + 6.0 * inv * s * (b - a) + 3.0 * s * s * (1.0 - b)
+    };
+
+    // Newton's method, falling back to bisection where the slope is flat
+    let mut s = x;
+    for _ in 0..8 {
+        let error = sample(x1, x2, s) - x;
+        if error.abs() < 1e-7 {
+            return sample(y1, y2, s);
+        }
+        let d = slope(x1, x2, s);
+        if d.abs() < 1e-6 {
+            break;
+        }
+        s -= error / d;
+    }
+    let (mut lo, mut hi) = (0.0, 1.0);
+    s = x;
+    for _ in 0..60 {
+        let value = sample(x1, x2, s);
+        if (value - x).abs() < 1e-7 {
+            break;
+        }
+        if value < x { lo = s } else { hi = s }
+        s = (lo + hi) / 2.0;
+    }
+    sample(y1, y2, s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_easing() {
+        assert_eq!(Easing::parse("ease-in-out"), Some(Easing::EaseInOut));
+        assert_eq!(Easing::parse("easeOutBack"), Some(Easing::OutBack));
+        assert_eq!(Easing::parse("ease-in-quad"), Some(Easing::InQuad));
+        assert_eq!(Easing::parse("cubic-bezier(0.1, 0.7, 1.0, 0.1)"), Some(Easing::CubicBezier(0.1, 0.7, 1.0, 0.1)));
+        assert_eq!(Easing::parse("steps(4)"), Some(Easing::Steps(4, StepPosition::End)));
+        assert_eq!(Easing::parse("step-start"), Some(Easing::Steps(1, StepPosition::Start)));
+        assert_eq!(Easing::parse("cubic-bezier(2, 0, 0, 1)"), None);
+        assert_eq!(Easing::parse("wobble"), None);
+    }
+
+    #[test]
+    fn test_evaluate_easing() {
+        assert_eq!(Easing::Linear.evaluate(0.3), 0.3);
+        assert!((Easing::EaseInOut.evaluate(0.5) - 0.5).abs() < 1e-6);
+        assert!(Easing::EaseIn.evaluate(0.25) < 0.25);
+        assert!(Easing::OutBack.evaluate(0.7) > 1.0);
+        assert!((Easing::InQuad.evaluate(0.5) - 0.25).abs() < 0.03);
+        assert_eq!(Easing::Steps(4, StepPosition::End).evaluate(0.3), 0.25);
+        assert_eq!(Easing::Steps(4, StepPosition::Start).evaluate(0.3), 0.5);
+        assert_eq!(Easing::Steps(4, StepPosition::End).evaluate(1.0), 1.0);
+    }
+
+    #[test]
+    fn test_serialize_easing() {
+        assert_eq!(Easing::EaseIn.to_css(), "ease-in");
+        assert_eq!(Easing::InSine.to_css(), "cubic-bezier(0.12, 0, 0.39, 0)");
+        assert_eq!(Easing::Steps(3, StepPosition::Start).to_css(), "steps(3, start)");
+        assert_eq!(Easing::EaseOut.key_spline(), Some((0.0, 0.0, 0.58, 1.0)));
+        assert_eq!(Easing::OutBack.key_spline(), None);
+        assert_eq!(Easing::Steps(2, StepPosition::End).key_spline(), None);
+    }
+}
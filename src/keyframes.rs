@@ -0,0 +1,337 @@
+//! CSS keyframe backend for animations
+//!
+//! SMIL animations built with `animate_attr`, `Animation` and friends can be
+//! emitted as CSS instead: an `@keyframes` rule per animation in a generated
+//! `<style>` element and an `animation` property on the animated element.
+//! Only animations with a CSS equivalent are converted (presentation
+//! attributes and transforms with a fixed begin offset); the rest stay SMIL.
+
+use std::collections::HashMap;
+
+use crate::animation::{format_clock, parse_clock, round_value};
+use crate::css::{is_presentation_attribute, split_top_level};
+use crate::svg::{Element, Svg};
+
+/// Which animation technology the document is rendered with
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AnimationMode {
+    /// `<animate>` and friends (default)
+    Smil,
+    /// `@keyframes` and `animation` properties where possible
+    Css,
+    /// Both, for environments that support only one of them
+    Both,
+}
+
+/// Properties whose bare numbers need a `px` unit in CSS
+const LENGTH_PROPERTIES: &[&str] = &[
+    "stroke-width", "stroke-dashoffset", "stroke-dasharray", "font-size", "letter-spacing",
+    "word-spacing", "baseline-shift",
+];
+
+impl Svg {
+    /// Choose whether animations are emitted as SMIL, CSS or both
+    pub fn animation_mode(&mut self, mode: AnimationMode) -> &mut Self {
+        self.animation_mode = mode;
+        self
+    }
+
+    /// Disable CSS animations for users who prefer reduced motion (on by default)
+    pub fn reduced_motion_guard(&mut self, enabled: bool) -> &mut Self {
+        self.reduced_motion_guard = enabled;
+        self
+    }
+
+    /// A copy of the document with CSS animations generated, if any apply
+    pub(crate) fn with_css_animations(&self) -> Option<Svg> {
+        if self.animation_mode == AnimationMode::Smil {
+            return None;
+        }
+        let mut document = self.clone();
+        document.animation_mode = AnimationMode::Smil;
+        let keep_smil = self.animation_mode == AnimationMode::Both;
+        let mut keyframes = Vec::new();
+        convert_elements(&mut document.elements, keep_smil, &mut keyframes);
+        if keyframes.is_empty() {
+            return None;
+        }
+
+        let mut css = keyframes.join("\n");
+        if self.reduced_motion_guard {
+            css.push_str("\n@media (prefers-reduced-motion: reduce) {\n  * { animation: none !important; }\n}");
+        }
+        let mut attrs = HashMap::new();
+        attrs.insert("text-content".to_string(), css);
+        let index = document.elements.iter()
+            .position(|e| e.tag != "title" && e.tag != "desc")
+            .unwrap_or(document.elements.len());
+        document.elements.insert(index, Element::new("style", attrs));
+        Some(document)
+    }
+}
+
+fn convert_elements(elements: &mut [Element], keep_smil: bool, keyframes: &mut Vec<String>) {
+    for element in elements {
+        let transform_animations = element.children.iter()
+            .filter(|c| is_animation(c) && c.attributes.get("attributeName").map_or(c.tag == "animateTransform", |a| a == "transform"))
+            .count();
+        let base_transform = element.attributes.get("transform").cloned();
+
+        let mut shorthands = Vec::new();
+        let mut converted = Vec::new();
+        for (index, child) in element.children.iter().enumerate() {
+            if !is_animation(child) {
+                continue;
+            }
+            let is_transform = child.tag == "animateTransform"
+                || child.attributes.get("attributeName").map(|a| a.as_str()) == Some("transform");
+            if is_transform && transform_animations > 1 {
+                continue;
+            }
+            let name = format!("svg-anim-{}", keyframes.len() + 1);
+            if let Some((rule, shorthand)) = css_animation(child, &name, base_transform.as_deref()) {
+                keyframes.push(rule);
+                shorthands.push(shorthand);
+                converted.push(index);
+            }
+        }
+
+        if !shorthands.is_empty() {
+            let animation = format!("animation: {}", shorthands.join(", "));
+            let style = match element.attributes.get("style") {
+                Some(style) if !style.trim().is_empty() => {
+                    format!("{}; {}", style.trim().trim_end_matches(';'), animation)
+                }
+                _ => animation,
+            };
+            element.attributes.insert("style".to_string(), style);
+        }
+        if !keep_smil {
+            for index in converted.into_iter().rev() {
+                element.children.remove(index);
+            }
+        }
+        convert_elements(&mut element.children, keep_smil, keyframes);
+    }
+}
+
+fn is_animation(element: &Element) -> bool {
+    element.tag == "animate" || element.tag == "animateTransform"
+}
+
+/// The `@keyframes` rule and `animation` shorthand for a SMIL element, if it has a CSS equivalent
+fn css_animation(smil: &Element, name: &str, base_transform: Option<&str>) -> Option<(String, String)> {
+    let attr = |key: &str| smil.attributes.get(key).map(|v| v.trim());
+    let transform_type = if smil.tag == "animateTransform" { Some(attr("type").unwrap_or("translate")) } else { None };
+    let property = match transform_type {
+        Some(_) => "transform",
+        None => attr("attributeName")?,
+    };
+    if property != "transform" && !is_presentation_attribute(property) {
+        return None;
+    }
+    if attr("by").is_some() || attr("end").is_some() || attr("accumulate") == Some("sum") {
+        return None;
+    }
+    let additive = attr("additive") == Some("sum");
+    if additive && property != "transform" {
+        return None;
+    }
+
+    let duration = parse_clock(attr("dur")?)?;
+    let delay = match attr("begin") {
+        Some(begin) => parse_clock(begin)?,
+        None => 0.0,
+    };
+    let iterations = match (attr("repeatCount"), attr("repeatDur")) {
+        (Some("indefinite"), _) | (None, Some("indefinite")) => "infinite".to_string(),
+        (Some(count), _) => round_value(count.parse::<f64>().ok()?).to_string(),
+        (None, Some(total)) => round_value(parse_clock(total)? / duration).to_string(),
+        (None, None) => "1".to_string(),
+    };
+    if duration <= 0.0 {
+        return None;
+    }
+
+    // Keyframe values and offsets
+    let values: Vec<String> = match attr("values") {
+        Some(values) => values.split(';').map(|v| v.trim().to_string()).filter(|v| !v.is_empty()).collect(),
+        None => [attr("from"), attr("to")].iter().flatten().map(|v| v.to_string()).collect(),
+    };
+    if values.is_empty() || (attr("values").is_none() && attr("to").is_none()) {
+        return None;
+    }
+    let to_only = attr("values").is_none() && attr("from").is_none();
+    let offsets: Vec<f64> = match attr("keyTimes") {
+        Some(times) => times.split(';').map(|t| t.trim().parse().ok()).collect::<Option<_>>()?,
+        None if to_only => vec![1.0],
+        None if values.len() == 1 => vec![0.0],
+        None => (0..values.len()).map(|i| i as f64 / (values.len() - 1) as f64).collect(),
+    };
+    if offsets.len() != values.len() {
+        return None;
+    }
+
+    let calc_mode = attr("calcMode").unwrap_or("linear");
+    let splines: Vec<String> = match attr("keySplines") {
+        Some(splines) if calc_mode == "spline" => splines.split(';')
+            .map(|s| {
+                let n: Vec<f64> = s.split(|c: char| c.is_whitespace() || c == ',')
+                    .filter(|p| !p.is_empty())
+                    .map(|p| p.parse().ok())
+                    .collect::<Option<_>>()?;
+                (n.len() == 4).then(|| format!("cubic-bezier({}, {}, {}, {})", n[0], n[1], n[2], n[3]))
+            })
+            .collect::<Option<_>>()?,
+        _ => Vec::new(),
+    };
+
+    let css_values: Vec<String> = match transform_type {
+        Some(kind) => {
+            let centered = kind == "rotate" && values.iter().any(|v| numbers(v).len() > 1);
+            values.iter().map(|v| {
+                let mut n = numbers(v);
+                if centered {
+                    n.resize(3, 0.0);
+                }
+                css_transform(&format!("{}({})", kind, n.iter().map(|x| x.to_string()).collect::<Vec<_>>().join(" ")))
+            }).collect::<Option<_>>()?
+        }
+        None if property == "transform" => values.iter().map(|v| css_transform(v)).collect::<Option<_>>()?,
+        None if LENGTH_PROPERTIES.contains(&property) => values.iter().map(|v| with_px(v)).collect(),
+        None => values.clone(),
+    };
+    let base = match (additive, base_transform) {
+        (true, Some(base)) => Some(css_transform(base)?),
+        _ => None,
+    };
+
+    let mut rule = format!("@keyframes {} {{", name);
+    for (index, (offset, value)) in offsets.iter().zip(&css_values).enumerate() {
+        let value = match &base {
+            Some(base) => format!("{} {}", base, value),
+            None => value.clone(),
+        };
+        let timing = match calc_mode {
+            "discrete" => " animation-timing-function: steps(1, end);".to_string(),
+            "spline" => splines.get(index).map(|s| format!(" animation-timing-function: {};", s)).unwrap_or_default(),
+            _ => String::new(),
+        };
+        rule.push_str(&format!("\n  {}% {{ {}: {};{} }}", round_value(offset * 100.0), property, value, timing));
+    }
+    rule.push_str("\n}");
+
+    let fill = if attr("fill") == Some("freeze") { " forwards" } else { "" };
+    let shorthand = format!(
+        "{} {} linear {} {}{}",
+        name,
+        format_clock(duration),
+        format_clock(delay),
+        iterations,
+        fill
+    );
+    Some((rule, shorthand))
+}
+
+fn numbers(value: &str) -> Vec<f64> {
+    value.split(|c: char| c.is_whitespace() || c == ',')
+        .filter_map(|p| p.parse().ok())
+        .collect()
+}
+
+fn with_px(value: &str) -> String {
+    split_top_level(value, ' ')
+        .iter()
+        .flat_map(|part| part.split(','))
+        .filter(|part| !part.is_empty())
+        .map(|part| match part.parse::<f64>() {
+            Ok(_) => format!("{}px", part),
+            Err(_) => part.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Convert an SVG transform list to CSS, adding units and expanding centred rotations
+fn css_transform(value: &str) -> Option<String> {
+    let mut functions = Vec::new();
+    let mut rest = value.trim();
+    while !rest.is_empty() {
+        let open = rest.find('(')?;
+        let close = rest.find(')')?;
+        let name = rest[..open].trim().trim_start_matches(',').trim();
+        let n = numbers(&rest[open + 1..close]);
+        let px = |v: f64| format!("{}px", v);
+        let css = match (name, n.len()) {
+            ("translate", 1) => format!("translate({})", px(n[0])),
+            ("translate", 2) => format!("translate({}, {})", px(n[0]), px(n[1])),
+            ("scale", 1) => format!("scale({})", n[0]),
+            ("scale", 2) => format!("scale({}, {})", n[0], n[1]),
+            ("rotate", 1) => format!("rotate({}deg)", n[0]),
+            ("rotate", 3) => format!(
+                "translate({}, {}) rotate({}deg) translate({}, {})",
+                px(n[1]), px(n[2]), n[0], px(-n[1]), px(-n[2])
+            ),
+            ("skewX", 1) => format!("skewX({}deg)", n[0]),
+            ("skewY", 1) => format!("skewY({}deg)", n[0]),
+            ("matrix", 6) => format!("matrix({}, {}, {}, {}, {}, {})", n[0], n[1], n[2], n[3], n[4], n[5]),
+            _ => return None,
+        };
+        functions.push(css);
+        rest = rest[close + 1..].trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+    }
+    (!functions.is_empty()).then(|| functions.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::animation::{Animation, Timing, TransformType};
+
+    #[test]
+    fn test_css_transform() {
+        assert_eq!(css_transform("rotate(45)").unwrap(), "rotate(45deg)");
+        assert_eq!(
+            css_transform("translate(10, 20) rotate(90 5 5)").unwrap(),
+            "translate(10px, 20px) translate(5px, 5px) rotate(90deg) translate(-5px, -5px)"
+        );
+        assert_eq!(with_px("5 10"), "5px 10px");
+        assert!(css_transform("perspective(3)").is_none());
+    }
+
+    #[test]
+    fn test_css_mode() {
+        let mut svg = Svg::new(100, 100);
+        svg.animation_mode(AnimationMode::Css);
+        svg.rect(10, 10)
+            .style("fill: red")
+            .animate_attr("opacity", "1", "0", 2)
+            .animation(Animation::transform(TransformType::Rotate).from("0 5 5").to("360 5 5").dur(1.5).repeat(3.0).freeze());
+        svg.circle(5)
+            .animation(Animation::animate("r").from("5").to("10").dur(1.0))
+            .animation(Animation::animate("stroke-dashoffset").from("100").to("0").dur(1.0).begin(Timing::on("click")));
+
+        let output = svg.to_string();
+        assert!(output.contains("@keyframes svg-anim-1 {\n  0% { opacity: 1; }\n  100% { opacity: 0; }\n}"));
+        assert!(output.contains("rotate(360deg)"));
+        assert!(output.contains("style=\"fill: red; animation: svg-anim-1 2s linear 0s infinite, svg-anim-2 1.5s linear 0s 3 forwards\""));
+        assert!(output.contains("prefers-reduced-motion"));
+        assert!(!output.contains("<animate attributeName=\"opacity\""));
+        // Geometry attributes and event timing stay SMIL
+        assert!(output.contains("attributeName=\"r\""));
+        assert!(output.contains("begin=\"click\""));
+    }
+
+    #[test]
+    fn test_both_mode_keeps_smil() {
+        let mut svg = Svg::new(100, 100);
+        svg.animation_mode(AnimationMode::Both).reduced_motion_guard(false);
+        svg.rect(10, 10).animate_attr("fill", "red", "blue", 1);
+
+        let output = svg.to_string();
+        assert!(output.contains("@keyframes"));
+        assert!(output.contains("<animate"));
+        assert!(!output.contains("prefers-reduced-motion"));
+        assert_eq!(output, svg.to_string());
+    }
+}
@@ -7,10 +7,10 @@
 //! - Complete SVG element support: rectangles, circles, ellipses, lines, paths, text, images, and more
 //! - Fluent API design: chainable methods similar to svg.js
 //! - Advanced graphics: gradients, patterns, masks, clipping paths
-//! - Animations: SVG animations with attribute interpolation
+//! - Animations: SMIL animations with keyframes, easing splines and event/syncbase timing
 //! - Text processing: advanced text handling with tspan and textPath
 //! - Transformations: rotate, scale, translate, skew, flip
-//! - CSS integration: class management and inline styles
+//! - CSS integration: class management, inline styles and stylesheet inlining
 //! - Zero dependencies: pure Rust implementation
 //! - Type safety: leverages Rust's type system for correctness
 //!
@@ -36,6 +36,13 @@
 //! ```
 
 pub mod svg;
+pub mod css;
+pub mod accessibility;
+pub mod metadata;
+pub mod animation;
+pub mod easing;
+pub mod keyframes;
+pub mod snapshot;
 
 #[cfg(feature = "draggable")]
 pub mod draggable;
@@ -50,6 +57,12 @@ pub mod math;
 pub mod path;
 
 pub use svg::*;
+pub use css::*;
+pub use accessibility::*;
+pub use metadata::*;
+pub use animation::*;
+pub use easing::*;
+pub use keyframes::*;
 
 #[cfg(feature = "draggable")]
 pub use draggable::*;
@@ -40,7 +40,7 @@ fn main() {
         .move_to(400, 250)
         .rotate(45.0);
     
-    println!("{}", canvas.to_string());
+    println!("{}", canvas);
     
     // Save to file
     canvas.save("example.svg").expect("Failed to save SVG file");
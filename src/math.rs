@@ -81,6 +81,37 @@ impl Point {
     pub fn angle_to(&self, other: &Point) -> f64 {
         Math::angle(self, other, None)
     }
+
+    /// Sum of the two points as vectors
+    pub fn add(&self, other: &Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+
+    /// Vector from `other` to this point
+    pub fn sub(&self, other: &Point) -> Point {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+
+    /// Dot product with another vector
+    pub fn dot(&self, other: &Point) -> f64 {
+        self.x * other.x + self.y * other.y
+    }
+
+    /// Cross product with another vector: positive when `other` turns
+    /// clockwise from this one on screen (y down)
+    pub fn cross(&self, other: &Point) -> f64 {
+        self.x * other.y - self.y * other.x
+    }
+
+    /// This vector multiplied by `factor`
+    pub fn scale(&self, factor: f64) -> Point {
+        Point::new(self.x * factor, self.y * factor)
+    }
+
+    /// Vector of length 1 in the same direction
+    pub fn normalized(&self) -> Point {
+        self.scale(1.0 / self.dot(self).sqrt())
+    }
 }
 
 /// Line structure for geometric calculations
@@ -205,6 +236,259 @@ pub enum LineIntersection {
     Parallel,
 }
 
+/// Axis-aligned rectangle
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Rect {
+    pub fn new(x: f64, y: f64, width: f64, height: f64) -> Self {
+        Self { x, y, width, height }
+    }
+
+    /// Smallest rectangle containing all points, `None` for no points
+    pub fn from_points(points: &[Point]) -> Option<Self> {
+        let first = points.first()?;
+        let (mut min, mut max) = (*first, *first);
+        for p in &points[1..] {
+            min = Point::new(min.x.min(p.x), min.y.min(p.y));
+            max = Point::new(max.x.max(p.x), max.y.max(p.y));
+        }
+        Some(Self::new(min.x, min.y, max.x - min.x, max.y - min.y))
+    }
+
+    pub fn right(&self) -> f64 {
+        self.x + self.width
+    }
+
+    pub fn bottom(&self) -> f64 {
+        self.y + self.height
+    }
+
+    pub fn center(&self) -> Point {
+        Point::new(self.x + self.width / 2.0, self.y + self.height / 2.0)
+    }
+
+    /// Whether the point lies inside or on the edge
+    pub fn contains(&self, p: &Point) -> bool {
+        p.x >= self.x && p.x <= self.right() && p.y >= self.y && p.y <= self.bottom()
+    }
+
+    /// Smallest rectangle containing both
+    pub fn union(&self, other: &Rect) -> Rect {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        Rect::new(x, y, self.right().max(other.right()) - x, self.bottom().max(other.bottom()) - y)
+    }
+}
+
+/// 2D affine transform in SVG `matrix(a, b, c, d, e, f)` form, mapping
+/// (x, y) to (a·x + c·y + e, b·x + d·y + f)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Matrix {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+    pub e: f64,
+    pub f: f64,
+}
+
+impl Default for Matrix {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Matrix {
+    pub fn new(a: f64, b: f64, c: f64, d: f64, e: f64, f: f64) -> Self {
+        Self { a, b, c, d, e, f }
+    }
+
+    pub fn identity() -> Self {
+        Self::new(1.0, 0.0, 0.0, 1.0, 0.0, 0.0)
+    }
+
+    pub fn translate(x: f64, y: f64) -> Self {
+        Self::new(1.0, 0.0, 0.0, 1.0, x, y)
+    }
+
+    pub fn scale(x: f64, y: f64) -> Self {
+        Self::new(x, 0.0, 0.0, y, 0.0, 0.0)
+    }
+
+    /// Rotation by `angle` radians (clockwise on screen, as SVG's `rotate`)
+    pub fn rotate(angle: f64) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Self::new(cos, sin, -sin, cos, 0.0, 0.0)
+    }
+
+    /// Skew along x by `angle` radians
+    pub fn skew_x(angle: f64) -> Self {
+        Self::new(1.0, 0.0, angle.tan(), 1.0, 0.0, 0.0)
+    }
+
+    /// Skew along y by `angle` radians
+    pub fn skew_y(angle: f64) -> Self {
+        Self::new(1.0, angle.tan(), 0.0, 1.0, 0.0, 0.0)
+    }
+
+    /// `self × other`: applies `other` first, like `transform="self other"`
+    pub fn multiply(&self, other: &Matrix) -> Matrix {
+        Matrix::new(
+            self.a * other.a + self.c * other.b,
+            self.b * other.a + self.d * other.b,
+            self.a * other.c + self.c * other.d,
+            self.b * other.c + self.d * other.d,
+            self.a * other.e + self.c * other.f + self.e,
+            self.b * other.e + self.d * other.f + self.f,
+        )
+    }
+
+    pub fn determinant(&self) -> f64 {
+        self.a * self.d - self.b * self.c
+    }
+
+    /// Inverse transform, `None` if the matrix is singular
+    pub fn inverse(&self) -> Option<Matrix> {
+        let det = self.determinant();
+        if det.abs() < f64::EPSILON {
+            return None;
+        }
+        Some(Matrix::new(
+            self.d / det,
+            -self.b / det,
+            -self.c / det,
+            self.a / det,
+            (self.c * self.f - self.d * self.e) / det,
+            (self.b * self.e - self.a * self.f) / det,
+        ))
+    }
+
+    /// Transform a point
+    pub fn apply(&self, p: &Point) -> Point {
+        Point::new(self.a * p.x + self.c * p.y + self.e, self.b * p.x + self.d * p.y + self.f)
+    }
+
+    /// Transform a direction or offset, ignoring the translation
+    pub fn apply_vector(&self, v: &Point) -> Point {
+        Point::new(self.a * v.x + self.c * v.y, self.b * v.x + self.d * v.y)
+    }
+}
+
+/// An elliptical arc as written in SVG path data
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EndpointArc {
+    pub from: Point,
+    pub rx: f64,
+    pub ry: f64,
+    /// Rotation of the ellipse's x axis in degrees
+    pub x_axis_rotation: f64,
+    pub large_arc: bool,
+    pub sweep: bool,
+    pub to: Point,
+}
+
+/// An elliptical arc by center, radii and angles (all in radians)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CenterArc {
+    pub center: Point,
+    pub rx: f64,
+    pub ry: f64,
+    /// Rotation of the ellipse's x axis
+    pub rotation: f64,
+    /// Parametric angle of the start point
+    pub start_angle: f64,
+    /// Parametric angle swept, positive towards increasing angles
+    pub sweep_angle: f64,
+}
+
+impl EndpointArc {
+    /// Center parameterization following the SVG implementation notes
+    ///
+    /// Negative radii are made positive and radii too small to span the end
+    /// points are scaled up. Returns `None` when the arc is drawn as a straight
+    /// line (a zero radius) or not at all (equal end points).
+    pub fn to_center(&self) -> Option<CenterArc> {
+        let (mut rx, mut ry) = (self.rx.abs(), self.ry.abs());
+        if rx == 0.0 || ry == 0.0 || self.from == self.to {
+            return None;
+        }
+        let rotation = Math::rad(self.x_axis_rotation);
+        let (cos, sin) = (rotation.cos(), rotation.sin());
+        let dx = (self.from.x - self.to.x) / 2.0;
+        let dy = (self.from.y - self.to.y) / 2.0;
+        let x1 = cos * dx + sin * dy;
+        let y1 = -sin * dx + cos * dy;
+
+        // Scale radii up if they cannot span the endpoints
+        let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+        if lambda > 1.0 {
+            rx *= lambda.sqrt();
+            ry *= lambda.sqrt();
+        }
+        let numerator = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+        let denominator = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+        let mut factor = (numerator / denominator).max(0.0).sqrt();
+        if self.large_arc == self.sweep {
+            factor = -factor;
+        }
+        let cx1 = factor * rx * y1 / ry;
+        let cy1 = -factor * ry * x1 / rx;
+        let center = Point::new(
+            cos * cx1 - sin * cy1 + (self.from.x + self.to.x) / 2.0,
+            sin * cx1 + cos * cy1 + (self.from.y + self.to.y) / 2.0,
+        );
+
+        let angle = |ux: f64, uy: f64, vx: f64, vy: f64| {
+            let sign = if ux * vy - uy * vx < 0.0 { -1.0 } else { 1.0 };
+            let dot = (ux * vx + uy * vy) / (ux.hypot(uy) * vx.hypot(vy));
+            sign * dot.clamp(-1.0, 1.0).acos()
+        };
+        let start_angle = angle(1.0, 0.0, (x1 - cx1) / rx, (y1 - cy1) / ry);
+        let mut sweep_angle = angle((x1 - cx1) / rx, (y1 - cy1) / ry, (-x1 - cx1) / rx, (-y1 - cy1) / ry);
+        if !self.sweep && sweep_angle > 0.0 {
+            sweep_angle -= 2.0 * PI;
+        } else if self.sweep && sweep_angle < 0.0 {
+            sweep_angle += 2.0 * PI;
+        }
+        Some(CenterArc { center, rx, ry, rotation, start_angle, sweep_angle })
+    }
+}
+
+impl CenterArc {
+    /// Point at parametric angle `theta`
+    pub fn point(&self, theta: f64) -> Point {
+        let (cos, sin) = (self.rotation.cos(), self.rotation.sin());
+        let (x, y) = (self.rx * theta.cos(), self.ry * theta.sin());
+        Point::new(self.center.x + cos * x - sin * y, self.center.y + sin * x + cos * y)
+    }
+
+    /// Derivative with respect to `theta`
+    pub fn derivative(&self, theta: f64) -> Point {
+        let (cos, sin) = (self.rotation.cos(), self.rotation.sin());
+        let (x, y) = (-self.rx * theta.sin(), self.ry * theta.cos());
+        Point::new(cos * x - sin * y, sin * x + cos * y)
+    }
+
+    /// The same arc in SVG endpoint parameterization
+    pub fn to_endpoint(&self) -> EndpointArc {
+        EndpointArc {
+            from: self.point(self.start_angle),
+            rx: self.rx,
+            ry: self.ry,
+            x_axis_rotation: Math::deg(self.rotation),
+            large_arc: self.sweep_angle.abs() > PI,
+            sweep: self.sweep_angle > 0.0,
+            to: self.point(self.start_angle + self.sweep_angle),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -224,10 +508,67 @@ mod tests {
         assert_eq!(Math::lerp(0.0, 10.0, 1.0), 10.0);
     }
 
+    #[test]
+    fn test_point_vectors() {
+        let (a, b) = (Point::new(3.0, 4.0), Point::new(1.0, 2.0));
+        assert_eq!(a.add(&b), Point::new(4.0, 6.0));
+        assert_eq!(a.sub(&b), Point::new(2.0, 2.0));
+        assert_eq!(a.dot(&b), 11.0);
+        assert_eq!(a.cross(&b), 2.0);
+        assert_eq!(a.scale(2.0), Point::new(6.0, 8.0));
+        assert!(a.normalized().distance_to(&Point::new(0.6, 0.8)) < 1e-12);
+    }
+
     #[test]
     fn test_line_midpoint() {
         let line = Line::new(Point::new(0.0, 0.0), Point::new(10.0, 10.0));
         let mid = line.midpoint();
         assert_eq!(mid, Point::new(5.0, 5.0));
     }
+
+    #[test]
+    fn test_rect() {
+        let rect = Rect::from_points(&[Point::new(5.0, 1.0), Point::new(-1.0, 4.0), Point::new(2.0, 2.0)]).unwrap();
+        assert_eq!(rect, Rect::new(-1.0, 1.0, 6.0, 3.0));
+        assert!(rect.contains(&Point::new(5.0, 4.0)));
+        assert_eq!(rect.union(&Rect::new(0.0, 0.0, 10.0, 1.0)), Rect::new(-1.0, 0.0, 11.0, 4.0));
+        assert!(Rect::from_points(&[]).is_none());
+    }
+
+    #[test]
+    fn test_arc_parameterization() {
+        let arc = EndpointArc {
+            from: Point::new(0.0, 0.0),
+            rx: 20.0,
+            ry: 10.0,
+            x_axis_rotation: 30.0,
+            large_arc: true,
+            sweep: false,
+            to: Point::new(15.0, 5.0),
+        };
+        let center = arc.to_center().unwrap();
+        assert!(center.sweep_angle < -PI);
+        let back = center.to_endpoint();
+        assert!(back.from.distance_to(&arc.from) < 1e-9 && back.to.distance_to(&arc.to) < 1e-9);
+        assert_eq!((back.large_arc, back.sweep), (true, false));
+        assert!((back.x_axis_rotation - 30.0).abs() < 1e-9);
+
+        // Radii too small to reach are scaled up to a half ellipse
+        let small = EndpointArc { rx: 1.0, ry: 1.0, x_axis_rotation: 0.0, to: Point::new(20.0, 0.0), ..arc };
+        let center = small.to_center().unwrap();
+        assert!((center.rx - 10.0).abs() < 1e-9 && (center.center.x - 10.0).abs() < 1e-9);
+        assert!((center.sweep_angle.abs() - PI).abs() < 1e-9);
+        assert!(EndpointArc { rx: 0.0, ..small }.to_center().is_none());
+    }
+
+    #[test]
+    fn test_matrix() {
+        let m = Matrix::translate(10.0, 0.0).multiply(&Matrix::rotate(PI / 2.0));
+        let p = m.apply(&Point::new(1.0, 0.0));
+        assert!((p.x - 10.0).abs() < 1e-9 && (p.y - 1.0).abs() < 1e-9);
+        let back = m.inverse().unwrap().apply(&p);
+        assert!((back.x - 1.0).abs() < 1e-9 && back.y.abs() < 1e-9);
+        assert_eq!(Matrix::scale(2.0, 3.0).apply_vector(&Point::new(1.0, 1.0)), Point::new(2.0, 3.0));
+        assert!(Matrix::scale(0.0, 1.0).inverse().is_none());
+    }
 }
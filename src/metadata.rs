@@ -0,0 +1,468 @@
+//! Document metadata for svg-rs
+//!
+//! A `Metadata` block is serialized as RDF with Dublin Core and Creative
+//! Commons terms (the layout used by Inkscape and Wikimedia), and can be read
+//! back from SVG text with `Metadata::from_svg`.
+
+use std::collections::HashMap;
+
+use crate::svg::{escape_attr, escape_text, Svg};
+
+pub const RDF_NAMESPACE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#";
+pub const DC_NAMESPACE: &str = "http://purl.org/dc/elements/1.1/";
+pub const CC_NAMESPACE: &str = "http://creativecommons.org/ns#";
+
+/// A content license, with the Creative Commons licenses built in
+#[derive(Debug, Clone, PartialEq)]
+pub enum License {
+    /// CC0 public domain dedication
+    Cc0,
+    CcBy,
+    CcBySa,
+    CcByNd,
+    CcByNc,
+    CcByNcSa,
+    CcByNcNd,
+    /// Any other license, identified by URL
+    Other(String),
+}
+
+impl License {
+    /// The license URL (Creative Commons licenses use version 4.0)
+    pub fn url(&self) -> String {
+        match self {
+            License::Cc0 => "http://creativecommons.org/publicdomain/zero/1.0/".to_string(),
+            License::Other(url) => url.clone(),
+            cc => format!("http://creativecommons.org/licenses/{}/4.0/", cc.code()),
+        }
+    }
+
+    /// Recognize a license from its URL
+    pub fn from_url(url: &str) -> License {
+        let normalized = url.trim().trim_start_matches("https://").trim_start_matches("http://");
+        if normalized.starts_with("creativecommons.org/publicdomain/zero/") {
+            return License::Cc0;
+        }
+        if let Some(rest) = normalized.strip_prefix("creativecommons.org/licenses/") {
+            let code = rest.split('/').next().unwrap_or_default();
+            let known = [
+                License::CcBy, License::CcBySa, License::CcByNd,
+                License::CcByNc, License::CcByNcSa, License::CcByNcNd,
+            ];
+            if let Some(license) = known.into_iter().find(|l| l.code() == code) {
+                return license;
+            }
+        }
+        License::Other(url.trim().to_string())
+    }
+
+    fn code(&self) -> &'static str {
+        match self {
+            License::Cc0 => "zero",
+            License::CcBy => "by",
+            License::CcBySa => "by-sa",
+            License::CcByNd => "by-nd",
+            License::CcByNc => "by-nc",
+            License::CcByNcSa => "by-nc-sa",
+            License::CcByNcNd => "by-nc-nd",
+            License::Other(_) => "",
+        }
+    }
+
+    /// Creative Commons `permits`, `requires` and `prohibits` terms
+    fn terms(&self) -> (Vec<&'static str>, Vec<&'static str>, Vec<&'static str>) {
+        if let License::Other(_) = self {
+            return (Vec::new(), Vec::new(), Vec::new());
+        }
+        let code = self.code();
+        let mut permits = vec!["Reproduction", "Distribution"];
+        let mut requires = Vec::new();
+        let mut prohibits = Vec::new();
+        if !code.contains("nd") {
+            permits.push("DerivativeWorks");
+        }
+        if code != "zero" {
+            requires.extend(["Notice", "Attribution"]);
+        }
+        if code.contains("sa") {
+            requires.push("ShareAlike");
+        }
+        if code.contains("nc") {
+            prohibits.push("CommercialUse");
+        }
+        (permits, requires, prohibits)
+    }
+}
+
+/// Dublin Core and Creative Commons metadata for a document
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Metadata {
+    pub title: Option<String>,
+    pub creators: Vec<String>,
+    pub contributors: Vec<String>,
+    pub publisher: Option<String>,
+    pub description: Option<String>,
+    pub subjects: Vec<String>,
+    pub date: Option<String>,
+    pub rights: Option<String>,
+    pub language: Option<String>,
+    pub identifier: Option<String>,
+    pub source: Option<String>,
+    pub license: Option<License>,
+    pub attribution_name: Option<String>,
+    pub attribution_url: Option<String>,
+}
+
+impl Metadata {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn title(&mut self, title: &str) -> &mut Self {
+        self.title = Some(title.to_string());
+        self
+    }
+
+    /// Add an author
+    pub fn creator(&mut self, name: &str) -> &mut Self {
+        self.creators.push(name.to_string());
+        self
+    }
+
+    pub fn contributor(&mut self, name: &str) -> &mut Self {
+        self.contributors.push(name.to_string());
+        self
+    }
+
+    pub fn publisher(&mut self, name: &str) -> &mut Self {
+        self.publisher = Some(name.to_string());
+        self
+    }
+
+    pub fn description(&mut self, description: &str) -> &mut Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    /// Add a keyword
+    pub fn subject(&mut self, keyword: &str) -> &mut Self {
+        self.subjects.push(keyword.to_string());
+        self
+    }
+
+    /// Set the date, preferably in ISO 8601 form
+    pub fn date(&mut self, date: &str) -> &mut Self {
+        self.date = Some(date.to_string());
+        self
+    }
+
+    /// Set the rights holder or copyright statement
+    pub fn rights(&mut self, rights: &str) -> &mut Self {
+        self.rights = Some(rights.to_string());
+        self
+    }
+
+    pub fn language(&mut self, language: &str) -> &mut Self {
+        self.language = Some(language.to_string());
+        self
+    }
+
+    pub fn identifier(&mut self, identifier: &str) -> &mut Self {
+        self.identifier = Some(identifier.to_string());
+        self
+    }
+
+    /// Set the URL of the work this graphic is derived from
+    pub fn source(&mut self, source: &str) -> &mut Self {
+        self.source = Some(source.to_string());
+        self
+    }
+
+    pub fn license(&mut self, license: License) -> &mut Self {
+        self.license = Some(license);
+        self
+    }
+
+    /// Set the Creative Commons attribution name and URL
+    pub fn attribution(&mut self, name: &str, url: &str) -> &mut Self {
+        self.attribution_name = Some(name.to_string());
+        self.attribution_url = Some(url.to_string()).filter(|u| !u.is_empty());
+        self
+    }
+
+    /// Serialize as a `<metadata>` element
+    pub fn to_markup(&self) -> String {
+        let mut work = String::new();
+        let text = |work: &mut String, tag: &str, value: &str| {
+            work.push_str(&format!("<{0}>{1}</{0}>", tag, escape_text(value)));
+        };
+        let agent = |work: &mut String, tag: &str, name: &str| {
+            work.push_str(&format!(
+                "<{0}><cc:Agent><dc:title>{1}</dc:title></cc:Agent></{0}>", tag, escape_text(name)
+            ));
+        };
+
+        text(&mut work, "dc:format", "image/svg+xml");
+        work.push_str(r#"<dc:type rdf:resource="http://purl.org/dc/dcmitype/StillImage" />"#);
+        if let Some(title) = &self.title {
+            text(&mut work, "dc:title", title);
+        }
+        for creator in &self.creators {
+            agent(&mut work, "dc:creator", creator);
+        }
+        for contributor in &self.contributors {
+            agent(&mut work, "dc:contributor", contributor);
+        }
+        if let Some(publisher) = &self.publisher {
+            agent(&mut work, "dc:publisher", publisher);
+        }
+        if let Some(rights) = &self.rights {
+            agent(&mut work, "dc:rights", rights);
+        }
+        if let Some(description) = &self.description {
+            text(&mut work, "dc:description", description);
+        }
+        if !self.subjects.is_empty() {
+            work.push_str("<dc:subject><rdf:Bag>");
+            for subject in &self.subjects {
+                text(&mut work, "rdf:li", subject);
+            }
+            work.push_str("</rdf:Bag></dc:subject>");
+        }
+        for (tag, value) in [
+            ("dc:date", &self.date),
+            ("dc:language", &self.language),
+            ("dc:identifier", &self.identifier),
+            ("dc:source", &self.source),
+            ("cc:attributionName", &self.attribution_name),
+        ] {
+            if let Some(value) = value {
+                text(&mut work, tag, value);
+            }
+        }
+        if let Some(url) = &self.attribution_url {
+            work.push_str(&format!(r#"<cc:attributionURL rdf:resource="{}" />"#, escape_attr(url)));
+        }
+
+        let mut license_block = String::new();
+        if let Some(license) = &self.license {
+            let url = escape_attr(&license.url());
+            work.push_str(&format!(r#"<cc:license rdf:resource="{}" />"#, url));
+            let (permits, requires, prohibits) = license.terms();
+            if !permits.is_empty() {
+                license_block.push_str(&format!(r#"<cc:License rdf:about="{}">"#, url));
+                for (kind, terms) in [("permits", permits), ("requires", requires), ("prohibits", prohibits)] {
+                    for term in terms {
+                        license_block.push_str(&format!(r#"<cc:{} rdf:resource="{}{}" />"#, kind, CC_NAMESPACE, term));
+                    }
+                }
+                license_block.push_str("</cc:License>");
+            }
+        }
+
+        format!(
+            r#"<metadata><rdf:RDF><cc:Work rdf:about="">{}</cc:Work>{}</rdf:RDF></metadata>"#,
+            work, license_block
+        )
+    }
+
+    /// Read metadata back from SVG text, if it contains an RDF block
+    pub fn from_svg(svg: &str) -> Option<Metadata> {
+        let start = svg.find("<rdf:RDF")?;
+        let end = svg[start..].find("</rdf:RDF>")? + start + "</rdf:RDF>".len();
+        let rdf = XmlNode::parse(&svg[start..end])?;
+        let work = rdf.children.iter().find(|c| c.name == "cc:Work" || c.name == "rdf:Description")?;
+
+        let mut metadata = Metadata::default();
+        for child in &work.children {
+            let value = child.text_or_agent();
+            match child.name.as_str() {
+                "dc:title" => metadata.title = Some(value),
+                "dc:creator" => metadata.creators.push(value),
+                "dc:contributor" => metadata.contributors.push(value),
+                "dc:publisher" => metadata.publisher = Some(value),
+                "dc:rights" => metadata.rights = Some(value),
+                "dc:description" => metadata.description = Some(value),
+                "dc:subject" => match child.find("rdf:Bag") {
+                    Some(bag) => metadata.subjects.extend(bag.children.iter().map(|li| li.text.clone())),
+                    None => metadata.subjects.push(value),
+                },
+                "dc:date" => metadata.date = Some(value),
+                "dc:language" => metadata.language = Some(value),
+                "dc:identifier" => metadata.identifier = Some(value),
+                "dc:source" => metadata.source = Some(value),
+                "cc:attributionName" => metadata.attribution_name = Some(value),
+                "cc:attributionURL" => metadata.attribution_url = child.attributes.get("rdf:resource").cloned().or(Some(value)),
+                "cc:license" => {
+                    let url = child.attributes.get("rdf:resource").cloned().unwrap_or(value);
+                    metadata.license = Some(License::from_url(&url));
+                }
+                _ => {}
+            }
+        }
+        Some(metadata)
+    }
+}
+
+impl Svg {
+    /// Access the document metadata, creating an empty block if needed
+    ///
+    /// The block is emitted after the document title and description, and
+    /// the RDF, Dublin Core and Creative Commons namespaces are declared on
+    /// the root element.
+    pub fn metadata(&mut self) -> &mut Metadata {
+        self.metadata.get_or_insert_with(Metadata::default)
+    }
+
+    /// Get the document metadata, if any has been set
+    pub fn get_metadata(&self) -> Option<&Metadata> {
+        self.metadata.as_ref()
+    }
+}
+
+/// A minimal XML element tree, enough to read back RDF metadata
+#[derive(Debug, Default)]
+struct XmlNode {
+    name: String,
+    attributes: HashMap<String, String>,
+    children: Vec<XmlNode>,
+    text: String,
+}
+
+impl XmlNode {
+    fn parse(xml: &str) -> Option<XmlNode> {
+        let mut stack: Vec<XmlNode> = Vec::new();
+        let mut rest = xml;
+
+        while let Some(open) = rest.find('<') {
+            if let Some(node) = stack.last_mut() {
+                node.text.push_str(&unescape(&rest[..open]));
+            }
+            rest = &rest[open..];
+            let close = rest.find('>')?;
+            let tag = &rest[1..close];
+            rest = &rest[close + 1..];
+
+            if tag.starts_with('?') || tag.starts_with('!') {
+                continue;
+            }
+            if let Some(name) = tag.strip_prefix('/') {
+                let node = stack.pop()?;
+                if node.name != name.trim() {
+                    return None;
+                }
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(node),
+                    None => return Some(node),
+                }
+                continue;
+            }
+
+            let self_closing = tag.ends_with('/');
+            let tag = tag.trim_end_matches('/');
+            let (name, attrs) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+            let node = XmlNode {
+                name: name.to_string(),
+                attributes: parse_attributes(attrs),
+                ..Default::default()
+            };
+            if self_closing {
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(node),
+                    None => return Some(node),
+                }
+            } else {
+                stack.push(node);
+            }
+        }
+        None
+    }
+
+    fn find(&self, name: &str) -> Option<&XmlNode> {
+        self.children.iter().find(|c| c.name == name)
+    }
+
+    /// The element text, or the title of a nested `cc:Agent`
+    fn text_or_agent(&self) -> String {
+        self.find("cc:Agent")
+            .and_then(|agent| agent.find("dc:title"))
+            .map(|title| title.text.trim().to_string())
+            .unwrap_or_else(|| self.text.trim().to_string())
+    }
+}
+
+fn parse_attributes(text: &str) -> HashMap<String, String> {
+    let mut attributes = HashMap::new();
+    let mut rest = text.trim();
+    while let Some(eq) = rest.find('=') {
+        let name = rest[..eq].trim().to_string();
+        let value = rest[eq + 1..].trim_start();
+        let Some(quote) = value.chars().next().filter(|c| *c == '"' || *c == '\'') else { break };
+        let Some(end) = value[1..].find(quote) else { break };
+        attributes.insert(name, unescape(&value[1..end + 1]));
+        rest = value[end + 2..].trim_start();
+    }
+    attributes
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_license_urls() {
+        assert_eq!(License::CcBySa.url(), "http://creativecommons.org/licenses/by-sa/4.0/");
+        assert_eq!(License::from_url("https://creativecommons.org/licenses/by-nc-nd/3.0/"), License::CcByNcNd);
+        assert_eq!(License::from_url("http://creativecommons.org/publicdomain/zero/1.0/"), License::Cc0);
+        assert_eq!(License::from_url("https://opensource.org/licenses/MIT"),
+            License::Other("https://opensource.org/licenses/MIT".to_string()));
+    }
+
+    #[test]
+    fn test_metadata_output() {
+        let mut svg = Svg::new(100, 100);
+        svg.rect(10, 10);
+        svg.title("Chart");
+        svg.metadata()
+            .title("Revenue & costs")
+            .creator("Ada Lovelace")
+            .license(License::CcByNc);
+
+        let output = svg.to_string();
+        assert!(output.contains(&format!("xmlns:rdf=\"{}\"", RDF_NAMESPACE)));
+        assert!(output.contains(&format!("xmlns:dc=\"{}\"", DC_NAMESPACE)));
+        assert!(output.contains(&format!("xmlns:cc=\"{}\"", CC_NAMESPACE)));
+        assert!(output.find("<title").unwrap() < output.find("<metadata>").unwrap());
+        assert!(output.find("<metadata>").unwrap() < output.find("<rect").unwrap());
+        assert!(output.contains("<dc:title>Revenue &amp; costs</dc:title>"));
+        assert!(output.contains("cc:prohibits rdf:resource=\"http://creativecommons.org/ns#CommercialUse\""));
+    }
+
+    #[test]
+    fn test_metadata_round_trip() {
+        let mut svg = Svg::new(100, 100);
+        svg.metadata()
+            .title("Map <v2>")
+            .creator("Ada Lovelace")
+            .creator("Charles Babbage")
+            .subject("maps")
+            .subject("transit")
+            .date("2024-05-01")
+            .rights("City of Example")
+            .source("https://example.com/data")
+            .license(License::CcBySa)
+            .attribution("Example Maps", "https://example.com");
+
+        let parsed = Metadata::from_svg(&svg.to_string()).unwrap();
+        assert_eq!(Some(&parsed), svg.get_metadata());
+        assert!(Metadata::from_svg("<svg></svg>").is_none());
+    }
+}
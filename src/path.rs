@@ -1,13 +1,140 @@
+use crate::animation::{round_value, Animation, Timing};
+use crate::easing::Easing;
+use crate::math::Matrix;
 use crate::svg::Element;
 
+mod arcs;
+mod boolean;
+mod bounds;
+mod edit;
+mod fit;
+mod flatten;
+mod geometry;
+mod measure;
+mod morph;
+mod normalize;
+mod parse;
+mod shape;
+mod spline;
+mod stroke;
+mod transform;
+
+pub use arcs::*;
+pub use boolean::*;
+pub use bounds::*;
+pub use edit::*;
+pub use fit::*;
+pub use flatten::*;
+pub use measure::*;
+pub use morph::*;
+pub use normalize::*;
+pub use parse::*;
+pub use spline::*;
+pub use stroke::*;
+pub use transform::*;
+
+pub(crate) use geometry::{resolve, Curve};
+
 /// Path segment types for SVG path construction
-#[derive(Debug, Clone)]
-pub struct PathSegment {
-    pub command: String,
-    pub coords: Vec<f64>,
+///
+/// Every variant carries `abs`: `true` for the uppercase (absolute) command,
+/// `false` for the lowercase (relative) one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PathSegment {
+    MoveTo { abs: bool, x: f64, y: f64 },
+    LineTo { abs: bool, x: f64, y: f64 },
+    Horizontal { abs: bool, x: f64 },
+    Vertical { abs: bool, y: f64 },
+    CubicTo { abs: bool, x1: f64, y1: f64, x2: f64, y2: f64, x: f64, y: f64 },
+    SmoothCubicTo { abs: bool, x2: f64, y2: f64, x: f64, y: f64 },
+    QuadTo { abs: bool, x1: f64, y1: f64, x: f64, y: f64 },
+    SmoothQuadTo { abs: bool, x: f64, y: f64 },
+    ArcTo { abs: bool, rx: f64, ry: f64, x_axis_rotation: f64, large_arc: bool, sweep: bool, x: f64, y: f64 },
+    Close { abs: bool },
+}
+
+impl PathSegment {
+    /// Whether the segment uses absolute coordinates
+    pub fn is_absolute(&self) -> bool {
+        match *self {
+            PathSegment::MoveTo { abs, .. }
+            | PathSegment::LineTo { abs, .. }
+            | PathSegment::Horizontal { abs, .. }
+            | PathSegment::Vertical { abs, .. }
+            | PathSegment::CubicTo { abs, .. }
+            | PathSegment::SmoothCubicTo { abs, .. }
+            | PathSegment::QuadTo { abs, .. }
+            | PathSegment::SmoothQuadTo { abs, .. }
+            | PathSegment::ArcTo { abs, .. }
+            | PathSegment::Close { abs } => abs,
+        }
+    }
+
+    /// The path data command letter
+    pub fn command(&self) -> char {
+        let command = match self {
+            PathSegment::MoveTo { .. } => 'M',
+            PathSegment::LineTo { .. } => 'L',
+            PathSegment::Horizontal { .. } => 'H',
+            PathSegment::Vertical { .. } => 'V',
+            PathSegment::CubicTo { .. } => 'C',
+            PathSegment::SmoothCubicTo { .. } => 'S',
+            PathSegment::QuadTo { .. } => 'Q',
+            PathSegment::SmoothQuadTo { .. } => 'T',
+            PathSegment::ArcTo { .. } => 'A',
+            PathSegment::Close { .. } => 'Z',
+        };
+        if self.is_absolute() { command } else { command.to_ascii_lowercase() }
+    }
+
+    /// The numbers following the command letter in path data; arc flags are 0 or 1
+    pub fn coords(&self) -> Vec<f64> {
+        match *self {
+            PathSegment::MoveTo { x, y, .. } | PathSegment::LineTo { x, y, .. } | PathSegment::SmoothQuadTo { x, y, .. } => vec![x, y],
+            PathSegment::Horizontal { x, .. } => vec![x],
+            PathSegment::Vertical { y, .. } => vec![y],
+            PathSegment::CubicTo { x1, y1, x2, y2, x, y, .. } => vec![x1, y1, x2, y2, x, y],
+            PathSegment::SmoothCubicTo { x2, y2, x, y, .. } => vec![x2, y2, x, y],
+            PathSegment::QuadTo { x1, y1, x, y, .. } => vec![x1, y1, x, y],
+            PathSegment::ArcTo { rx, ry, x_axis_rotation, large_arc, sweep, x, y, .. } => {
+                let flag = |f: bool| if f { 1.0 } else { 0.0 };
+                vec![rx, ry, x_axis_rotation, flag(large_arc), flag(sweep), x, y]
+            }
+            PathSegment::Close { .. } => vec![],
+        }
+    }
+
+    /// Build a segment from a command letter and exactly its arguments
+    pub(crate) fn from_command(command: char, args: &[f64]) -> Option<Self> {
+        let abs = command.is_ascii_uppercase();
+        let segment = match (command.to_ascii_uppercase(), args) {
+            ('M', &[x, y]) => PathSegment::MoveTo { abs, x, y },
+            ('L', &[x, y]) => PathSegment::LineTo { abs, x, y },
+            ('H', &[x]) => PathSegment::Horizontal { abs, x },
+            ('V', &[y]) => PathSegment::Vertical { abs, y },
+            ('C', &[x1, y1, x2, y2, x, y]) => PathSegment::CubicTo { abs, x1, y1, x2, y2, x, y },
+            ('S', &[x2, y2, x, y]) => PathSegment::SmoothCubicTo { abs, x2, y2, x, y },
+            ('Q', &[x1, y1, x, y]) => PathSegment::QuadTo { abs, x1, y1, x, y },
+            ('T', &[x, y]) => PathSegment::SmoothQuadTo { abs, x, y },
+            ('A', &[rx, ry, x_axis_rotation, large_arc, sweep, x, y]) => PathSegment::ArcTo {
+                abs,
+                rx,
+                ry,
+                x_axis_rotation,
+                large_arc: large_arc != 0.0,
+                sweep: sweep != 0.0,
+                x,
+                y,
+            },
+            ('Z', &[]) => PathSegment::Close { abs },
+            _ => return None,
+        };
+        Some(segment)
+    }
 }
 
 /// Enhanced path builder with fluent API
+#[allow(non_snake_case)]
 pub trait PathBuilder {
     /// Move to absolute position
     fn M(&mut self, x: f64, y: f64) -> &mut Self;
@@ -42,8 +169,10 @@ pub trait PathBuilder {
     /// Smooth quadratic Bezier relative
     fn t(&mut self, dx: f64, dy: f64) -> &mut Self;
     /// Arc absolute
+    #[allow(clippy::too_many_arguments)]
     fn A(&mut self, rx: f64, ry: f64, x_axis_rotation: f64, large_arc_flag: i32, sweep_flag: i32, x: f64, y: f64) -> &mut Self;
     /// Arc relative
+    #[allow(clippy::too_many_arguments)]
     fn a(&mut self, rx: f64, ry: f64, x_axis_rotation: f64, large_arc_flag: i32, sweep_flag: i32, dx: f64, dy: f64) -> &mut Self;
     /// Close path
     fn Z(&mut self) -> &mut Self;
@@ -55,83 +184,103 @@ pub trait PathBuilder {
     fn remove_segment(&mut self, index: usize) -> &mut Self;
     fn replace_segment(&mut self, index: usize, segment: PathSegment) -> &mut Self;
     fn redraw(&mut self) -> &mut Self;
+    /// Apply an affine matrix to every segment
+    fn transform_path(&mut self, matrix: &Matrix) -> &mut Self;
 }
 
 impl PathBuilder for Element {
     fn M(&mut self, x: f64, y: f64) -> &mut Self {
-        self.add_segment("M", vec![x, y])
+        self.add_segment(PathSegment::MoveTo { abs: true, x, y })
     }
 
     fn m(&mut self, dx: f64, dy: f64) -> &mut Self {
-        self.add_segment("m", vec![dx, dy])
+        self.add_segment(PathSegment::MoveTo { abs: false, x: dx, y: dy })
     }
 
     fn L(&mut self, x: f64, y: f64) -> &mut Self {
-        self.add_segment("L", vec![x, y])
+        self.add_segment(PathSegment::LineTo { abs: true, x, y })
     }
 
     fn l(&mut self, dx: f64, dy: f64) -> &mut Self {
-        self.add_segment("l", vec![dx, dy])
+        self.add_segment(PathSegment::LineTo { abs: false, x: dx, y: dy })
     }
 
     fn H(&mut self, x: f64) -> &mut Self {
-        self.add_segment("H", vec![x])
+        self.add_segment(PathSegment::Horizontal { abs: true, x })
     }
 
     fn h(&mut self, dx: f64) -> &mut Self {
-        self.add_segment("h", vec![dx])
+        self.add_segment(PathSegment::Horizontal { abs: false, x: dx })
     }
 
     fn V(&mut self, y: f64) -> &mut Self {
-        self.add_segment("V", vec![y])
+        self.add_segment(PathSegment::Vertical { abs: true, y })
     }
 
     fn v(&mut self, dy: f64) -> &mut Self {
-        self.add_segment("v", vec![dy])
+        self.add_segment(PathSegment::Vertical { abs: false, y: dy })
     }
 
     fn C(&mut self, c1x: f64, c1y: f64, c2x: f64, c2y: f64, x: f64, y: f64) -> &mut Self {
-        self.add_segment("C", vec![c1x, c1y, c2x, c2y, x, y])
+        self.add_segment(PathSegment::CubicTo { abs: true, x1: c1x, y1: c1y, x2: c2x, y2: c2y, x, y })
     }
 
     fn c(&mut self, dc1x: f64, dc1y: f64, dc2x: f64, dc2y: f64, dx: f64, dy: f64) -> &mut Self {
-        self.add_segment("c", vec![dc1x, dc1y, dc2x, dc2y, dx, dy])
+        self.add_segment(PathSegment::CubicTo { abs: false, x1: dc1x, y1: dc1y, x2: dc2x, y2: dc2y, x: dx, y: dy })
     }
 
     fn S(&mut self, c2x: f64, c2y: f64, x: f64, y: f64) -> &mut Self {
-        self.add_segment("S", vec![c2x, c2y, x, y])
+        self.add_segment(PathSegment::SmoothCubicTo { abs: true, x2: c2x, y2: c2y, x, y })
     }
 
     fn s(&mut self, dc2x: f64, dc2y: f64, dx: f64, dy: f64) -> &mut Self {
-        self.add_segment("s", vec![dc2x, dc2y, dx, dy])
+        self.add_segment(PathSegment::SmoothCubicTo { abs: false, x2: dc2x, y2: dc2y, x: dx, y: dy })
     }
 
     fn Q(&mut self, cx: f64, cy: f64, x: f64, y: f64) -> &mut Self {
-        self.add_segment("Q", vec![cx, cy, x, y])
+        self.add_segment(PathSegment::QuadTo { abs: true, x1: cx, y1: cy, x, y })
     }
 
     fn q(&mut self, dcx: f64, dcy: f64, dx: f64, dy: f64) -> &mut Self {
-        self.add_segment("q", vec![dcx, dcy, dx, dy])
+        self.add_segment(PathSegment::QuadTo { abs: false, x1: dcx, y1: dcy, x: dx, y: dy })
     }
 
     fn T(&mut self, x: f64, y: f64) -> &mut Self {
-        self.add_segment("T", vec![x, y])
+        self.add_segment(PathSegment::SmoothQuadTo { abs: true, x, y })
     }
 
     fn t(&mut self, dx: f64, dy: f64) -> &mut Self {
-        self.add_segment("t", vec![dx, dy])
+        self.add_segment(PathSegment::SmoothQuadTo { abs: false, x: dx, y: dy })
     }
 
     fn A(&mut self, rx: f64, ry: f64, x_axis_rotation: f64, large_arc_flag: i32, sweep_flag: i32, x: f64, y: f64) -> &mut Self {
-        self.add_segment("A", vec![rx, ry, x_axis_rotation, large_arc_flag as f64, sweep_flag as f64, x, y])
+        self.add_segment(PathSegment::ArcTo {
+            abs: true,
+            rx,
+            ry,
+            x_axis_rotation,
+            large_arc: large_arc_flag != 0,
+            sweep: sweep_flag != 0,
+            x,
+            y,
+        })
     }
 
     fn a(&mut self, rx: f64, ry: f64, x_axis_rotation: f64, large_arc_flag: i32, sweep_flag: i32, dx: f64, dy: f64) -> &mut Self {
-        self.add_segment("a", vec![rx, ry, x_axis_rotation, large_arc_flag as f64, sweep_flag as f64, dx, dy])
+        self.add_segment(PathSegment::ArcTo {
+            abs: false,
+            rx,
+            ry,
+            x_axis_rotation,
+            large_arc: large_arc_flag != 0,
+            sweep: sweep_flag != 0,
+            x: dx,
+            y: dy,
+        })
     }
 
     fn Z(&mut self) -> &mut Self {
-        self.add_segment("Z", vec![])
+        self.add_segment(PathSegment::Close { abs: true })
     }
 
     fn clear_path(&mut self) -> &mut Self {
@@ -173,15 +322,18 @@ impl PathBuilder for Element {
         let path_data = self.build_path_string();
         self.set_attr("d", &path_data)
     }
+
+    fn transform_path(&mut self, matrix: &Matrix) -> &mut Self {
+        if let Some(segments) = self.path_segments.as_mut() {
+            *segments = transform_segments(segments, matrix);
+            self.redraw();
+        }
+        self
+    }
 }
 
 impl Element {
-    fn add_segment(&mut self, command: &str, coords: Vec<f64>) -> &mut Self {
-        let segment = PathSegment {
-            command: command.to_string(),
-            coords,
-        };
-
+    fn add_segment(&mut self, segment: PathSegment) -> &mut Self {
         if self.path_segments.is_none() {
             self.path_segments = Some(Vec::new());
         }
@@ -199,37 +351,7 @@ impl Element {
     }
 
     fn build_path_string(&self) -> String {
-        if let Some(segments) = &self.path_segments {
-            let mut path_data = String::new();
-            let mut last_command = "";
-
-            for segment in segments {
-                if last_command == segment.command {
-                    // Same command, just add coordinates
-                    path_data.push(' ');
-                    path_data.push_str(&segment.coords.iter()
-                        .map(|c| c.to_string())
-                        .collect::<Vec<_>>()
-                        .join(" "));
-                } else {
-                    // New command
-                    if !path_data.is_empty() {
-                        path_data.push(' ');
-                    }
-                    path_data.push_str(&segment.command);
-                    if !segment.coords.is_empty() {
-                        path_data.push_str(&segment.coords.iter()
-                            .map(|c| c.to_string())
-                            .collect::<Vec<_>>()
-                            .join(" "));
-                    }
-                }
-                last_command = &segment.command;
-            }
-            path_data
-        } else {
-            String::new()
-        }
+        self.path_segments.as_deref().map(path_data).unwrap_or_default()
     }
 
     /// Enable/disable auto-redraw for path operations
@@ -239,35 +361,99 @@ impl Element {
     }
 
     /// Draw path with animation
+    ///
+    /// `duration` and `delay` are in milliseconds; `easing` is any value accepted
+    /// by `Easing::parse` and falls back to linear.
     pub fn draw_animated(&mut self, duration: u32, delay: u32, easing: &str) -> &mut Self {
-        // Calculate path length (simplified)
-        let length = self.calculate_path_length();
+        let length = round_value(self.path_length());
         
         self.set_attr("stroke-dasharray", &format!("{} {}", length, length))
             .set_attr("stroke-dashoffset", &length.to_string());
         
-        // Add animation (simplified - would need full animation system)
-        self.animate_attr("stroke-dashoffset", &length.to_string(), "0", duration);
-        
-        self
+        let mut animation = Animation::animate("stroke-dashoffset")
+            .from(&length.to_string())
+            .to("0")
+            .dur_ms(duration as u64)
+            .freeze();
+        if delay > 0 {
+            animation = animation.begin(Timing::at(delay as f64 / 1000.0));
+        }
+        if let Some(easing) = Easing::parse(easing) {
+            animation = animation.easing(easing);
+        }
+        self.animation(animation)
     }
+}
 
-    fn calculate_path_length(&self) -> f64 {
-        // Simplified path length calculation
-        // In a real implementation, this would calculate the actual path length
-        if let Some(segments) = &self.path_segments {
-            segments.len() as f64 * 100.0 // Rough approximation
-        } else {
-            0.0
+/// Compact path data for a list of segments, the inverse of `parse_path`
+///
+/// Consecutive segments with the same command share the command letter
+/// (except moves, whose repeats would mean line-to), and separators are left
+/// out wherever the grammar allows: after command letters, before negative
+/// numbers, between `.5` style fractions and after arc flags.
+pub fn path_data(segments: &[PathSegment]) -> String {
+    let mut path_data = String::new();
+    let mut last_command = None;
+    for segment in segments {
+        let command = segment.command();
+        let repeatable = !matches!(command, 'M' | 'm' | 'Z' | 'z');
+        if !(repeatable && last_command == Some(command)) {
+            path_data.push(command);
         }
+        let is_arc = matches!(segment, PathSegment::ArcTo { .. });
+        for (index, value) in segment.coords().iter().enumerate() {
+            let number = format_number(*value);
+            // Arc flags are single characters, so whatever follows them needs no separator
+            let after_flag = is_arc && (index == 4 || index == 5);
+            if !joinable(&path_data, &number, after_flag) {
+                path_data.push(' ');
+            }
+            path_data.push_str(&number);
+        }
+        last_command = Some(command);
+    }
+    path_data
+}
+
+/// Whether `number` can follow the path data so far without a separator
+fn joinable(path_data: &str, number: &str, after_flag: bool) -> bool {
+    after_flag
+        || path_data.ends_with(|c: char| c.is_ascii_alphabetic())
+        || number.starts_with('-')
+        || (number.starts_with('.') && last_number(path_data).contains('.'))
+}
+
+/// The trailing number of some path data
+fn last_number(path_data: &str) -> &str {
+    let start = path_data
+        .rfind(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .map_or(0, |i| i + 1);
+    &path_data[start..]
+}
+
+/// Shortest text for a number: no leading zero before the decimal point and no negative zero
+fn format_number(value: f64) -> String {
+    let text = if value == 0.0 { "0".to_string() } else { value.to_string() };
+    if let Some(fraction) = text.strip_prefix("0.") {
+        format!(".{}", fraction)
+    } else if let Some(fraction) = text.strip_prefix("-0.") {
+        format!("-.{}", fraction)
+    } else {
+        text
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::math::Point;
     use crate::svg::Svg;
 
+    /// Points from coordinate pairs, shared by the submodules' tests
+    pub(crate) fn points(coords: &[(f64, f64)]) -> Vec<Point> {
+        coords.iter().map(|&(x, y)| Point::new(x, y)).collect()
+    }
+
     #[test]
     fn test_path_building() {
         let mut canvas = Svg::new(100, 100);
@@ -303,4 +489,20 @@ mod tests {
         path.remove_segment(1);
         assert_eq!(path.get_segment_count(), 2);
     }
+
+    #[test]
+    fn test_draw_animated() {
+        let mut canvas = Svg::new(100, 100);
+        canvas.path("")
+            .M(10.0, 10.0)
+            .L(90.0, 10.0)
+            .draw_animated(1500, 250, "ease-out");
+
+        let output = canvas.to_string();
+        assert!(output.contains("dur=\"1.5s\""));
+        assert!(output.contains("begin=\"250ms\""));
+        assert!(output.contains("calcMode=\"spline\""));
+        assert!(output.contains("keySplines=\"0 0 0.58 1\""));
+        assert!(output.contains("fill=\"freeze\""));
+    }
 }
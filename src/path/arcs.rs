@@ -0,0 +1,101 @@
+//! Replacing elliptical arcs with cubic Béziers
+//!
+//! For renderers and output formats without elliptical arcs. Each arc is cut
+//! into equal angular pieces, at least one per quarter turn, until the cubic
+//! approximation of every piece is within the requested tolerance.
+
+use super::normalize::Cursor;
+use super::{PathBuilder, PathSegment};
+use crate::animation::round_value;
+use crate::math::{EndpointArc, Point};
+use crate::svg::Element;
+
+/// The same path with every `A`/`a` segment replaced by `C`/`c` segments that
+/// deviate from the arc by at most `tolerance`
+pub fn arcs_to_cubics(segments: &[PathSegment], tolerance: f64) -> Vec<PathSegment> {
+    let mut cursor = Cursor::new();
+    let mut result = Vec::with_capacity(segments.len());
+    for segment in segments {
+        let absolute = cursor.absolute(segment);
+        if let PathSegment::ArcTo { rx, ry, x_axis_rotation, large_arc, sweep, x, y, .. } = absolute {
+            let arc = EndpointArc { from: cursor.position, rx, ry, x_axis_rotation, large_arc, sweep, to: Point::new(x, y) };
+            match arc.to_center() {
+                Some(center) => {
+                    let cubics = center.cubics(arc.from, arc.to, center.pieces(tolerance));
+                    result.extend(cubics.iter().map(|cubic| cubic_segment(cubic, segment.is_absolute())));
+                }
+                // A zero radius draws a straight line; equal end points draw nothing
+                None if arc.from != arc.to => {
+                    let abs = segment.is_absolute();
+                    let (x, y) = if abs { (x, y) } else { relative(arc.to, arc.from) };
+                    result.push(PathSegment::LineTo { abs, x, y });
+                }
+                None => {}
+            }
+        } else {
+            result.push(*segment);
+        }
+        cursor.advance(&absolute);
+    }
+    result
+}
+
+fn relative(point: Point, origin: Point) -> (f64, f64) {
+    (round_value(point.x - origin.x), round_value(point.y - origin.y))
+}
+
+fn cubic_segment([start, c1, c2, end]: &[Point; 4], abs: bool) -> PathSegment {
+    if abs {
+        return PathSegment::CubicTo { abs, x1: c1.x, y1: c1.y, x2: c2.x, y2: c2.y, x: end.x, y: end.y };
+    }
+    let ((x1, y1), (x2, y2), (x, y)) = (relative(*c1, *start), relative(*c2, *start), relative(*end, *start));
+    PathSegment::CubicTo { abs, x1, y1, x2, y2, x, y }
+}
+
+impl Element {
+    /// Replace this path's arcs with cubic Béziers within `tolerance`
+    pub fn arcs_to_curves(&mut self, tolerance: f64) -> &mut Self {
+        if let Some(segments) = self.path_segments.as_mut() {
+            *segments = arcs_to_cubics(segments, tolerance);
+            self.redraw();
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::{parse_path, path_bounds, path_data};
+
+    #[test]
+    fn test_arcs_to_cubics() {
+        let segments = parse_path("M0 0 A10 10 0 0 1 20 0 a10 10 0 0 1 -20 0").unwrap();
+        let coarse = arcs_to_cubics(&segments, 1.0);
+        assert!(coarse.iter().all(|s| !matches!(s, PathSegment::ArcTo { .. })));
+        // One cubic per quarter turn at a loose tolerance, more when tightened
+        assert_eq!(coarse.len(), 5);
+        let fine = arcs_to_cubics(&segments, 1e-4);
+        assert!(fine.len() > coarse.len());
+        // The relative half keeps relative coordinates and ends back at the start
+        assert!(!fine.last().unwrap().is_absolute());
+        let bounds = path_bounds(&fine).unwrap();
+        assert!((bounds.y + 10.0).abs() < 1e-3 && (bounds.height - 20.0).abs() < 1e-3);
+
+        // Every sample of the approximation stays on the circle
+        let mut svg = crate::svg::Svg::new(100, 100);
+        let path = svg.path("M0 0 A10 10 0 0 1 20 0");
+        path.arcs_to_curves(0.01);
+        let length = path.path_length();
+        for i in 0..=20 {
+            let p = path.point_at_length(length * i as f64 / 20.0).unwrap();
+            assert!((p.distance_to(&Point::new(10.0, 0.0)) - 10.0).abs() <= 0.01);
+        }
+    }
+
+    #[test]
+    fn test_degenerate_arcs() {
+        let segments = parse_path("M0 0 A0 5 0 0 1 10 0 a5 5 0 0 1 0 0 a0 0 0 0 1 5 5").unwrap();
+        assert_eq!(path_data(&arcs_to_cubics(&segments, 0.1)), "M0 0L10 0l5 5");
+    }
+}
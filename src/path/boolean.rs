@@ -0,0 +1,447 @@
+//! Boolean operations on filled paths
+//!
+//! Both outlines are flattened to polygons and cut at every crossing and
+//! overlap. Vertices are snapped to a fine grid so that coincident edges from
+//! the two inputs collapse into one, and each remaining edge is kept when the
+//! operation's result differs on its two sides. The kept edges are linked
+//! back into closed loops running clockwise on screen around filled areas and
+//! counterclockwise around holes.
+//!
+//! Curves are flattened to a fixed absolute tolerance, `TOLERANCE`, so the
+//! result is always a polygon: curved input comes back as many short lines
+//! (a circle of radius 100 as about 250).
+
+use std::collections::{BTreeMap, HashMap};
+
+use super::flatten::flatten_segments;
+use super::geometry::lerp;
+use super::PathSegment;
+use crate::animation::round_value;
+use crate::math::Point;
+use crate::svg::Element;
+
+/// Flattening tolerance for curves, in user units; it does not scale with the input
+pub(super) const TOLERANCE: f64 = 0.01;
+/// Vertices closer than this are merged
+const GRID: f64 = 1e-6;
+
+/// How overlapping and self-intersecting areas are filled (`fill-rule`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FillRule {
+    #[default]
+    NonZero,
+    EvenOdd,
+}
+
+impl FillRule {
+    fn contains(self, winding: i32) -> bool {
+        match self {
+            FillRule::NonZero => winding != 0,
+            FillRule::EvenOdd => winding % 2 != 0,
+        }
+    }
+}
+
+/// Which area of two shapes to keep
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BooleanOp {
+    /// Area inside either shape
+    Union,
+    /// Area inside both shapes
+    Intersection,
+    /// Area of the first shape outside the second
+    Difference,
+    /// Area inside exactly one of the shapes
+    Xor,
+}
+
+impl BooleanOp {
+    fn apply(self, a: bool, b: bool) -> bool {
+        match self {
+            BooleanOp::Union => a || b,
+            BooleanOp::Intersection => a && b,
+            BooleanOp::Difference => a && !b,
+            BooleanOp::Xor => a != b,
+        }
+    }
+}
+
+type Key = (i64, i64);
+/// An input edge running along a piece: its index, the parameter at the
+/// piece's middle, and whether it runs from the lower key to the higher
+type Along = (usize, f64, bool);
+
+fn key(p: Point) -> Key {
+    ((p.x / GRID).round() as i64, (p.y / GRID).round() as i64)
+}
+
+fn snapped(key: Key) -> Point {
+    Point::new(round_value(key.0 as f64 * GRID), round_value(key.1 as f64 * GRID))
+}
+
+/// Closed polygons approximating every subpath; open subpaths are closed as when filled
+pub(super) fn rings(segments: &[PathSegment]) -> Vec<Vec<Point>> {
+    flatten_segments(segments, TOLERANCE)
+        .into_iter()
+        .filter_map(|mut ring| {
+            if ring.len() > 1 && ring.first() == ring.last() {
+                ring.pop();
+            }
+            (ring.len() > 2).then_some(ring)
+        })
+        .collect()
+}
+
+fn edges(rings: &[Vec<Point>]) -> impl Iterator<Item = (Point, Point)> + '_ {
+    rings.iter().flat_map(|ring| (0..ring.len()).map(move |i| (ring[i], ring[(i + 1) % ring.len()])))
+}
+
+/// Winding number of `edges` around `p`, counting crossings of the ray from
+/// `p` in direction `ray`
+fn winding<'a>(edges: impl Iterator<Item = &'a (Point, Point)>, p: Point, ray: Point) -> i32 {
+    // Rotate so that the ray points along +x
+    let rotate = |v: Point| Point::new(v.dot(&ray), ray.cross(&v));
+    let p = rotate(p);
+    edges
+        .map(|&(a, b)| {
+            let (a, b) = (rotate(a), rotate(b));
+            let side = b.sub(&a).cross(&p.sub(&a));
+            if a.y <= p.y && b.y > p.y && side > 0.0 {
+                1
+            } else if a.y > p.y && b.y <= p.y && side < 0.0 {
+                -1
+            } else {
+                0
+            }
+        })
+        .sum()
+}
+
+/// Where each edge has to be cut, as parameters along it with the snapped
+/// cut point: its ends, crossings with other edges, and the ends of collinear
+/// overlaps. Each cut point is computed once and shared by both edges so that
+/// the pieces on either side meet at the same vertex.
+fn cuts(edges: &[(Point, Point)]) -> Vec<Vec<(f64, Key)>> {
+    let mut cuts: Vec<Vec<(f64, Key)>> = edges.iter().map(|&(p, q)| vec![(0.0, key(p)), (1.0, key(q))]).collect();
+    for i in 0..edges.len() {
+        let (p, p2) = edges[i];
+        if key(p) == key(p2) {
+            continue;
+        }
+        let r = p2.sub(&p);
+        let r_length = r.dot(&r).sqrt();
+        for j in i + 1..edges.len() {
+            let (q, q2) = edges[j];
+            if key(q) == key(q2)
+                || p.x.max(p2.x) < q.x.min(q2.x) - GRID
+                || q.x.max(q2.x) < p.x.min(p2.x) - GRID
+                || p.y.max(p2.y) < q.y.min(q2.y) - GRID
+                || q.y.max(q2.y) < p.y.min(p2.y) - GRID
+            {
+                continue;
+            }
+            let s = q2.sub(&q);
+            let s_length = s.dot(&s).sqrt();
+            let pq = q.sub(&p);
+            let denominator = r.cross(&s);
+            if denominator.abs() > 1e-12 * r_length * s_length {
+                let t = pq.cross(&s) / denominator;
+                let u = pq.cross(&r) / denominator;
+                // Ends touching the other edge within the grid count as crossings
+                let (t_slack, u_slack) = (GRID / r_length, GRID / s_length);
+                if (-t_slack..=1.0 + t_slack).contains(&t) && (-u_slack..=1.0 + u_slack).contains(&u) {
+                    let (t, u) = (t.clamp(0.0, 1.0), u.clamp(0.0, 1.0));
+                    let point = match (t, u) {
+                        (0.0, _) => p,
+                        (1.0, _) => p2,
+                        (_, 0.0) => q,
+                        (_, 1.0) => q2,
+                        _ => lerp(p, p2, t),
+                    };
+                    cuts[i].push((t, key(point)));
+                    cuts[j].push((u, key(point)));
+                }
+            } else if pq.cross(&r).abs() <= GRID * r_length {
+                // Collinear: each edge is cut where the other one ends
+                for end in [q, q2] {
+                    let t = end.sub(&p).dot(&r) / (r_length * r_length);
+                    if t > 0.0 && t < 1.0 {
+                        cuts[i].push((t, key(end)));
+                    }
+                }
+                for end in [p, p2] {
+                    let u = end.sub(&q).dot(&s) / (s_length * s_length);
+                    if u > 0.0 && u < 1.0 {
+                        cuts[j].push((u, key(end)));
+                    }
+                }
+            }
+        }
+    }
+    cuts
+}
+
+/// Merge vertices where a loop continues straight on
+fn drop_collinear(ring: &mut Vec<Point>) {
+    let mut index = 0;
+    while ring.len() > 2 && index < ring.len() {
+        let previous = ring[(index + ring.len() - 1) % ring.len()];
+        let next = ring[(index + 1) % ring.len()];
+        let (incoming, outgoing) = (ring[index].sub(&previous), next.sub(&ring[index]));
+        let scale = incoming.dot(&incoming).sqrt() * outgoing.dot(&outgoing).sqrt();
+        if incoming.cross(&outgoing).abs() <= 1e-9 * scale && incoming.dot(&outgoing) > 0.0 {
+            ring.remove(index);
+            index = index.saturating_sub(1);
+        } else {
+            index += 1;
+        }
+    }
+}
+
+/// The outline of `op` applied to the areas filled by `a` and `b`, as closed
+/// polygons; curves are flattened to within 0.01 user units, so curved input
+/// gives densely sampled lines rather than curves
+pub fn path_boolean(a: &[PathSegment], a_rule: FillRule, b: &[PathSegment], b_rule: FillRule, op: BooleanOp) -> Vec<PathSegment> {
+    combine_rings(&rings(a), a_rule, &rings(b), b_rule, op)
+}
+
+/// `path_boolean` on polygons
+pub(super) fn combine_rings(a: &[Vec<Point>], a_rule: FillRule, b: &[Vec<Point>], b_rule: FillRule, op: BooleanOp) -> Vec<PathSegment> {
+    let all: Vec<(Point, Point)> = edges(a).chain(edges(b)).collect();
+    let a_count = edges(a).count();
+    let in_a = |edge: usize| edge < a_count;
+
+    // Undirected pieces between cuts, deduplicated on the grid, with the edges
+    // running along each one
+    let mut pieces: BTreeMap<(Key, Key), Vec<Along>> = BTreeMap::new();
+    for (edge, mut cuts) in cuts(&all).into_iter().enumerate() {
+        cuts.sort_by(|x, y| x.0.total_cmp(&y.0));
+        for pair in cuts.windows(2) {
+            let (from, to) = (pair[0].1, pair[1].1);
+            if from != to {
+                pieces.entry((from.min(to), from.max(to))).or_default().push((edge, (pair[0].0 + pair[1].0) / 2.0, from < to));
+            }
+        }
+    }
+
+    // Keep the pieces separating filled from unfilled, directed so the filled
+    // side has a positive cross product (clockwise in y-down coordinates).
+    // Both sides are classified from the piece's middle with a ray along its
+    // normal: away from the piece the two sides see the same crossings, and
+    // each edge along the piece counts for one side only. Sampling points off
+    // the piece instead would misjudge slivers narrower than the offset.
+    let mut kept = Vec::new();
+    for ((from, to), along) in pieces {
+        let direction = snapped(to).sub(&snapped(from));
+        let normal = Point::new(-direction.y, direction.x);
+        let (edge, t, _) = along[0];
+        let middle = lerp(all[edge].0, all[edge].1, t);
+        let sides = |first: bool| {
+            let others = all.iter().enumerate().filter(|(index, _)| in_a(*index) == first && along.iter().all(|(edge, ..)| edge != index));
+            let left = winding(others.map(|(_, edge)| edge), middle, normal);
+            let right = left + along.iter().filter(|(edge, ..)| in_a(*edge) == first).map(|&(_, _, forward)| if forward { -1 } else { 1 }).sum::<i32>();
+            (left, right)
+        };
+        let ((a_left, a_right), (b_left, b_right)) = (sides(true), sides(false));
+        let inner = op.apply(a_rule.contains(a_left), b_rule.contains(b_left));
+        let outer = op.apply(a_rule.contains(a_right), b_rule.contains(b_right));
+        match (inner, outer) {
+            (true, false) => kept.push((from, to)),
+            (false, true) => kept.push((to, from)),
+            _ => {}
+        }
+    }
+
+    // Link into loops, turning as sharply as possible towards the filled side
+    // at shared vertices so that loops touching at a vertex stay separate
+    let mut outgoing: HashMap<Key, Vec<usize>> = HashMap::new();
+    for (index, (from, _)) in kept.iter().enumerate() {
+        outgoing.entry(*from).or_default().push(index);
+    }
+    let mut used = vec![false; kept.len()];
+    let mut segments = Vec::new();
+    for first in 0..kept.len() {
+        if used[first] {
+            continue;
+        }
+        used[first] = true;
+        let start = kept[first].0;
+        let mut ring = vec![snapped(start)];
+        let mut current = first;
+        loop {
+            let (from, to) = kept[current];
+            if to == start {
+                break;
+            }
+            ring.push(snapped(to));
+            let incoming = snapped(to).sub(&snapped(from));
+            let turn = |&index: &usize| {
+                let outgoing = snapped(kept[index].1).sub(&snapped(to));
+                incoming.cross(&outgoing).atan2(incoming.dot(&outgoing))
+            };
+            // A loop left open by rounding ends here and is closed as it is
+            let candidates = outgoing.get(&to).map(Vec::as_slice).unwrap_or_default();
+            let next = candidates.iter().filter(|&&index| !used[index]).max_by(|x, y| turn(x).total_cmp(&turn(y)));
+            match next {
+                Some(&index) => {
+                    used[index] = true;
+                    current = index;
+                }
+                None => break,
+            }
+        }
+        drop_collinear(&mut ring);
+        if ring.len() > 2 {
+            // Start at the top-left vertex for stable output
+            let top_left = (0..ring.len()).min_by(|&i, &j| (ring[i].y, ring[i].x).partial_cmp(&(ring[j].y, ring[j].x)).unwrap()).unwrap();
+            ring.rotate_left(top_left);
+            segments.push(PathSegment::MoveTo { abs: true, x: ring[0].x, y: ring[0].y });
+            segments.extend(ring[1..].iter().map(|p| PathSegment::LineTo { abs: true, x: p.x, y: p.y }));
+            segments.push(PathSegment::Close { abs: true });
+        }
+    }
+    segments
+}
+
+impl Element {
+    /// A detached path element drawing `segments`
+    pub(super) fn from_segments(segments: Vec<PathSegment>) -> Element {
+        let mut attrs = HashMap::new();
+        attrs.insert("d".to_string(), super::path_data(&segments));
+        let mut path = Element::new("path", attrs);
+        path.path_segments = Some(segments);
+        path
+    }
+
+    /// The `fill-rule` attribute of this element
+    pub fn fill_rule(&self) -> FillRule {
+        match self.attributes.get("fill-rule").map(|rule| rule.trim()) {
+            Some("evenodd") => FillRule::EvenOdd,
+            _ => FillRule::NonZero,
+        }
+    }
+
+    /// A new path element outlining `op` applied to this shape and `other`,
+    /// using each one's fill rule; `None` if either is not a shape.
+    /// Curves come back flattened to lines, as in `path_boolean`.
+    /// Styling is not copied; add the result with `Svg::append`.
+    pub fn boolean(&self, other: &Element, op: BooleanOp) -> Option<Element> {
+        let segments = path_boolean(&self.to_path_segments()?, self.fill_rule(), &other.to_path_segments()?, other.fill_rule(), op);
+        Some(Element::from_segments(segments))
+    }
+
+    /// The area covered by this shape or `other`
+    pub fn union(&self, other: &Element) -> Option<Element> {
+        self.boolean(other, BooleanOp::Union)
+    }
+
+    /// The area covered by both this shape and `other`
+    pub fn intersection(&self, other: &Element) -> Option<Element> {
+        self.boolean(other, BooleanOp::Intersection)
+    }
+
+    /// The area of this shape not covered by `other`
+    pub fn difference(&self, other: &Element) -> Option<Element> {
+        self.boolean(other, BooleanOp::Difference)
+    }
+
+    /// The area covered by exactly one of this shape and `other`
+    pub fn xor(&self, other: &Element) -> Option<Element> {
+        self.boolean(other, BooleanOp::Xor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::{parse_path, path_data};
+    use crate::svg::Svg;
+
+    fn combine(a: &str, b: &str, op: BooleanOp) -> String {
+        path_data(&path_boolean(&parse_path(a).unwrap(), FillRule::NonZero, &parse_path(b).unwrap(), FillRule::NonZero, op))
+    }
+
+    /// Filled area, counting each loop by its orientation
+    fn area(segments: &[PathSegment]) -> f64 {
+        rings(segments)
+            .iter()
+            .map(|ring| (0..ring.len()).map(|i| ring[i].cross(&ring[(i + 1) % ring.len()])).sum::<f64>() / 2.0)
+            .sum()
+    }
+
+    #[test]
+    fn test_boolean_squares() {
+        let (a, b) = ("M0 0H20V20H0Z", "M10 10H30V30H10Z");
+        assert_eq!(combine(a, b, BooleanOp::Intersection), "M10 10L20 10 20 20 10 20Z");
+        assert_eq!(combine(a, b, BooleanOp::Union), "M0 0L20 0 20 10 30 10 30 30 10 30 10 20 0 20Z");
+        assert_eq!(combine(a, b, BooleanOp::Difference), "M0 0L20 0 20 10 10 10 10 20 0 20Z");
+        let xor = path_boolean(&parse_path(a).unwrap(), FillRule::NonZero, &parse_path(b).unwrap(), FillRule::NonZero, BooleanOp::Xor);
+        assert!((area(&xor) - 600.0).abs() < 1e-9);
+
+        // Shared edges and overlapping collinear edges merge
+        assert_eq!(combine(a, "M20 0H40V20H20Z", BooleanOp::Union), "M0 0L40 0 40 20 0 20Z");
+        assert_eq!(combine(a, "M20 5H40V15H20Z", BooleanOp::Union), "M0 0L20 0 20 5 40 5 40 15 20 15 20 20 0 20Z");
+        assert_eq!(combine(a, "M0 0H10V20H0Z", BooleanOp::Difference), "M10 0L20 0 20 20 10 20Z");
+        assert_eq!(combine(a, a, BooleanOp::Xor), "");
+    }
+
+    #[test]
+    fn test_boolean_holes_and_fill_rules() {
+        // Concentric squares: a hole under evenodd, solid under nonzero
+        let frame = parse_path("M0 0H30V30H0Z M10 10H20V20H10Z").unwrap();
+        let dot = parse_path("M12 12H18V18H12Z").unwrap();
+        let holed = path_boolean(&frame, FillRule::EvenOdd, &dot, FillRule::NonZero, BooleanOp::Union);
+        assert!((area(&holed) - (900.0 - 100.0 + 36.0)).abs() < 1e-9);
+        let solid = path_boolean(&frame, FillRule::NonZero, &dot, FillRule::NonZero, BooleanOp::Union);
+        assert_eq!(path_data(&solid), "M0 0L30 0 30 30 0 30Z");
+        assert!((area(&path_boolean(&frame, FillRule::EvenOdd, &frame, FillRule::EvenOdd, BooleanOp::Intersection)) - 800.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_boolean_large_circle_band() {
+        // Quads along a finely flattened large circle, with triangles filling the
+        // gaps between them, cross each other at very shallow angles; every
+        // crossing has to link up for the band to close
+        for radius in [150.0_f64, 200.0] {
+            let circle = parse_path(&format!("M{radius} 0A{radius} {radius} 0 0 1-{radius} 0A{radius} {radius} 0 0 1 {radius} 0Z")).unwrap();
+            let outline = rings(&circle).remove(0);
+            let count = outline.len();
+            let normal = |i: usize| {
+                let (p, q) = (outline[i % count], outline[(i + 1) % count]);
+                let length = p.distance_to(&q);
+                Point::new(-(q.y - p.y) / length * 2.0, (q.x - p.x) / length * 2.0)
+            };
+            let mut pieces = Vec::new();
+            for i in 0..count {
+                let (p, q, n, m) = (outline[i], outline[(i + 1) % count], normal(i), normal(i + 1));
+                pieces.push(vec![Point::new(p.x - n.x, p.y - n.y), Point::new(q.x - n.x, q.y - n.y), Point::new(q.x + n.x, q.y + n.y), Point::new(p.x + n.x, p.y + n.y)]);
+                pieces.push(vec![q, Point::new(q.x - n.x, q.y - n.y), Point::new(q.x - m.x, q.y - m.y)]);
+            }
+            // Oriented alike, so that the nonzero union covers every piece
+            for piece in &mut pieces {
+                if (0..piece.len()).map(|i| piece[i].cross(&piece[(i + 1) % piece.len()])).sum::<f64>() < 0.0 {
+                    piece.reverse();
+                }
+            }
+            let band = combine_rings(&pieces, FillRule::NonZero, &[], FillRule::NonZero, BooleanOp::Union);
+            assert_eq!(band.iter().filter(|s| matches!(s, PathSegment::MoveTo { .. })).count(), 2);
+            let expected = std::f64::consts::PI * ((radius + 2.0).powi(2) - (radius - 2.0).powi(2));
+            assert!((area(&band).abs() - expected).abs() < 0.01 * expected);
+        }
+    }
+
+    #[test]
+    fn test_boolean_shapes() {
+        let mut svg = Svg::new(100, 100);
+        let circle = svg.circle(10).center(10, 10).clone();
+        let square = svg.rect(10, 20).clone();
+        let half = circle.difference(&square).unwrap();
+        assert_eq!(half.tag, "path");
+        let bounds = half.path_bbox().unwrap();
+        assert!((bounds.x - 10.0).abs() < 1e-9 && (bounds.width - 10.0).abs() < 1e-6);
+        // The flattened half disc is within the tolerance of the exact area
+        let area = area(half.path_segments.as_ref().unwrap()).abs();
+        assert!((area - std::f64::consts::PI * 50.0).abs() < 0.5);
+        assert!(circle.union(&svg.text("x").clone()).is_none());
+    }
+}
@@ -0,0 +1,133 @@
+//! Exact bounding boxes
+//!
+//! Curves are bounded by their end points plus the interior points where one
+//! coordinate has a turning point: derivative roots for Béziers, and the
+//! angles where the rotated ellipse is horizontal or vertical for arcs.
+
+use std::f64::consts::{PI, TAU};
+
+use super::geometry::Curve;
+use super::measure::outline_curves;
+use super::PathSegment;
+use crate::math::Rect;
+use crate::svg::Element;
+
+impl Curve {
+    /// Tight axis-aligned bounds
+    pub fn bounds(&self) -> Rect {
+        let mut points = vec![self.point(0.0), self.point(1.0)];
+        match *self {
+            Curve::Line(..) => {}
+            Curve::Quad(a, b, c) => {
+                for (p0, p1, p2) in [(a.x, b.x, c.x), (a.y, b.y, c.y)] {
+                    let denominator = p0 - 2.0 * p1 + p2;
+                    if denominator != 0.0 {
+                        points.extend(interior(&[(p0 - p1) / denominator]).map(|t| self.point(t)));
+                    }
+                }
+            }
+            Curve::Cubic(a, b, c, d) => {
+                for (p0, p1, p2, p3) in [(a.x, b.x, c.x, d.x), (a.y, b.y, c.y, d.y)] {
+                    // Derivative / 3 = qa t² + qb t + qc
+                    let qa = -p0 + 3.0 * p1 - 3.0 * p2 + p3;
+                    let qb = 2.0 * (p0 - 2.0 * p1 + p2);
+                    let qc = p1 - p0;
+                    points.extend(interior(&quadratic_roots(qa, qb, qc)).map(|t| self.point(t)));
+                }
+            }
+            Curve::Arc(arc) => {
+                if let Some(center) = arc.to_center() {
+                    let (cos, sin) = (center.rotation.cos(), center.rotation.sin());
+                    let x_extreme = (-center.ry * sin).atan2(center.rx * cos);
+                    let y_extreme = (center.ry * cos).atan2(center.rx * sin);
+                    for theta in [x_extreme, x_extreme + PI, y_extreme, y_extreme + PI] {
+                        // Angle travelled from the start in the sweep direction
+                        let travelled = ((theta - center.start_angle) * center.sweep_angle.signum()).rem_euclid(TAU);
+                        if travelled < center.sweep_angle.abs() {
+                            points.push(center.point(theta));
+                        }
+                    }
+                }
+            }
+        }
+        Rect::from_points(&points).unwrap_or(Rect::new(0.0, 0.0, 0.0, 0.0))
+    }
+}
+
+/// Real roots of a t² + b t + c
+fn quadratic_roots(a: f64, b: f64, c: f64) -> Vec<f64> {
+    if a.abs() < 1e-12 {
+        return if b.abs() < 1e-12 { vec![] } else { vec![-c / b] };
+    }
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return vec![];
+    }
+    let root = discriminant.sqrt();
+    vec![(-b + root) / (2.0 * a), (-b - root) / (2.0 * a)]
+}
+
+/// Parameters strictly inside the curve
+fn interior(values: &[f64]) -> impl Iterator<Item = f64> + '_ {
+    values.iter().copied().filter(|t| *t > 0.0 && *t < 1.0)
+}
+
+/// Exact bounds of everything a segment list draws, `None` if it draws nothing
+pub fn path_bounds(segments: &[PathSegment]) -> Option<Rect> {
+    outline_curves(segments)
+        .iter()
+        .map(Curve::bounds)
+        .reduce(|a, b| a.union(&b))
+}
+
+impl Element {
+    /// Exact bounding box of this path's geometry, like `getBBox()`
+    pub fn path_bbox(&self) -> Option<Rect> {
+        path_bounds(self.path_segments.as_deref()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::parse_path;
+
+    fn bounds(d: &str) -> Rect {
+        path_bounds(&parse_path(d).unwrap()).unwrap()
+    }
+
+    fn close(a: Rect, b: Rect) -> bool {
+        [(a.x, b.x), (a.y, b.y), (a.width, b.width), (a.height, b.height)]
+            .iter()
+            .all(|(p, q)| (p - q).abs() < 1e-9)
+    }
+
+    #[test]
+    fn test_bezier_bounds() {
+        // Control points reach y = 100, the curve only 75
+        assert!(close(bounds("M0 0 C0 100 100 100 100 0"), Rect::new(0.0, 0.0, 100.0, 75.0)));
+        assert!(close(bounds("M0 0 Q50 100 100 0"), Rect::new(0.0, 0.0, 100.0, 50.0)));
+        // Smooth and relative segments are resolved first
+        assert!(close(bounds("M0 0 c0 100 100 100 100 0 s100 -100 100 0"), Rect::new(0.0, -75.0, 200.0, 150.0)));
+        assert!(path_bounds(&parse_path("M10 10").unwrap()).is_none());
+    }
+
+    #[test]
+    fn test_arc_bounds() {
+        // Upper half of a circle of radius 10 around (10, 0)
+        assert!(close(bounds("M0 0 A10 10 0 0 1 20 0"), Rect::new(0.0, -10.0, 20.0, 10.0)));
+        // Ellipse rotated by 45°: the full outline of rx 20, ry 10 spans sqrt((400 + 100) / 2) each way
+        let half = (250f64).sqrt();
+        let d = format!(
+            "M{} {} A20 10 45 1 1 {} {} A20 10 45 1 1 {} {}",
+            20.0 * 0.5f64.sqrt(), 20.0 * 0.5f64.sqrt(),
+            -20.0 * 0.5f64.sqrt(), -20.0 * 0.5f64.sqrt(),
+            20.0 * 0.5f64.sqrt(), 20.0 * 0.5f64.sqrt(),
+        );
+        assert!(close(bounds(&d), Rect::new(-half, -half, 2.0 * half, 2.0 * half)));
+
+        let mut svg = crate::svg::Svg::new(100, 100);
+        let path = svg.path("M0 0 H10 A5 5 0 0 0 20 0");
+        assert!(close(path.path_bbox().unwrap(), Rect::new(0.0, 0.0, 20.0, 5.0)));
+    }
+}
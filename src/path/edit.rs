@@ -0,0 +1,320 @@
+//! Cutting, reversing and joining paths
+//!
+//! Splitting and trimming work on the absolute curves of the outline (closing
+//! lines included, as in `path_length`) and write the pieces back as absolute
+//! segments. Subpaths that are kept whole stay closed; cut ones are open.
+
+use super::geometry::{resolve, Curve, Subpath};
+use super::normalize::{expanded, to_absolute, Cursor};
+use super::{PathBuilder, PathSegment};
+use crate::animation::round_value;
+use crate::math::EndpointArc;
+use crate::svg::Element;
+
+impl Curve {
+    /// The same curve traced from end to start
+    pub fn reversed(&self) -> Curve {
+        match *self {
+            Curve::Line(a, b) => Curve::Line(b, a),
+            Curve::Quad(a, b, c) => Curve::Quad(c, b, a),
+            Curve::Cubic(a, b, c, d) => Curve::Cubic(d, c, b, a),
+            Curve::Arc(arc) => Curve::Arc(EndpointArc { from: arc.to, to: arc.from, sweep: !arc.sweep, ..arc }),
+        }
+    }
+
+    /// The part between parameters `t0` and `t1`
+    pub fn between(&self, t0: f64, t1: f64) -> Curve {
+        let tail = if t0 > 0.0 { self.split(t0).1 } else { *self };
+        if t1 >= 1.0 || t0 >= 1.0 {
+            return tail;
+        }
+        tail.split((t1 - t0) / (1.0 - t0)).0
+    }
+}
+
+/// Absolute segments drawing `subpaths`
+fn to_segments(subpaths: &[Subpath]) -> Vec<PathSegment> {
+    let mut segments = Vec::new();
+    for subpath in subpaths {
+        segments.push(PathSegment::MoveTo { abs: true, x: subpath.start.x, y: subpath.start.y });
+        segments.extend(subpath.curves.iter().map(|curve| match *curve {
+            Curve::Line(_, b) => PathSegment::LineTo { abs: true, x: b.x, y: b.y },
+            Curve::Quad(_, c, b) => PathSegment::QuadTo { abs: true, x1: c.x, y1: c.y, x: b.x, y: b.y },
+            Curve::Cubic(_, c1, c2, b) => PathSegment::CubicTo { abs: true, x1: c1.x, y1: c1.y, x2: c2.x, y2: c2.y, x: b.x, y: b.y },
+            Curve::Arc(arc) => PathSegment::ArcTo {
+                abs: true,
+                rx: arc.rx,
+                ry: arc.ry,
+                x_axis_rotation: arc.x_axis_rotation,
+                large_arc: arc.large_arc,
+                sweep: arc.sweep,
+                x: arc.to.x,
+                y: arc.to.y,
+            },
+        }));
+        if subpath.closed {
+            segments.push(PathSegment::Close { abs: true });
+        }
+    }
+    segments.iter_mut().for_each(|segment| *segment = round_segment(segment));
+    segments
+}
+
+fn round_segment(segment: &PathSegment) -> PathSegment {
+    let coords: Vec<f64> = segment.coords().iter().map(|&value| round_value(value)).collect();
+    PathSegment::from_command(segment.command(), &coords).unwrap_or(*segment)
+}
+
+/// The parts of `segments` between lengths `start` and `end` along the path
+fn slice(segments: &[PathSegment], start: f64, end: f64) -> Vec<PathSegment> {
+    let mut pieces = Vec::new();
+    let mut offset = 0.0;
+    for subpath in resolve(segments) {
+        let length = subpath.length();
+        let (from, to) = (start - offset, end - offset);
+        if from <= 0.0 && to >= length {
+            pieces.push(subpath);
+        } else {
+            let mut piece: Option<Subpath> = None;
+            let mut before = 0.0;
+            for curve in subpath.outline() {
+                let curve_length = curve.length();
+                let (a, b) = ((from - before).max(0.0), (to - before).min(curve_length));
+                if b > a {
+                    let part = curve.between(curve.parameter_at(a), curve.parameter_at(b));
+                    piece.get_or_insert_with(|| Subpath { start: part.point(0.0), curves: Vec::new(), closed: false }).curves.push(part);
+                }
+                before += curve_length;
+            }
+            pieces.extend(piece);
+        }
+        offset += length;
+    }
+    to_segments(&pieces)
+}
+
+/// The path up to `length` and the rest
+pub fn split_segments(segments: &[PathSegment], length: f64) -> (Vec<PathSegment>, Vec<PathSegment>) {
+    (slice(segments, f64::NEG_INFINITY, length), slice(segments, length, f64::INFINITY))
+}
+
+/// The part of the path between lengths `start` and `end`
+pub fn trim_segments(segments: &[PathSegment], start: f64, end: f64) -> Vec<PathSegment> {
+    slice(segments, start, end)
+}
+
+/// Each subpath on its own, starting with an absolute move; other segments
+/// keep their form
+pub fn split_subpaths(segments: &[PathSegment]) -> Vec<Vec<PathSegment>> {
+    let mut subpaths: Vec<Vec<PathSegment>> = Vec::new();
+    let mut cursor = Cursor::new();
+    let mut closed = false;
+    for segment in segments {
+        let absolute = cursor.absolute(segment);
+        match absolute {
+            PathSegment::MoveTo { .. } => subpaths.push(vec![absolute]),
+            // Drawing on after a close starts a new subpath at the same point
+            _ if closed || subpaths.is_empty() => {
+                let start = cursor.position;
+                subpaths.push(vec![PathSegment::MoveTo { abs: true, x: start.x, y: start.y }, *segment]);
+            }
+            _ => subpaths.last_mut().unwrap().push(*segment),
+        }
+        closed = matches!(segment, PathSegment::Close { .. });
+        cursor.advance(&absolute);
+    }
+    subpaths
+}
+
+/// The same geometry traced backwards: subpaths in reverse order, each from
+/// its end to its start; closed subpaths keep their start point
+pub fn reverse_segments(segments: &[PathSegment]) -> Vec<PathSegment> {
+    let subpaths: Vec<Subpath> = resolve(segments)
+        .into_iter()
+        .rev()
+        .map(|subpath| {
+            if subpath.closed {
+                let mut curves: Vec<Curve> = subpath.outline().iter().rev().map(Curve::reversed).collect();
+                // `Z` draws the final line back to the start
+                if curves.len() > 1 && matches!(curves.last(), Some(Curve::Line(..))) {
+                    curves.pop();
+                }
+                Subpath { start: subpath.start, curves, closed: true }
+            } else {
+                let curves = subpath.curves.iter().rev().map(Curve::reversed).collect();
+                Subpath { start: subpath.end(), curves, closed: false }
+            }
+        })
+        .collect();
+    to_segments(&subpaths)
+}
+
+/// `a` followed by `b`, connected with a line from the end of `a` to the start
+/// of `b` instead of `b`'s first move. `b` is written in absolute coordinates
+/// so that it keeps its geometry: a leading `S` or `T` is expanded rather than
+/// reflecting a control point of `a`, and closing `b`'s first subpath, which
+/// now starts on `a`, becomes a line back to `b`'s start.
+pub fn join_segments(a: &[PathSegment], b: &[PathSegment]) -> Vec<PathSegment> {
+    let b = to_absolute(b);
+    let Some(&PathSegment::MoveTo { x, y, .. }) = b.first() else {
+        return [a, &b].concat();
+    };
+    if a.is_empty() {
+        return b;
+    }
+    let mut joined = a.to_vec();
+    let mut cursor = Cursor::new();
+    for segment in a {
+        cursor.advance(&cursor.absolute(segment));
+    }
+    if (x, y) != (cursor.position.x, cursor.position.y) {
+        joined.push(PathSegment::LineTo { abs: true, x, y });
+    }
+    let mut cursor = Cursor::new();
+    cursor.advance(&b[0]);
+    let mut first_subpath = true;
+    for (index, segment) in b.iter().enumerate().skip(1) {
+        joined.push(match *segment {
+            PathSegment::MoveTo { .. } => {
+                first_subpath = false;
+                *segment
+            }
+            PathSegment::Close { .. } if first_subpath => PathSegment::LineTo { abs: true, x, y },
+            PathSegment::SmoothCubicTo { .. } | PathSegment::SmoothQuadTo { .. } if index == 1 => expanded(&cursor, *segment),
+            _ => *segment,
+        });
+        cursor.advance(segment);
+    }
+    joined
+}
+
+impl Element {
+    /// A detached copy of this path drawing `segments`, without the `id`
+    fn with_segments(&self, segments: Vec<PathSegment>) -> Element {
+        let mut path = self.clone();
+        path.attributes.remove("id");
+        path.path_segments = Some(segments);
+        path.redraw();
+        path
+    }
+
+    fn edit_path(&mut self, edit: impl FnOnce(&[PathSegment]) -> Vec<PathSegment>) -> &mut Self {
+        if let Some(segments) = self.path_segments.as_mut() {
+            *segments = edit(segments);
+            self.redraw();
+        }
+        self
+    }
+
+    /// Two detached copies of this path: up to `length`, and the rest
+    pub fn split_at_length(&self, length: f64) -> (Element, Element) {
+        let (first, second) = split_segments(self.path_segments.as_deref().unwrap_or_default(), length);
+        (self.with_segments(first), self.with_segments(second))
+    }
+
+    /// Keep only the part between lengths `start` and `end`
+    pub fn trim(&mut self, start: f64, end: f64) -> &mut Self {
+        self.edit_path(|segments| trim_segments(segments, start, end))
+    }
+
+    /// Keep only the part between `start` and `end` as fractions (0 to 1) of the length
+    pub fn trim_normalized(&mut self, start: f64, end: f64) -> &mut Self {
+        let length = self.path_length();
+        self.trim(start * length, end * length)
+    }
+
+    /// Detached copies of this path, one per subpath
+    pub fn subpaths(&self) -> Vec<Element> {
+        split_subpaths(self.path_segments.as_deref().unwrap_or_default())
+            .into_iter()
+            .map(|segments| self.with_segments(segments))
+            .collect()
+    }
+
+    /// Trace this path in the opposite direction
+    pub fn reverse(&mut self) -> &mut Self {
+        self.edit_path(reverse_segments)
+    }
+
+    /// Continue this path with `other`, drawing a line from this path's end
+    /// to the start of `other`; see `join_segments`
+    pub fn join(&mut self, other: &Element) -> &mut Self {
+        let other = other.path_segments.clone().unwrap_or_default();
+        if self.path_segments.is_none() {
+            self.path_segments = Some(Vec::new());
+        }
+        self.edit_path(|segments| join_segments(segments, &other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::{parse_path, path_bounds, path_data, path_length};
+    use crate::svg::Svg;
+
+    fn apply(d: &str, edit: impl FnOnce(&[PathSegment]) -> Vec<PathSegment>) -> String {
+        path_data(&edit(&parse_path(d).unwrap()))
+    }
+
+    #[test]
+    fn test_split_and_trim() {
+        let (first, second) = split_segments(&parse_path("M0 0H10V10").unwrap(), 15.0);
+        assert_eq!((path_data(&first), path_data(&second)), ("M0 0L10 0 10 5".to_string(), "M10 5L10 10".to_string()));
+        // Cutting a closed subpath opens it, untouched ones stay closed
+        assert_eq!(apply("M0 0H10V10H0Z M20 0H30", |s| trim_segments(s, 35.0, 45.0)), "M0 5L0 0M20 0L25 0");
+        assert_eq!(apply("M0 0H10V10H0Z", |s| trim_segments(s, -1.0, 100.0)), "M0 0L10 0 10 10 0 10Z");
+        // Curves are cut at the right length
+        let curve = parse_path("M0 0C0 50 100 50 100 0").unwrap();
+        let (head, tail) = split_segments(&curve, path_length(&curve) / 3.0);
+        assert!((path_length(&head) * 2.0 - path_length(&tail)).abs() < 1e-3);
+        let arc = parse_path("M0 0A10 10 0 0 1 20 0").unwrap();
+        let quarter = trim_segments(&arc, 0.0, path_length(&arc) / 2.0);
+        assert_eq!(path_data(&quarter), "M0 0A10 10 0 0110-10");
+
+        let mut svg = Svg::new(100, 100);
+        let path = svg.path("M0 0H100");
+        path.trim_normalized(0.25, 0.5);
+        assert_eq!(path.attributes.get("d").unwrap(), "M25 0L50 0");
+    }
+
+    #[test]
+    fn test_subpaths() {
+        let parts: Vec<String> = split_subpaths(&parse_path("M0 0h10z l5 5 m10 0 h5").unwrap()).iter().map(|s| path_data(s)).collect();
+        assert_eq!(parts, vec!["M0 0h10z", "M0 0l5 5", "M15 5h5"]);
+        let mut svg = Svg::new(100, 100);
+        let path = svg.path("M0 0H10 M20 0H30").id("route").stroke("red").clone();
+        let pieces = path.subpaths();
+        assert_eq!(pieces.len(), 2);
+        assert_eq!(pieces[1].attributes.get("d").unwrap(), "M20 0H30");
+        assert_eq!(pieces[1].attributes.get("stroke").unwrap(), "red");
+        assert!(!pieces[1].attributes.contains_key("id"));
+    }
+
+    #[test]
+    fn test_reverse_and_join() {
+        assert_eq!(apply("M0 0L10 0Q20 0 20 10C20 20 10 20 0 20", reverse_segments), "M0 20C10 20 20 20 20 10Q20 0 10 0L0 0");
+        assert_eq!(apply("M0 0H10V10Z M20 0A5 5 0 0 1 30 0", reverse_segments), "M30 0A5 5 0 0020 0M0 0L10 10 10 0Z");
+        // Reversing twice gives the same geometry
+        let once = apply("M0 0H10V10H0Z", reverse_segments);
+        assert_eq!(apply(&once, reverse_segments), "M0 0L10 0 10 10 0 10Z");
+
+        assert_eq!(apply("M0 0H10", |a| join_segments(a, &parse_path("M20 0h10").unwrap())), "M0 0H10L20 0H30");
+        assert_eq!(apply("M0 0H10", |a| join_segments(a, &parse_path("m10 0 v10").unwrap())), "M0 0H10V10");
+        // A closed first subpath still closes at its own start, and later
+        // relative moves keep their position
+        let closed = parse_path("M20 0h10v10z m5 5h1").unwrap();
+        assert_eq!(apply("M0 0H10", |a| join_segments(a, &closed)), "M0 0H10L20 0H30V10L20 0M25 5H26");
+        // A leading smooth curve keeps its own first control point
+        let smooth = parse_path("M10 0S20-10 30 0").unwrap();
+        let joined = join_segments(&parse_path("M0 0C0 10 10 10 10 0").unwrap(), &smooth);
+        assert_eq!(path_data(&joined[2..]), "C10 0 20-10 30 0");
+        let own = path_bounds(&smooth).unwrap().y;
+        assert!((path_bounds(&joined).unwrap().y - own).abs() < 1e-9);
+        let mut svg = Svg::new(100, 100);
+        let other = svg.path("M5 5L10 10").clone();
+        let path = svg.path("M0 0L5 5");
+        path.join(&other).reverse();
+        assert_eq!(path.attributes.get("d").unwrap(), "M10 10L5 5 0 0");
+    }
+}
@@ -0,0 +1,274 @@
+//! Simplifying and fitting sampled points
+//!
+//! Ramer–Douglas–Peucker keeps the fewest input points such that the polyline
+//! stays within a tolerance of every dropped point. The curve fitter follows
+//! Schneider's algorithm (Graphics Gems, 1990): a least-squares cubic with
+//! fixed end tangents, refined by Newton reparameterization, and split at the
+//! worst point with a shared tangent when it does not fit.
+
+use super::geometry::Curve;
+use super::{PathBuilder, PathSegment};
+use crate::animation::round_value;
+use crate::math::Point;
+use crate::svg::Element;
+
+/// Distance from `p` to the infinite line through `a` and `b`, or to `a` if they coincide
+fn line_distance(p: Point, a: Point, b: Point) -> f64 {
+    let length = a.distance_to(&b);
+    if length == 0.0 {
+        return p.distance_to(&a);
+    }
+    b.sub(&a).cross(&p.sub(&a)).abs() / length
+}
+
+/// The subset of `points` whose polyline is within `tolerance` of every point
+/// (Ramer–Douglas–Peucker); the first and last points are always kept
+pub fn simplify_points(points: &[Point], tolerance: f64) -> Vec<Point> {
+    if points.len() < 3 {
+        return points.to_vec();
+    }
+    let mut keep = vec![false; points.len()];
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+    let mut spans = vec![(0, points.len() - 1)];
+    while let Some((first, last)) = spans.pop() {
+        let farthest = (first + 1..last)
+            .map(|index| (index, line_distance(points[index], points[first], points[last])))
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((index, distance)) = farthest {
+            if distance > tolerance {
+                keep[index] = true;
+                spans.push((first, index));
+                spans.push((index, last));
+            }
+        }
+    }
+    points.iter().zip(keep).filter_map(|(point, kept)| kept.then_some(*point)).collect()
+}
+
+fn cubic(curve: &[Point; 4]) -> Curve {
+    Curve::Cubic(curve[0], curve[1], curve[2], curve[3])
+}
+
+/// Cubic Bézier control points passing near `points` within `tolerance`, joined
+/// with matching tangents so the result is smooth
+pub fn fit_cubics(points: &[Point], tolerance: f64) -> Vec<[Point; 4]> {
+    let mut points = points.to_vec();
+    points.dedup();
+    if points.len() < 2 {
+        return Vec::new();
+    }
+    let last = points.len() - 1;
+    let start_tangent = points[1].sub(&points[0]).normalized();
+    let end_tangent = points[last - 1].sub(&points[last]).normalized();
+    let mut curves = Vec::new();
+    fit(&points, start_tangent, end_tangent, tolerance, &mut curves);
+    curves
+}
+
+/// Fit `points` with one cubic leaving along `start` and arriving against `end`, or split
+fn fit(points: &[Point], start: Point, end: Point, tolerance: f64, curves: &mut Vec<[Point; 4]>) {
+    let (first, last) = (points[0], points[points.len() - 1]);
+    if points.len() == 2 {
+        let third = first.distance_to(&last) / 3.0;
+        curves.push([first, first.add(&start.scale(third)), last.add(&end.scale(third)), last]);
+        return;
+    }
+
+    let mut parameters = chord_lengths(points);
+    let mut curve = least_squares(points, &parameters, start, end);
+    let (error, mut split) = max_error(points, &curve, &parameters);
+    if error <= tolerance {
+        curves.push(curve);
+        return;
+    }
+    // Chord-length parameters are only a first guess; refine them before splitting
+    for _ in 0..4 {
+        parameters = reparameterize(points, &curve, &parameters);
+        curve = least_squares(points, &parameters, start, end);
+        let (error, worst) = max_error(points, &curve, &parameters);
+        split = worst;
+        if error <= tolerance {
+            curves.push(curve);
+            return;
+        }
+    }
+
+    // Split at the worst point, continuing through it along the local chord
+    let center = points[split - 1].sub(&points[split + 1]);
+    let center = if center.dot(&center) == 0.0 { points[split - 1].sub(&points[split]).normalized() } else { center.normalized() };
+    fit(&points[..=split], start, center, tolerance, curves);
+    fit(&points[split..], center.scale(-1.0), end, tolerance, curves);
+}
+
+/// Parameters proportional to the distance along the polyline
+fn chord_lengths(points: &[Point]) -> Vec<f64> {
+    let mut parameters = vec![0.0];
+    for pair in points.windows(2) {
+        parameters.push(parameters.last().unwrap() + pair[0].distance_to(&pair[1]));
+    }
+    let total = *parameters.last().unwrap();
+    parameters.iter().map(|length| length / total).collect()
+}
+
+/// The cubic with the given end tangents that best fits `points` at `parameters`
+fn least_squares(points: &[Point], parameters: &[f64], start: Point, end: Point) -> [Point; 4] {
+    let (first, last) = (points[0], points[points.len() - 1]);
+    let (mut c00, mut c01, mut c11, mut x0, mut x1) = (0.0, 0.0, 0.0, 0.0, 0.0);
+    for (point, &u) in points.iter().zip(parameters) {
+        let v = 1.0 - u;
+        let (b0, b1, b2, b3) = (v * v * v, 3.0 * u * v * v, 3.0 * u * u * v, u * u * u);
+        let (a1, a2) = (start.scale(b1), end.scale(b2));
+        c00 += a1.dot(&a1);
+        c01 += a1.dot(&a2);
+        c11 += a2.dot(&a2);
+        let shifted = point.sub(&Point::new(first.x * (b0 + b1) + last.x * (b2 + b3), first.y * (b0 + b1) + last.y * (b2 + b3)));
+        x0 += a1.dot(&shifted);
+        x1 += a2.dot(&shifted);
+    }
+    let determinant = c00 * c11 - c01 * c01;
+    let (mut alpha_start, mut alpha_end) =
+        if determinant == 0.0 { (0.0, 0.0) } else { ((x0 * c11 - x1 * c01) / determinant, (c00 * x1 - c01 * x0) / determinant) };
+    // Degenerate or backwards handles fall back to a third of the chord
+    let chord = first.distance_to(&last);
+    if alpha_start < 1e-6 * chord || alpha_end < 1e-6 * chord {
+        (alpha_start, alpha_end) = (chord / 3.0, chord / 3.0);
+    }
+    [first, first.add(&start.scale(alpha_start)), last.add(&end.scale(alpha_end)), last]
+}
+
+/// Largest distance from a point to the curve at its parameter, and that point's index
+fn max_error(points: &[Point], curve: &[Point; 4], parameters: &[f64]) -> (f64, usize) {
+    let curve = cubic(curve);
+    let mut worst = (0.0, points.len() / 2);
+    for index in 1..points.len() - 1 {
+        let distance = curve.point(parameters[index]).distance_to(&points[index]);
+        if distance > worst.0 {
+            worst = (distance, index);
+        }
+    }
+    worst
+}
+
+/// One Newton step per point towards the parameter of its nearest curve point
+fn reparameterize(points: &[Point], control: &[Point; 4], parameters: &[f64]) -> Vec<f64> {
+    let curve = cubic(control);
+    let [p0, p1, p2, p3] = *control;
+    points
+        .iter()
+        .zip(parameters)
+        .map(|(point, &u)| {
+            let offset = curve.point(u).sub(point);
+            let first = curve.derivative(u);
+            let second = Point::new(
+                6.0 * ((1.0 - u) * (p2.x - 2.0 * p1.x + p0.x) + u * (p3.x - 2.0 * p2.x + p1.x)),
+                6.0 * ((1.0 - u) * (p2.y - 2.0 * p1.y + p0.y) + u * (p3.y - 2.0 * p2.y + p1.y)),
+            );
+            let denominator = first.dot(&first) + offset.dot(&second);
+            if denominator == 0.0 { u } else { (u - offset.dot(&first) / denominator).clamp(0.0, 1.0) }
+        })
+        .collect()
+}
+
+/// `M` followed by smooth cubic segments through `points` within `tolerance`
+pub fn fit_path(points: &[Point], tolerance: f64) -> Vec<PathSegment> {
+    let curves = fit_cubics(points, tolerance);
+    let Some(start) = curves.first().map(|curve| curve[0]) else {
+        return Vec::new();
+    };
+    let mut segments = vec![PathSegment::MoveTo { abs: true, x: round_value(start.x), y: round_value(start.y) }];
+    segments.extend(curves.iter().map(|[_, c1, c2, end]| PathSegment::CubicTo {
+        abs: true,
+        x1: round_value(c1.x),
+        y1: round_value(c1.y),
+        x2: round_value(c2.x),
+        y2: round_value(c2.y),
+        x: round_value(end.x),
+        y: round_value(end.y),
+    }));
+    segments
+}
+
+impl Element {
+    /// Run `draw` with the path builder, redrawing once at the end
+    pub(super) fn draw_segments(&mut self, draw: impl FnOnce(&mut Self)) -> &mut Self {
+        let auto_redraw = self.auto_redraw;
+        self.auto_redraw = false;
+        draw(self);
+        self.auto_redraw = auto_redraw;
+        self.redraw()
+    }
+
+    /// Append a polyline through the RDP-simplified `points`
+    pub fn draw_simplified(&mut self, points: &[Point], tolerance: f64) -> &mut Self {
+        let points = simplify_points(points, tolerance);
+        self.draw_segments(|path| {
+            for (index, p) in points.iter().enumerate() {
+                if index == 0 { path.M(p.x, p.y) } else { path.L(p.x, p.y) };
+            }
+        })
+    }
+
+    /// Append smooth cubic curves within `tolerance` of every one of `points`
+    pub fn draw_fitted(&mut self, points: &[Point], tolerance: f64) -> &mut Self {
+        let curves = fit_cubics(points, tolerance);
+        self.draw_segments(|path| {
+            if let Some(start) = curves.first().map(|curve| curve[0]) {
+                path.M(round_value(start.x), round_value(start.y));
+            }
+            for [_, c1, c2, end] in &curves {
+                let r = round_value;
+                path.C(r(c1.x), r(c1.y), r(c2.x), r(c2.y), r(end.x), r(end.y));
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::tests::points;
+    use crate::svg::Svg;
+
+    #[test]
+    fn test_simplify_points() {
+        let zigzag = points(&[(0.0, 0.0), (1.0, 0.1), (2.0, -0.1), (3.0, 5.0), (4.0, 6.0), (5.0, 7.0), (6.0, 8.1), (7.0, 9.0)]);
+        assert_eq!(simplify_points(&zigzag, 0.5), points(&[(0.0, 0.0), (2.0, -0.1), (3.0, 5.0), (7.0, 9.0)]));
+        assert_eq!(simplify_points(&zigzag, 100.0).len(), 2);
+        assert_eq!(simplify_points(&zigzag[..2], 0.5).len(), 2);
+    }
+
+    #[test]
+    fn test_fit_cubics() {
+        // Samples of a half circle fit with a few cubics, every sample within tolerance
+        let samples: Vec<Point> = (0..=200)
+            .map(|i| {
+                let angle = std::f64::consts::PI * i as f64 / 200.0;
+                Point::new(50.0 - 50.0 * angle.cos(), 50.0 * angle.sin())
+            })
+            .collect();
+        let curves = fit_cubics(&samples, 0.1);
+        assert!(!curves.is_empty() && curves.len() <= 4);
+        let curves: Vec<Curve> = curves.iter().map(cubic).collect();
+        for sample in &samples {
+            let nearest = curves.iter().map(|curve| curve.point(curve.nearest_parameter(sample)).distance_to(sample)).fold(f64::MAX, f64::min);
+            assert!(nearest <= 0.1 + 1e-9);
+        }
+        // Collinear samples give a single straight cubic
+        assert_eq!(fit_cubics(&points(&[(0.0, 0.0), (5.0, 5.0), (10.0, 10.0)]), 0.01).len(), 1);
+        assert!(fit_path(&points(&[(1.0, 1.0)]), 0.1).is_empty());
+    }
+
+    #[test]
+    fn test_draw_from_points() {
+        let mut svg = Svg::new(100, 100);
+        let trace = points(&[(0.0, 0.0), (5.0, 0.01), (10.0, 0.0), (10.0, 10.0)]);
+        let path = svg.path("");
+        path.draw_simplified(&trace, 0.1);
+        assert_eq!(path.attributes.get("d").unwrap(), "M0 0L10 0 10 10");
+        let path = svg.path("");
+        path.draw_fitted(&trace[..3], 0.1);
+        assert_eq!(path.get_segment_count(), 2);
+        assert_eq!(path.get_segment(1).unwrap().command(), 'C');
+    }
+}
@@ -0,0 +1,97 @@
+//! Flattening paths to polylines
+//!
+//! Curves and arcs are subdivided adaptively: a span is halved until the
+//! chord stays within the tolerance of the curve, so flat stretches get few
+//! points and tight bends many.
+
+use std::collections::HashMap;
+
+use super::geometry::resolve;
+use super::PathSegment;
+use crate::animation::round_value;
+use crate::math::Point;
+use crate::svg::Element;
+
+/// Every subpath as a list of points whose chords stay within `tolerance` of
+/// the path; a closed subpath ends with its start point repeated
+pub fn flatten_segments(segments: &[PathSegment], tolerance: f64) -> Vec<Vec<Point>> {
+    resolve(segments)
+        .iter()
+        .map(|subpath| {
+            let mut points = vec![subpath.start];
+            for curve in subpath.outline() {
+                curve.flatten(tolerance, &mut points);
+            }
+            points
+        })
+        .collect()
+}
+
+impl Element {
+    /// This path's subpaths as points within `tolerance` of the curves; see `flatten_segments`
+    pub fn flatten(&self, tolerance: f64) -> Vec<Vec<Point>> {
+        self.path_segments.as_deref().map(|segments| flatten_segments(segments, tolerance)).unwrap_or_default()
+    }
+
+    /// One detached `polygon` per closed subpath and `polyline` per open one,
+    /// within `tolerance` of this path and carrying its attributes except `d` and `id`
+    pub fn to_polylines(&self, tolerance: f64) -> Vec<Element> {
+        let closed: Vec<bool> = self.path_segments.as_deref().map(|segments| resolve(segments).iter().map(|s| s.closed).collect()).unwrap_or_default();
+        self.flatten(tolerance)
+            .into_iter()
+            .zip(closed)
+            .map(|(mut points, closed)| {
+                if closed && points.len() > 1 {
+                    points.pop();
+                }
+                let mut attrs: HashMap<String, String> =
+                    self.attributes.iter().filter(|(name, _)| !matches!(name.as_str(), "d" | "id")).map(|(k, v)| (k.clone(), v.clone())).collect();
+                let list = points.iter().map(|p| format!("{},{}", round_value(p.x), round_value(p.y))).collect::<Vec<_>>().join(" ");
+                attrs.insert("points".to_string(), list);
+                Element::new(if closed { "polygon" } else { "polyline" }, attrs)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::parse_path;
+    use crate::svg::Svg;
+
+    #[test]
+    fn test_flatten_segments() {
+        let lines = flatten_segments(&parse_path("M0 0H10V10Z M20 0L30 0").unwrap(), 0.1);
+        assert_eq!(lines, vec![
+            vec![Point::new(0.0, 0.0), Point::new(10.0, 0.0), Point::new(10.0, 10.0), Point::new(0.0, 0.0)],
+            vec![Point::new(20.0, 0.0), Point::new(30.0, 0.0)],
+        ]);
+
+        // A circle gets more points at a finer tolerance, all on the circle
+        let circle = parse_path("M10 0A10 10 0 0 1-10 0A10 10 0 0 1 10 0Z").unwrap();
+        let coarse = &flatten_segments(&circle, 0.5)[0];
+        let fine = &flatten_segments(&circle, 0.01)[0];
+        assert!(coarse.len() < fine.len());
+        assert!(fine.iter().all(|p| (p.x.hypot(p.y) - 10.0).abs() < 1e-9));
+        // Midpoints of the chords stay within the tolerance
+        for pair in coarse.windows(2) {
+            let middle = Point::new((pair[0].x + pair[1].x) / 2.0, (pair[0].y + pair[1].y) / 2.0);
+            assert!(10.0 - middle.x.hypot(middle.y) <= 0.5);
+        }
+    }
+
+    #[test]
+    fn test_to_polylines() {
+        let mut svg = Svg::new(100, 100);
+        let path = svg.path("M0 0H10V10Z M20 0Q25 10 30 0").stroke("red").id("route").clone();
+        let shapes = path.to_polylines(0.1);
+        assert_eq!(shapes.len(), 2);
+        assert_eq!(shapes[0].tag, "polygon");
+        assert_eq!(shapes[0].attributes.get("points").unwrap(), "0,0 10,0 10,10");
+        assert_eq!(shapes[0].attributes.get("stroke").unwrap(), "red");
+        assert!(!shapes[0].attributes.contains_key("id"));
+        assert_eq!(shapes[1].tag, "polyline");
+        assert!(shapes[1].attributes.get("points").unwrap().ends_with("30,0"));
+    }
+}
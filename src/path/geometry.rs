@@ -0,0 +1,378 @@
+//! Absolute geometry of path segments
+//!
+//! Path segments are resolved into absolute primitives (lines, quadratic and
+//! cubic Béziers, elliptical arcs) grouped by subpath. Relative coordinates,
+//! `H`/`V` and the reflected control points of `S`/`T` are resolved here so
+//! that the geometric operations only deal with these four shapes.
+
+use std::f64::consts::FRAC_PI_2;
+
+use super::PathSegment;
+use crate::math::{CenterArc, EndpointArc, Point};
+
+/// An absolute drawing primitive
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Curve {
+    Line(Point, Point),
+    Quad(Point, Point, Point),
+    Cubic(Point, Point, Point, Point),
+    Arc(EndpointArc),
+}
+
+/// A connected run of curves starting with a move
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Subpath {
+    pub start: Point,
+    pub curves: Vec<Curve>,
+    pub closed: bool,
+}
+
+pub(crate) fn lerp(a: Point, b: Point, t: f64) -> Point {
+    Point::new(a.x + (b.x - a.x) * t, a.y + (b.y - a.y) * t)
+}
+
+impl CenterArc {
+    /// Number of equal pieces, at least one per quarter turn, whose cubic
+    /// approximations stay within `tolerance` of the arc
+    pub(crate) fn pieces(&self, tolerance: f64) -> usize {
+        const MAX_PIECES: usize = 1024;
+        let quarters = (self.sweep_angle.abs() / FRAC_PI_2 - 1e-9).ceil().max(1.0) as usize;
+        let radius = self.rx.max(self.ry);
+        (quarters..MAX_PIECES)
+            .find(|&pieces| {
+                // Radial error bound of the standard approximation of a circular arc
+                let quarter = self.sweep_angle.abs() / pieces as f64 / 4.0;
+                radius * 4.0 / 27.0 * quarter.sin().powi(6) / quarter.cos().powi(2) <= tolerance
+            })
+            .unwrap_or(MAX_PIECES)
+    }
+
+    /// Cubic approximation in `pieces` equal steps, with the end points pinned to `from` and `to`
+    pub(crate) fn cubics(&self, from: Point, to: Point, pieces: usize) -> Vec<[Point; 4]> {
+        let step = self.sweep_angle / pieces as f64;
+        let mut cubics: Vec<[Point; 4]> = (0..pieces)
+            .map(|i| {
+                let angle = self.start_angle + step * i as f64;
+                self.cubic(angle, angle + step)
+            })
+            .collect();
+        cubics[0][0] = from;
+        cubics[pieces - 1][3] = to;
+        cubics
+    }
+
+    /// Cubic approximation of the angular range `from..to` (at most a quarter turn)
+    pub(crate) fn cubic(&self, from: f64, to: f64) -> [Point; 4] {
+        let k = 4.0 / 3.0 * ((to - from) / 4.0).tan();
+        let (p0, p3) = (self.point(from), self.point(to));
+        let (d0, d3) = (self.derivative(from), self.derivative(to));
+        [
+            p0,
+            Point::new(p0.x + k * d0.x, p0.y + k * d0.y),
+            Point::new(p3.x - k * d3.x, p3.y - k * d3.y),
+            p3,
+        ]
+    }
+}
+
+impl Curve {
+    pub fn end(&self) -> Point {
+        match *self {
+            Curve::Line(_, p) | Curve::Quad(_, _, p) | Curve::Cubic(_, _, _, p) => p,
+            Curve::Arc(arc) => arc.to,
+        }
+    }
+
+    /// Point at parameter `t` (0 to 1); arcs are parameterized by angle
+    pub fn point(&self, t: f64) -> Point {
+        match *self {
+            Curve::Line(a, b) => lerp(a, b, t),
+            Curve::Quad(a, b, c) => lerp(lerp(a, b, t), lerp(b, c, t), t),
+            Curve::Cubic(a, b, c, d) => {
+                let (ab, bc, cd) = (lerp(a, b, t), lerp(b, c, t), lerp(c, d, t));
+                lerp(lerp(ab, bc, t), lerp(bc, cd, t), t)
+            }
+            Curve::Arc(arc) => match arc.to_center() {
+                Some(center) => center.point(center.start_angle + center.sweep_angle * t),
+                None => lerp(arc.from, arc.to, t),
+            },
+        }
+    }
+
+    /// Derivative with respect to `t`
+    pub fn derivative(&self, t: f64) -> Point {
+        let diff = |a: Point, b: Point, k: f64| Point::new((b.x - a.x) * k, (b.y - a.y) * k);
+        match *self {
+            Curve::Line(a, b) => diff(a, b, 1.0),
+            Curve::Quad(a, b, c) => lerp(diff(a, b, 2.0), diff(b, c, 2.0), t),
+            Curve::Cubic(a, b, c, d) => {
+                let (u, v, w) = (diff(a, b, 3.0), diff(b, c, 3.0), diff(c, d, 3.0));
+                lerp(lerp(u, v, t), lerp(v, w, t), t)
+            }
+            Curve::Arc(arc) => match arc.to_center() {
+                Some(center) => {
+                    let d = center.derivative(center.start_angle + center.sweep_angle * t);
+                    Point::new(d.x * center.sweep_angle, d.y * center.sweep_angle)
+                }
+                None => diff(arc.from, arc.to, 1.0),
+            },
+        }
+    }
+
+    /// Split at parameter `t` into two curves of the same kind
+    pub fn split(&self, t: f64) -> (Curve, Curve) {
+        match *self {
+            Curve::Line(a, b) => {
+                let m = lerp(a, b, t);
+                (Curve::Line(a, m), Curve::Line(m, b))
+            }
+            Curve::Quad(a, b, c) => {
+                let (ab, bc) = (lerp(a, b, t), lerp(b, c, t));
+                let m = lerp(ab, bc, t);
+                (Curve::Quad(a, ab, m), Curve::Quad(m, bc, c))
+            }
+            Curve::Cubic(a, b, c, d) => {
+                let (ab, bc, cd) = (lerp(a, b, t), lerp(b, c, t), lerp(c, d, t));
+                let (abc, bcd) = (lerp(ab, bc, t), lerp(bc, cd, t));
+                let m = lerp(abc, bcd, t);
+                (Curve::Cubic(a, ab, abc, m), Curve::Cubic(m, bcd, cd, d))
+            }
+            Curve::Arc(arc) => match arc.to_center() {
+                Some(center) => {
+                    let m = center.point(center.start_angle + center.sweep_angle * t);
+                    let first = center.sweep_angle.abs() * t;
+                    let second = center.sweep_angle.abs() * (1.0 - t);
+                    let part = |from: Point, to: Point, angle: f64| Curve::Arc(EndpointArc {
+                        from,
+                        rx: center.rx,
+                        ry: center.ry,
+                        large_arc: angle > std::f64::consts::PI,
+                        to,
+                        ..arc
+                    });
+                    (part(arc.from, m, first), part(m, arc.to, second))
+                }
+                None => {
+                    let m = lerp(arc.from, arc.to, t);
+                    (Curve::Line(arc.from, m), Curve::Line(m, arc.to))
+                }
+            },
+        }
+    }
+
+    /// Cubic Bézier control points representing this curve; arcs are
+    /// approximated with one cubic per quarter turn
+    pub fn to_cubics(self) -> Vec<[Point; 4]> {
+        match self {
+            Curve::Line(a, b) => vec![[a, lerp(a, b, 1.0 / 3.0), lerp(a, b, 2.0 / 3.0), b]],
+            Curve::Quad(a, b, c) => vec![[a, lerp(a, b, 2.0 / 3.0), lerp(c, b, 2.0 / 3.0), c]],
+            Curve::Cubic(a, b, c, d) => vec![[a, b, c, d]],
+            Curve::Arc(arc) => match arc.to_center() {
+                Some(center) => center.cubics(arc.from, arc.to, center.pieces(f64::INFINITY)),
+                None => Curve::Line(arc.from, arc.to).to_cubics(),
+            },
+        }
+    }
+
+    /// Rough length from the chord and control polygon
+    pub fn estimated_length(&self) -> f64 {
+        let polygon: f64 = self.to_cubics().iter()
+            .map(|[a, b, c, d]| {
+                let net = a.distance_to(b) + b.distance_to(c) + c.distance_to(d);
+                (net + a.distance_to(d)) / 2.0
+            })
+            .sum();
+        polygon
+    }
+
+    /// Append points approximating this curve to within `tolerance`,
+    /// excluding the start point
+    pub fn flatten(&self, tolerance: f64, points: &mut Vec<Point>) {
+        if let Curve::Line(_, b) = *self {
+            points.push(b);
+            return;
+        }
+        let (mut t0, mut from) = (0.0, self.point(0.0));
+        // Right ends of the spans still to emit, nearest last
+        let mut pending = vec![(1.0, self.point(1.0))];
+        while let Some(&(t1, to)) = pending.last() {
+            // Quarter points catch S-shaped spans whose midpoint lies on the chord
+            let flat = t1 - t0 < 1e-5
+                || [0.25, 0.5, 0.75].iter().all(|k| chord_distance(self.point(t0 + (t1 - t0) * k), from, to) <= tolerance);
+            if flat {
+                points.push(to);
+                pending.pop();
+                (t0, from) = (t1, to);
+            } else {
+                let t = (t0 + t1) / 2.0;
+                pending.push((t, self.point(t)));
+            }
+        }
+    }
+}
+
+/// Distance from `p` to the segment `a`–`b`
+fn chord_distance(p: Point, a: Point, b: Point) -> f64 {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let squared = dx * dx + dy * dy;
+    let t = if squared == 0.0 { 0.0 } else { (((p.x - a.x) * dx + (p.y - a.y) * dy) / squared).clamp(0.0, 1.0) };
+    p.distance_to(&lerp(a, b, t))
+}
+
+impl Subpath {
+    /// End point of the last curve, or the start of an empty subpath
+    pub fn end(&self) -> Point {
+        self.curves.last().map_or(self.start, |c| c.end())
+    }
+
+    /// The implicit line drawn by `Z`, if the subpath is closed and not already back at its start
+    pub fn closing_line(&self) -> Option<Curve> {
+        let end = self.end();
+        (self.closed && end != self.start).then_some(Curve::Line(end, self.start))
+    }
+
+    /// All curves including the closing line
+    pub fn outline(&self) -> Vec<Curve> {
+        let mut curves = self.curves.clone();
+        curves.extend(self.closing_line());
+        curves
+    }
+}
+
+/// Resolve segments into absolute subpaths
+pub(crate) fn resolve(segments: &[PathSegment]) -> Vec<Subpath> {
+    let mut subpaths: Vec<Subpath> = Vec::new();
+    let mut current = Subpath { start: Point::new(0.0, 0.0), curves: Vec::new(), closed: false };
+    let mut started = false;
+    let mut position = Point::new(0.0, 0.0);
+    // Last control point and whether it belongs to a cubic (true) or quadratic (false)
+    let mut control: Option<(Point, bool)> = None;
+
+    for segment in segments {
+        let relative = !segment.is_absolute();
+        let point = |x: f64, y: f64| {
+            if relative { Point::new(position.x + x, position.y + y) } else { Point::new(x, y) }
+        };
+
+        let reflect = |cubic: bool| match control {
+            Some((c, is_cubic)) if is_cubic == cubic => Point::new(2.0 * position.x - c.x, 2.0 * position.y - c.y),
+            _ => position,
+        };
+        let (curve, next_control) = match *segment {
+            PathSegment::Close { .. } => {
+                if started {
+                    current.closed = true;
+                    position = current.start;
+                    let start = current.start;
+                    subpaths.push(std::mem::replace(&mut current, Subpath { start, curves: Vec::new(), closed: false }));
+                    started = false;
+                }
+                control = None;
+                continue;
+            }
+            PathSegment::MoveTo { x, y, .. } => {
+                if started {
+                    subpaths.push(current.clone());
+                }
+                position = point(x, y);
+                current = Subpath { start: position, curves: Vec::new(), closed: false };
+                started = true;
+                control = None;
+                continue;
+            }
+            PathSegment::LineTo { x, y, .. } => (Curve::Line(position, point(x, y)), None),
+            PathSegment::Horizontal { x, .. } => {
+                let x = if relative { position.x + x } else { x };
+                (Curve::Line(position, Point::new(x, position.y)), None)
+            }
+            PathSegment::Vertical { y, .. } => {
+                let y = if relative { position.y + y } else { y };
+                (Curve::Line(position, Point::new(position.x, y)), None)
+            }
+            PathSegment::CubicTo { x1, y1, x2, y2, x, y, .. } => {
+                let (c1, c2) = (point(x1, y1), point(x2, y2));
+                (Curve::Cubic(position, c1, c2, point(x, y)), Some((c2, true)))
+            }
+            PathSegment::SmoothCubicTo { x2, y2, x, y, .. } => {
+                let c2 = point(x2, y2);
+                (Curve::Cubic(position, reflect(true), c2, point(x, y)), Some((c2, true)))
+            }
+            PathSegment::QuadTo { x1, y1, x, y, .. } => {
+                let c = point(x1, y1);
+                (Curve::Quad(position, c, point(x, y)), Some((c, false)))
+            }
+            PathSegment::SmoothQuadTo { x, y, .. } => {
+                let c = reflect(false);
+                (Curve::Quad(position, c, point(x, y)), Some((c, false)))
+            }
+            PathSegment::ArcTo { rx, ry, x_axis_rotation, large_arc, sweep, x, y, .. } => (
+                Curve::Arc(EndpointArc { from: position, rx, ry, x_axis_rotation, large_arc, sweep, to: point(x, y) }),
+                None,
+            ),
+        };
+        if !started {
+            // Drawing after `Z` starts a new subpath at the closed subpath's start
+            current = Subpath { start: position, curves: Vec::new(), closed: false };
+            started = true;
+        }
+        position = curve.end();
+        control = next_control;
+        current.curves.push(curve);
+    }
+    if started {
+        subpaths.push(current);
+    }
+    subpaths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(command: char, coords: &[f64]) -> PathSegment {
+        PathSegment::from_command(command, coords).unwrap()
+    }
+
+    #[test]
+    fn test_resolve_relative_and_smooth() {
+        let subpaths = resolve(&[
+            segment('m', &[10.0, 10.0]),
+            segment('h', &[10.0]),
+            segment('c', &[0.0, 10.0, 10.0, 10.0, 10.0, 0.0]),
+            segment('s', &[10.0, -10.0, 10.0, 0.0]),
+            segment('z', &[]),
+            segment('l', &[5.0, 5.0]),
+        ]);
+        assert_eq!(subpaths.len(), 2);
+        assert!(subpaths[0].closed);
+        assert_eq!(subpaths[0].curves[0], Curve::Line(Point::new(10.0, 10.0), Point::new(20.0, 10.0)));
+        assert_eq!(
+            subpaths[0].curves[2],
+            Curve::Cubic(Point::new(30.0, 10.0), Point::new(30.0, 0.0), Point::new(40.0, 0.0), Point::new(40.0, 10.0))
+        );
+        assert_eq!(subpaths[1].curves[0], Curve::Line(Point::new(10.0, 10.0), Point::new(15.0, 15.0)));
+    }
+
+    #[test]
+    fn test_arc_center_and_cubics() {
+        let arc = EndpointArc {
+            from: Point::new(0.0, 0.0),
+            rx: 10.0,
+            ry: 10.0,
+            x_axis_rotation: 0.0,
+            large_arc: false,
+            sweep: true,
+            to: Point::new(20.0, 0.0),
+        };
+        let center = arc.to_center().unwrap();
+        assert!((center.center.x - 10.0).abs() < 1e-9 && center.center.y.abs() < 1e-9);
+        assert!((center.sweep_angle.abs() - std::f64::consts::PI).abs() < 1e-9);
+
+        let cubics = Curve::Arc(arc).to_cubics();
+        assert_eq!(cubics.len(), 2);
+        assert_eq!(cubics[1][3], Point::new(20.0, 0.0));
+        let (first, _) = Curve::Arc(arc).split(0.5);
+        let mid = first.end();
+        assert!((mid.x - 10.0).abs() < 1e-9 && (mid.y.abs() - 10.0).abs() < 1e-9);
+    }
+}
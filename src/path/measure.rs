@@ -0,0 +1,302 @@
+//! Path length and queries by distance along a path
+//!
+//! Lines are measured exactly, circular arcs from their radius and sweep, and
+//! Béziers and elliptical arcs by adaptive Gauss–Legendre integration of the
+//! curve's speed. Distances are mapped back to curve parameters with a
+//! bracketed Newton iteration.
+
+use std::f64::consts::{FRAC_PI_2, TAU};
+
+use super::geometry::{resolve, Curve, Subpath};
+use super::PathSegment;
+use crate::math::{Math, Point};
+use crate::svg::Element;
+
+/// Nodes and weights of 5-point Gauss–Legendre quadrature on [-1, 1]
+const GAUSS: [(f64, f64); 5] = [
+    (0.0, 0.568_888_888_888_888_9),
+    (-0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+    (0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+    (-0.906_179_845_938_664, 0.236_926_885_056_189_1),
+    (0.906_179_845_938_664, 0.236_926_885_056_189_1),
+];
+
+/// Relative accuracy of integrated lengths
+const TOLERANCE: f64 = 1e-9;
+const MAX_DEPTH: u32 = 16;
+
+impl Curve {
+    /// Arc length of the whole curve
+    pub fn length(&self) -> f64 {
+        self.length_between(0.0, 1.0)
+    }
+
+    /// Arc length between parameters `t0` and `t1`
+    pub fn length_between(&self, t0: f64, t1: f64) -> f64 {
+        match self {
+            Curve::Line(a, b) => a.distance_to(b) * (t1 - t0),
+            Curve::Arc(arc) => match arc.to_center() {
+                Some(center) if (center.rx - center.ry).abs() <= f64::EPSILON * center.rx => {
+                    center.rx * center.sweep_angle.abs() * (t1 - t0)
+                }
+                Some(_) => self.integrate(t0, t1),
+                None => arc.from.distance_to(&arc.to) * (t1 - t0),
+            },
+            _ => self.integrate(t0, t1),
+        }
+    }
+
+    /// Parameter at which the curve has covered `length`
+    pub fn parameter_at(&self, length: f64) -> f64 {
+        let total = self.length();
+        if total <= 0.0 || length <= 0.0 {
+            return 0.0;
+        }
+        if length >= total {
+            return 1.0;
+        }
+        if matches!(self, Curve::Line(..)) {
+            return length / total;
+        }
+        let (mut low, mut high) = (0.0, 1.0);
+        let mut t = length / total;
+        for _ in 0..50 {
+            let error = self.length_between(0.0, t) - length;
+            if error.abs() < total * TOLERANCE {
+                break;
+            }
+            if error > 0.0 {
+                high = t;
+            } else {
+                low = t;
+            }
+            // Newton step, falling back to bisection when it leaves the bracket
+            let speed = self.speed(t);
+            t = if speed > 0.0 { t - error / speed } else { f64::NAN };
+            if !(t > low && t < high) {
+                t = (low + high) / 2.0;
+            }
+        }
+        t
+    }
+
+    /// Direction of travel at `t`, also where the derivative vanishes (such as a
+    /// cubic whose control point sits on its end point)
+    pub fn direction(&self, t: f64) -> Point {
+        let d = self.derivative(t);
+        if d.x.hypot(d.y) > 1e-12 {
+            return d;
+        }
+        let (a, b) = (self.point((t - 1e-6).max(0.0)), self.point((t + 1e-6).min(1.0)));
+        if a != b {
+            return Point::new(b.x - a.x, b.y - a.y);
+        }
+        let (start, end) = (self.point(0.0), self.point(1.0));
+        Point::new(end.x - start.x, end.y - start.y)
+    }
+
+    /// Parameter of the point on the curve nearest to `p`
+    pub fn nearest_parameter(&self, p: &Point) -> f64 {
+        const SAMPLES: usize = 64;
+        let distance = |t: f64| self.point(t).distance_to(p);
+        let best = (0..=SAMPLES)
+            .map(|i| i as f64 / SAMPLES as f64)
+            .min_by(|&a, &b| distance(a).total_cmp(&distance(b)))
+            .unwrap_or(0.0);
+        // Golden section search around the best sample
+        let step = 1.0 / SAMPLES as f64;
+        let (mut low, mut high) = ((best - step).max(0.0), (best + step).min(1.0));
+        let ratio = (5f64.sqrt() - 1.0) / 2.0;
+        for _ in 0..60 {
+            let a = high - ratio * (high - low);
+            let b = low + ratio * (high - low);
+            if distance(a) < distance(b) {
+                high = b;
+            } else {
+                low = a;
+            }
+        }
+        let t = (low + high) / 2.0;
+        if distance(best) < distance(t) { best } else { t }
+    }
+
+    fn speed(&self, t: f64) -> f64 {
+        let d = self.derivative(t);
+        d.x.hypot(d.y)
+    }
+
+    fn gauss(&self, t0: f64, t1: f64) -> f64 {
+        let half = (t1 - t0) / 2.0;
+        let middle = (t0 + t1) / 2.0;
+        GAUSS.iter().map(|&(x, w)| w * self.speed(middle + half * x)).sum::<f64>() * half
+    }
+
+    fn integrate(&self, t0: f64, t1: f64) -> f64 {
+        let whole = self.gauss(t0, t1);
+        self.refine(t0, t1, whole, (whole * TOLERANCE).max(1e-12), 0)
+    }
+
+    /// Split the interval until both halves agree with the whole
+    fn refine(&self, t0: f64, t1: f64, whole: f64, tolerance: f64, depth: u32) -> f64 {
+        let middle = (t0 + t1) / 2.0;
+        let (left, right) = (self.gauss(t0, middle), self.gauss(middle, t1));
+        if depth >= MAX_DEPTH || (left + right - whole).abs() <= tolerance {
+            left + right
+        } else {
+            self.refine(t0, middle, left, tolerance / 2.0, depth + 1)
+                + self.refine(middle, t1, right, tolerance / 2.0, depth + 1)
+        }
+    }
+}
+
+impl Subpath {
+    /// Length including the closing line
+    pub fn length(&self) -> f64 {
+        self.outline().iter().map(Curve::length).sum()
+    }
+}
+
+/// Total length of a segment list, as `getTotalLength()` reports it
+pub fn path_length(segments: &[PathSegment]) -> f64 {
+    resolve(segments).iter().map(Subpath::length).sum()
+}
+
+/// All drawn curves of a segment list in order, closing lines included
+pub(crate) fn outline_curves(segments: &[PathSegment]) -> Vec<Curve> {
+    resolve(segments).iter().flat_map(Subpath::outline).collect()
+}
+
+/// The curve containing distance `length` along `curves`, and the parameter on it
+pub(crate) fn locate(curves: &[Curve], length: f64) -> Option<(&Curve, f64)> {
+    let mut remaining = length.max(0.0);
+    for (index, curve) in curves.iter().enumerate() {
+        let curve_length = curve.length();
+        if remaining <= curve_length || index == curves.len() - 1 {
+            return Some((curve, curve.parameter_at(remaining)));
+        }
+        remaining -= curve_length;
+    }
+    None
+}
+
+impl Element {
+    /// Total length of this path's segments
+    pub fn path_length(&self) -> f64 {
+        self.path_segments.as_deref().map_or(0.0, path_length)
+    }
+
+    /// Point at distance `length` along the path, like `getPointAtLength`;
+    /// distances outside the path are clamped to its ends
+    pub fn point_at_length(&self, length: f64) -> Option<Point> {
+        let curves = outline_curves(self.path_segments.as_deref()?);
+        locate(&curves, length).map(|(curve, t)| curve.point(t))
+    }
+
+    /// Direction of travel at distance `length`, in radians like `Math::angle`
+    pub fn tangent_at_length(&self, length: f64) -> Option<f64> {
+        let curves = outline_curves(self.path_segments.as_deref()?);
+        locate(&curves, length).map(|(curve, t)| {
+            let d = curve.direction(t);
+            Math::angle(&Point::new(0.0, 0.0), &d, None)
+        })
+    }
+
+    /// Normal at distance `length`: the tangent turned a quarter turn clockwise
+    /// on screen (towards positive angles), in radians like `Math::angle`
+    pub fn normal_at_length(&self, length: f64) -> Option<f64> {
+        self.tangent_at_length(length).map(|angle| (angle + FRAC_PI_2) % TAU)
+    }
+
+    /// Distance along the path to the point nearest to `point`
+    pub fn length_at_point(&self, point: &Point) -> Option<f64> {
+        let curves = outline_curves(self.path_segments.as_deref()?);
+        let mut before = 0.0;
+        let mut best: Option<(f64, f64)> = None;
+        for curve in &curves {
+            let t = curve.nearest_parameter(point);
+            let distance = curve.point(t).distance_to(point);
+            if best.is_none_or(|(nearest, _)| distance < nearest) {
+                best = Some((distance, before + curve.length_between(0.0, t)));
+            }
+            before += curve.length();
+        }
+        best.map(|(_, length)| length)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::PathBuilder;
+    use crate::svg::Svg;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-6
+    }
+
+    #[test]
+    fn test_line_and_arc_lengths() {
+        let mut svg = Svg::new(100, 100);
+        let path = svg.path("").M(10.0, 10.0).h(30.0).v(40.0).Z();
+        assert!(close(path.path_length(), 120.0));
+
+        // Half circle of radius 10, then the same with a relative arc
+        let path = svg.path("").M(0.0, 0.0).A(10.0, 10.0, 0.0, 0, 1, 20.0, 0.0).a(10.0, 10.0, 0.0, 0, 1, 20.0, 0.0);
+        assert!(close(path.path_length(), 20.0 * std::f64::consts::PI));
+
+        // Quarter of an ellipse with radii 20 and 10
+        let path = svg.path("").M(20.0, 0.0).A(20.0, 10.0, 0.0, 0, 1, 0.0, 10.0);
+        assert!(close(path.path_length(), 24.221_120_551));
+    }
+
+    #[test]
+    fn test_queries_at_length() {
+        let mut svg = Svg::new(100, 100);
+        let path = svg.path("M0 0 H10 V10 A10 10 0 0 1 -10 10");
+        let point = path.point_at_length(15.0).unwrap();
+        assert!(close(point.x, 10.0) && close(point.y, 5.0));
+        assert!(close(path.tangent_at_length(5.0).unwrap(), 0.0));
+        assert!(close(path.tangent_at_length(15.0).unwrap(), std::f64::consts::FRAC_PI_2));
+        assert!(close(path.normal_at_length(5.0).unwrap(), Math::angle(&Point::new(0.0, 0.0), &Point::new(0.0, 1.0), None)));
+        // Half way round the half circle centred on (0, 10)
+        let point = path.point_at_length(20.0 + 5.0 * std::f64::consts::PI).unwrap();
+        assert!(close(point.x, 0.0) && close(point.y, 20.0));
+        assert!(close(path.tangent_at_length(20.0 + 5.0 * std::f64::consts::PI).unwrap(), std::f64::consts::PI));
+        // Clamped to the ends
+        assert_eq!(path.point_at_length(-5.0), Some(Point::new(0.0, 0.0)));
+        let end = path.point_at_length(1000.0).unwrap();
+        assert!(close(end.x, -10.0) && close(end.y, 10.0));
+
+        assert!(close(path.length_at_point(&Point::new(4.0, -3.0)).unwrap(), 4.0));
+        assert!(close(path.length_at_point(&Point::new(0.0, 25.0)).unwrap(), 20.0 + 5.0 * std::f64::consts::PI));
+
+        // Points on a cubic round-trip through their length
+        let path = svg.path("M0 0 C0 40 60 -20 60 20");
+        let point = path.point_at_length(30.0).unwrap();
+        assert!((path.length_at_point(&point).unwrap() - 30.0).abs() < 1e-4);
+        assert_eq!(svg.path("").point_at_length(1.0), None);
+    }
+
+    #[test]
+    fn test_curve_lengths() {
+        let mut svg = Svg::new(100, 100);
+        // A straight cubic with evenly spaced controls is its chord
+        let path = svg.path("").M(0.0, 0.0).C(10.0, 0.0, 20.0, 0.0, 30.0, 0.0);
+        assert!(close(path.path_length(), 30.0));
+
+        // Parabola y = x² from 0 to 1 has length (2√5 + asinh 2) / 4
+        let path = svg.path("").M(0.0, 0.0).Q(0.5, 0.0, 1.0, 1.0);
+        let expected = (2.0 * 5f64.sqrt() + 2f64.asinh()) / 4.0;
+        assert!(close(path.path_length(), expected));
+
+        // The smooth cubic mirrors the first one, doubling the length
+        let path = svg.path("").M(0.0, 0.0).C(0.0, 10.0, 10.0, 10.0, 10.0, 0.0).s(10.0, -10.0, 10.0, 0.0);
+        let first = Curve::Cubic(
+            crate::math::Point::new(0.0, 0.0),
+            crate::math::Point::new(0.0, 10.0),
+            crate::math::Point::new(10.0, 10.0),
+            crate::math::Point::new(10.0, 0.0),
+        );
+        assert!(close(path.path_length(), 2.0 * first.length()));
+    }
+}
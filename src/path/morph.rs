@@ -0,0 +1,248 @@
+//! Morphing between two paths
+//!
+//! Browsers only interpolate `d` when both paths have the same command
+//! structure. `PathMorph` converts both paths to cubic Béziers, pairs up their
+//! subpaths and splits curves until every pair has the same number of
+//! segments, so the two results can be animated or interpolated directly.
+
+use super::geometry::{lerp, resolve, Curve};
+use super::{path_data, PathSegment};
+use crate::animation::Animation;
+use crate::math::Point;
+use crate::svg::Element;
+
+/// A subpath made only of cubic segments
+#[derive(Debug, Clone, PartialEq)]
+struct CubicPath {
+    start: Point,
+    /// Control points and end point of each cubic
+    cubics: Vec<[Point; 3]>,
+    closed: bool,
+}
+
+/// Two paths normalized to the same cubic structure
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathMorph {
+    from: Vec<CubicPath>,
+    to: Vec<CubicPath>,
+}
+
+impl PathMorph {
+    /// Normalize two segment lists to compatible structures
+    pub fn new(from: &[PathSegment], to: &[PathSegment]) -> Self {
+        let mut from = cubic_paths(from);
+        let mut to = cubic_paths(to);
+
+        // Missing subpaths grow from (or shrink to) a point at the centroid of their partner
+        while from.len() < to.len() {
+            from.push(collapsed(&to[from.len()]));
+        }
+        while to.len() < from.len() {
+            to.push(collapsed(&from[to.len()]));
+        }
+
+        for (a, b) in from.iter_mut().zip(to.iter_mut()) {
+            while a.cubics.len() < b.cubics.len() {
+                split_longest(a);
+            }
+            while b.cubics.len() < a.cubics.len() {
+                split_longest(b);
+            }
+            if a.closed && b.closed {
+                align_loop(a, b);
+            }
+        }
+        Self { from, to }
+    }
+
+    /// Number of cubic segments per frame, for checking compatibility
+    pub fn segment_count(&self) -> usize {
+        self.from.iter().map(|p| p.cubics.len()).sum()
+    }
+
+    /// Segments of the frame at `t` (0 is the source, 1 the target)
+    pub fn interpolate(&self, t: f64) -> Vec<PathSegment> {
+        let mut segments = Vec::new();
+        for (a, b) in self.from.iter().zip(&self.to) {
+            let start = lerp(a.start, b.start, t);
+            segments.push(PathSegment::MoveTo { abs: true, x: start.x, y: start.y });
+            for (p, q) in a.cubics.iter().zip(&b.cubics) {
+                let [c1, c2, end] = [0, 1, 2].map(|i| lerp(p[i], q[i], t));
+                segments.push(PathSegment::CubicTo { abs: true, x1: c1.x, y1: c1.y, x2: c2.x, y2: c2.y, x: end.x, y: end.y });
+            }
+            if a.closed && b.closed {
+                segments.push(PathSegment::Close { abs: true });
+            }
+        }
+        segments
+    }
+
+    /// Path data of the frame at `t`
+    pub fn path_data(&self, t: f64) -> String {
+        path_data(&self.interpolate(t))
+    }
+
+    /// An `<animate attributeName="d">` going from the source to the target shape;
+    /// timing can be added with the usual `Animation` methods
+    pub fn animation(&self) -> Animation {
+        Animation::animate("d").values(&[&self.path_data(0.0), &self.path_data(1.0)])
+    }
+}
+
+impl Element {
+    /// Animate this path into the shape described by `to`
+    pub fn morph_to(&mut self, to: &[PathSegment], duration: f64) -> &mut Self {
+        let from = self.path_segments.clone().unwrap_or_default();
+        let morph = PathMorph::new(&from, to);
+        self.animation(morph.animation().dur(duration))
+    }
+}
+
+fn cubic_paths(segments: &[PathSegment]) -> Vec<CubicPath> {
+    let mut paths: Vec<CubicPath> = resolve(segments)
+        .into_iter()
+        .filter(|subpath| !subpath.curves.is_empty())
+        .map(|subpath| CubicPath {
+            start: subpath.start,
+            cubics: subpath.outline().iter()
+                .flat_map(|curve| curve.to_cubics())
+                .map(|[_, c1, c2, end]| [c1, c2, end])
+                .collect(),
+            closed: subpath.closed,
+        })
+        .collect();
+    if paths.is_empty() {
+        // An empty path morphs from a single point
+        paths.push(CubicPath { start: Point::new(0.0, 0.0), cubics: vec![[Point::new(0.0, 0.0); 3]], closed: false });
+    }
+    paths
+}
+
+/// A point-sized copy of `path` at its centroid
+fn collapsed(path: &CubicPath) -> CubicPath {
+    let n = path.cubics.len() as f64 + 1.0;
+    let sum = path.cubics.iter().fold(path.start, |acc, c| Point::new(acc.x + c[2].x, acc.y + c[2].y));
+    let center = Point::new(sum.x / n, sum.y / n);
+    CubicPath { start: center, cubics: vec![[center; 3]], closed: path.closed }
+}
+
+fn cubic_at(path: &CubicPath, index: usize) -> Curve {
+    let start = if index == 0 { path.start } else { path.cubics[index - 1][2] };
+    let [c1, c2, end] = path.cubics[index];
+    Curve::Cubic(start, c1, c2, end)
+}
+
+/// Split the longest segment in half
+fn split_longest(path: &mut CubicPath) {
+    let index = (0..path.cubics.len())
+        .max_by(|&a, &b| {
+            cubic_at(path, a).estimated_length().total_cmp(&cubic_at(path, b).estimated_length())
+        })
+        .unwrap_or(0);
+    if let (Curve::Cubic(_, a1, a2, a3), Curve::Cubic(_, b1, b2, b3)) = cubic_at(path, index).split(0.5) {
+        path.cubics[index] = [a1, a2, a3];
+        path.cubics.insert(index + 1, [b1, b2, b3]);
+    }
+}
+
+/// Rotate (and possibly reverse) the closed loop `b` so its vertices line up with `a`
+fn align_loop(a: &CubicPath, b: &mut CubicPath) {
+    let vertices = |path: &CubicPath| path.cubics.iter().map(|c| c[2]).collect::<Vec<_>>();
+    let target = vertices(a);
+    let n = target.len();
+    let mut best = (f64::INFINITY, 0, false);
+    for reversed in [false, true] {
+        let candidate = if reversed { reverse_loop(b) } else { b.clone() };
+        let points = vertices(&candidate);
+        for shift in 0..n {
+            let cost: f64 = (0..n)
+                .map(|i| {
+                    let p = points[(i + shift) % n];
+                    let q = target[i];
+                    (p.x - q.x).powi(2) + (p.y - q.y).powi(2)
+                })
+                .sum();
+            if cost < best.0 - 1e-9 {
+                best = (cost, shift, reversed);
+            }
+        }
+    }
+    let (_, shift, reversed) = best;
+    if reversed {
+        *b = reverse_loop(b);
+    }
+    if shift > 0 {
+        // Start the loop at the end point of cubic `shift - 1` so vertex `shift` comes first
+        b.cubics.rotate_left(shift);
+        b.start = b.cubics[n - 1][2];
+    }
+}
+
+fn reverse_loop(path: &CubicPath) -> CubicPath {
+    let cubics = path.cubics.iter()
+        .enumerate()
+        .rev()
+        .map(|(index, cubic)| {
+            let start = if index == 0 { path.start } else { path.cubics[index - 1][2] };
+            [cubic[1], cubic[0], start]
+        })
+        .collect();
+    CubicPath { start: path.cubics.last().map_or(path.start, |c| c[2]), cubics, closed: path.closed }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::PathBuilder;
+    use crate::svg::Svg;
+
+    fn segment(command: char, coords: &[f64]) -> PathSegment {
+        PathSegment::from_command(command, coords).unwrap()
+    }
+
+    #[test]
+    fn test_morph_structure() {
+        let square = [
+            segment('M', &[0.0, 0.0]),
+            segment('H', &[10.0]),
+            segment('V', &[10.0]),
+            segment('H', &[0.0]),
+            segment('Z', &[]),
+        ];
+        let triangle = [segment('M', &[5.0, 0.0]), segment('L', &[10.0, 10.0]), segment('L', &[0.0, 10.0]), segment('Z', &[])];
+        let morph = PathMorph::new(&square, &triangle);
+
+        let start = morph.interpolate(0.0);
+        let end = morph.interpolate(1.0);
+        assert_eq!(start.len(), end.len());
+        assert!(start.iter().zip(&end).all(|(a, b)| a.command() == b.command()));
+        assert_eq!(morph.segment_count(), 4);
+        assert_eq!(start[0], PathSegment::MoveTo { abs: true, x: 0.0, y: 0.0 });
+        assert_eq!(end.last(), Some(&PathSegment::Close { abs: true }));
+    }
+
+    #[test]
+    fn test_morph_subpaths_and_animation() {
+        let mut svg = Svg::new(100, 100);
+        let target = [
+            segment('M', &[0.0, 0.0]),
+            segment('L', &[10.0, 0.0]),
+            segment('M', &[20.0, 20.0]),
+            segment('L', &[30.0, 20.0]),
+            segment('L', &[30.0, 30.0]),
+        ];
+        let path = svg.path("").M(0.0, 0.0).L(10.0, 10.0);
+        path.morph_to(&target, 2.0);
+
+        let output = svg.to_string();
+        assert!(output.contains("attributeName=\"d\""));
+        let values = output.split("values=\"").nth(1).unwrap().split('"').next().unwrap();
+        let frames: Vec<&str> = values.split("; ").collect();
+        assert_eq!(frames.len(), 2);
+        let skeleton = |d: &str| d.chars().filter(|c| c.is_ascii_alphabetic()).collect::<String>();
+        assert_eq!(skeleton(frames[0]), skeleton(frames[1]));
+        // Consecutive cubics share one command letter
+        assert_eq!(skeleton(frames[1]), "MCMC");
+        assert_eq!(frames[1].split(|c: char| c.is_ascii_alphabetic() || c == ' ').filter(|s| !s.is_empty()).count(), 22);
+    }
+}
@@ -0,0 +1,248 @@
+//! Path normalization
+//!
+//! Rewrites segment lists without changing the drawn geometry: all absolute,
+//! all relative, shorthand commands expanded, or each segment in whichever
+//! form is shortest as text.
+
+use super::{path_data, PathBuilder, PathSegment};
+use crate::animation::round_value;
+use crate::math::Point;
+use crate::svg::Element;
+
+/// Current point, subpath start and reflectable control point while walking a path
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Cursor {
+    pub position: Point,
+    start: Point,
+    /// Last control point and whether it belongs to a cubic (true) or quadratic (false)
+    control: Option<(Point, bool)>,
+}
+
+impl Cursor {
+    pub(super) fn new() -> Self {
+        Self { position: Point::new(0.0, 0.0), start: Point::new(0.0, 0.0), control: None }
+    }
+
+    /// Control point implied by `S` (cubic) or `T` (quadratic) at the current position
+    fn reflection(&self, cubic: bool) -> Point {
+        match self.control {
+            Some((c, is_cubic)) if is_cubic == cubic => {
+                Point::new(round_value(2.0 * self.position.x - c.x), round_value(2.0 * self.position.y - c.y))
+            }
+            _ => self.position,
+        }
+    }
+
+    /// The absolute form of `segment` at the current position
+    pub(super) fn absolute(&self, segment: &PathSegment) -> PathSegment {
+        if segment.is_absolute() {
+            return *segment;
+        }
+        offset(segment, self.position, 1.0)
+    }
+
+    /// The relative form of the absolute `segment` at the current position
+    fn relative(&self, segment: &PathSegment) -> PathSegment {
+        if !segment.is_absolute() {
+            return *segment;
+        }
+        offset(segment, self.position, -1.0)
+    }
+
+    /// Move past the absolute `segment`
+    pub(super) fn advance(&mut self, segment: &PathSegment) {
+        let (position, control) = match *segment {
+            PathSegment::MoveTo { x, y, .. } => {
+                self.start = Point::new(x, y);
+                (self.start, None)
+            }
+            PathSegment::LineTo { x, y, .. } => (Point::new(x, y), None),
+            PathSegment::Horizontal { x, .. } => (Point::new(x, self.position.y), None),
+            PathSegment::Vertical { y, .. } => (Point::new(self.position.x, y), None),
+            PathSegment::CubicTo { x2, y2, x, y, .. } | PathSegment::SmoothCubicTo { x2, y2, x, y, .. } => {
+                (Point::new(x, y), Some((Point::new(x2, y2), true)))
+            }
+            PathSegment::QuadTo { x1, y1, x, y, .. } => (Point::new(x, y), Some((Point::new(x1, y1), false))),
+            PathSegment::SmoothQuadTo { x, y, .. } => (Point::new(x, y), Some((self.reflection(false), false))),
+            PathSegment::ArcTo { x, y, .. } => (Point::new(x, y), None),
+            PathSegment::Close { .. } => (self.start, None),
+        };
+        self.position = position;
+        self.control = control;
+    }
+}
+
+/// Shift every coordinate of `segment` by `origin` times `sign`, flipping its `abs` flag
+fn offset(segment: &PathSegment, origin: Point, sign: f64) -> PathSegment {
+    let abs = sign > 0.0;
+    let x = |value: f64| round_value(value + sign * origin.x);
+    let y = |value: f64| round_value(value + sign * origin.y);
+    match *segment {
+        PathSegment::MoveTo { x: px, y: py, .. } => PathSegment::MoveTo { abs, x: x(px), y: y(py) },
+        PathSegment::LineTo { x: px, y: py, .. } => PathSegment::LineTo { abs, x: x(px), y: y(py) },
+        PathSegment::Horizontal { x: px, .. } => PathSegment::Horizontal { abs, x: x(px) },
+        PathSegment::Vertical { y: py, .. } => PathSegment::Vertical { abs, y: y(py) },
+        PathSegment::CubicTo { x1, y1, x2, y2, x: px, y: py, .. } => {
+            PathSegment::CubicTo { abs, x1: x(x1), y1: y(y1), x2: x(x2), y2: y(y2), x: x(px), y: y(py) }
+        }
+        PathSegment::SmoothCubicTo { x2, y2, x: px, y: py, .. } => {
+            PathSegment::SmoothCubicTo { abs, x2: x(x2), y2: y(y2), x: x(px), y: y(py) }
+        }
+        PathSegment::QuadTo { x1, y1, x: px, y: py, .. } => PathSegment::QuadTo { abs, x1: x(x1), y1: y(y1), x: x(px), y: y(py) },
+        PathSegment::SmoothQuadTo { x: px, y: py, .. } => PathSegment::SmoothQuadTo { abs, x: x(px), y: y(py) },
+        PathSegment::ArcTo { rx, ry, x_axis_rotation, large_arc, sweep, x: px, y: py, .. } => {
+            PathSegment::ArcTo { abs, rx, ry, x_axis_rotation, large_arc, sweep, x: x(px), y: y(py) }
+        }
+        PathSegment::Close { .. } => PathSegment::Close { abs },
+    }
+}
+
+/// Rewrite each absolute segment with `rewrite(cursor, absolute, original)`
+fn map_segments(segments: &[PathSegment], rewrite: impl Fn(&Cursor, PathSegment, &PathSegment) -> PathSegment) -> Vec<PathSegment> {
+    let mut cursor = Cursor::new();
+    segments
+        .iter()
+        .map(|segment| {
+            let absolute = cursor.absolute(segment);
+            let rewritten = rewrite(&cursor, absolute, segment);
+            cursor.advance(&absolute);
+            rewritten
+        })
+        .collect()
+}
+
+/// The same path with every segment in absolute coordinates
+pub fn to_absolute(segments: &[PathSegment]) -> Vec<PathSegment> {
+    map_segments(segments, |_, absolute, _| absolute)
+}
+
+/// The same path with every segment in relative coordinates
+pub fn to_relative(segments: &[PathSegment]) -> Vec<PathSegment> {
+    map_segments(segments, |cursor, absolute, _| cursor.relative(&absolute))
+}
+
+/// The same path with `H`/`V` written as `L`, `S` as `C` and `T` as `Q`;
+/// segments keep their absolute or relative form
+pub fn expand_shorthands(segments: &[PathSegment]) -> Vec<PathSegment> {
+    map_segments(segments, |cursor, absolute, original| {
+        let expanded = expanded(cursor, absolute);
+        if original.is_absolute() { expanded } else { cursor.relative(&expanded) }
+    })
+}
+
+/// The same path with each segment in its shortest textual form, choosing
+/// between absolute and relative coordinates and the `H`/`V`/`S`/`T` shorthands
+pub fn shortest_form(segments: &[PathSegment]) -> Vec<PathSegment> {
+    map_segments(segments, |cursor, absolute, original| {
+        let mut candidates = vec![*original, absolute, cursor.relative(&absolute)];
+        if let Some(shorthand) = shorthand(cursor, absolute) {
+            candidates.push(shorthand);
+            candidates.push(cursor.relative(&shorthand));
+        }
+        candidates
+            .into_iter()
+            .min_by_key(|candidate| path_data(std::slice::from_ref(candidate)).len())
+            .unwrap_or(*original)
+    })
+}
+
+/// Absolute `H`, `V`, `S` and `T` segments in their full form
+pub(super) fn expanded(cursor: &Cursor, segment: PathSegment) -> PathSegment {
+    let position = cursor.position;
+    match segment {
+        PathSegment::Horizontal { x, .. } => PathSegment::LineTo { abs: true, x, y: position.y },
+        PathSegment::Vertical { y, .. } => PathSegment::LineTo { abs: true, x: position.x, y },
+        PathSegment::SmoothCubicTo { x2, y2, x, y, .. } => {
+            let c1 = cursor.reflection(true);
+            PathSegment::CubicTo { abs: true, x1: c1.x, y1: c1.y, x2, y2, x, y }
+        }
+        PathSegment::SmoothQuadTo { x, y, .. } => {
+            let c = cursor.reflection(false);
+            PathSegment::QuadTo { abs: true, x1: c.x, y1: c.y, x, y }
+        }
+        other => other,
+    }
+}
+
+/// The shorthand form of an absolute segment, if it has one
+fn shorthand(cursor: &Cursor, segment: PathSegment) -> Option<PathSegment> {
+    let position = cursor.position;
+    match segment {
+        PathSegment::LineTo { x, y, .. } if y == position.y => Some(PathSegment::Horizontal { abs: true, x }),
+        PathSegment::LineTo { x, y, .. } if x == position.x => Some(PathSegment::Vertical { abs: true, y }),
+        PathSegment::CubicTo { x1, y1, x2, y2, x, y, .. } if cursor.reflection(true) == Point::new(x1, y1) => {
+            Some(PathSegment::SmoothCubicTo { abs: true, x2, y2, x, y })
+        }
+        PathSegment::QuadTo { x1, y1, x, y, .. } if cursor.reflection(false) == Point::new(x1, y1) => {
+            Some(PathSegment::SmoothQuadTo { abs: true, x, y })
+        }
+        _ => None,
+    }
+}
+
+impl Element {
+    fn rewrite_path(&mut self, rewrite: fn(&[PathSegment]) -> Vec<PathSegment>) -> &mut Self {
+        if let Some(segments) = self.path_segments.as_mut() {
+            *segments = rewrite(segments);
+            self.redraw();
+        }
+        self
+    }
+
+    /// Convert this path's segments to absolute coordinates
+    pub fn make_absolute(&mut self) -> &mut Self {
+        self.rewrite_path(to_absolute)
+    }
+
+    /// Convert this path's segments to relative coordinates
+    pub fn make_relative(&mut self) -> &mut Self {
+        self.rewrite_path(to_relative)
+    }
+
+    /// Expand `H`/`V`/`S`/`T` in this path into `L`/`C`/`Q`
+    pub fn expand_shorthands(&mut self) -> &mut Self {
+        self.rewrite_path(expand_shorthands)
+    }
+
+    /// Rewrite this path in its shortest textual form
+    pub fn minify_path(&mut self) -> &mut Self {
+        self.rewrite_path(shortest_form)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::parse_path;
+
+    fn convert(d: &str, rewrite: fn(&[PathSegment]) -> Vec<PathSegment>) -> String {
+        path_data(&rewrite(&parse_path(d).unwrap()))
+    }
+
+    #[test]
+    fn test_absolute_and_relative() {
+        assert_eq!(convert("m10 10 l5 5 h10 v-5 z l1 1", to_absolute), "M10 10L15 15H25V10ZL11 11");
+        assert_eq!(convert("M10 10 L15 15 H25 V10 Z L11 11", to_relative), "m10 10l5 5h10v-5zl1 1");
+        assert_eq!(convert("M0 0 C.1 .1 .2 .2 .3 .3 A5 5 0 0 1 10.3 .3", to_relative), "m0 0c.1.1.2.2.3.3a5 5 0 0110 0");
+    }
+
+    #[test]
+    fn test_expand_shorthands() {
+        assert_eq!(
+            convert("M0 0 H10 v10 C10 20 20 20 20 10 s10 -10 10 0 Q40 0 50 10 T60 10", expand_shorthands),
+            "M0 0L10 0l0 10C10 20 20 20 20 10c0-10 10-10 10 0Q40 0 50 10 60 20 60 10"
+        );
+    }
+
+    #[test]
+    fn test_shortest_form() {
+        assert_eq!(
+            convert("M100 100 L200 100 L200 200 C200 250 150 250 150 200 C150 150 100 150 100 100", shortest_form),
+            "M100 100H200V200c0 50-50 50-50 0s-50-50-50-100"
+        );
+        let mut svg = crate::svg::Svg::new(100, 100);
+        let path = svg.path("M 10 10 L 10 20 L 10.5 20.5 Z");
+        path.minify_path();
+        assert_eq!(path.attributes.get("d").unwrap(), "M10 10V20l.5.5Z");
+    }
+}
@@ -0,0 +1,295 @@
+//! Path data parsing
+//!
+//! Follows the SVG path grammar: implicit repeated commands (a repeated `M`
+//! continues as `L`), numbers that run together such as `1.5.5` or `10-5`,
+//! exponents, and arc flags packed without separators (`a1 1 0 1120 20`).
+//! Each repetition becomes its own `PathSegment`.
+
+use std::fmt;
+
+use super::PathSegment;
+use crate::svg::Element;
+
+/// What went wrong while parsing path data
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathParseErrorKind {
+    /// Path data must start with `M` or `m`
+    MissingMoveTo,
+    /// Found something other than a command letter where a command was expected
+    ExpectedCommand,
+    /// A command is missing one of its numbers
+    ExpectedNumber,
+    /// A number has no digits or an incomplete exponent
+    InvalidNumber,
+    /// An arc flag is not `0` or `1`
+    InvalidFlag,
+}
+
+/// A path data syntax error and where it happened
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathParseError {
+    pub kind: PathParseErrorKind,
+    /// Byte offset of the offending character, or the input length at end of input
+    pub position: usize,
+    /// The offending character, `None` at end of input
+    pub found: Option<char>,
+}
+
+impl fmt::Display for PathParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let expected = match self.kind {
+            PathParseErrorKind::MissingMoveTo => "path data must start with a moveto",
+            PathParseErrorKind::ExpectedCommand => "expected a path command",
+            PathParseErrorKind::ExpectedNumber => "expected a number",
+            PathParseErrorKind::InvalidNumber => "invalid number",
+            PathParseErrorKind::InvalidFlag => "arc flags must be 0 or 1",
+        };
+        match self.found {
+            Some(c) => write!(f, "{}, found '{}' at position {}", expected, c, self.position),
+            None => write!(f, "{}, found end of input at position {}", expected, self.position),
+        }
+    }
+}
+
+impl std::error::Error for PathParseError {}
+
+/// Parse path data into segments
+pub fn parse_path(d: &str) -> Result<Vec<PathSegment>, PathParseError> {
+    match parse_prefix(d) {
+        (segments, None) => Ok(segments),
+        (_, Some(error)) => Err(error),
+    }
+}
+
+/// Segments before the first error, and the error if any; browsers render
+/// invalid path data up to the command containing the error
+pub(crate) fn parse_prefix(d: &str) -> (Vec<PathSegment>, Option<PathParseError>) {
+    let mut parser = Parser { d, bytes: d.as_bytes(), position: 0 };
+    let mut segments = Vec::new();
+    let error = parser.parse(&mut segments).err();
+    (segments, error)
+}
+
+struct Parser<'a> {
+    d: &'a str,
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn parse(&mut self, segments: &mut Vec<PathSegment>) -> Result<(), PathParseError> {
+        self.skip_whitespace();
+        if self.position < self.bytes.len() && !matches!(self.peek(), Some(b'M' | b'm')) {
+            return Err(self.error(PathParseErrorKind::MissingMoveTo));
+        }
+
+        while let Some(byte) = self.peek() {
+            let command = byte as char;
+            let arity = match command.to_ascii_uppercase() {
+                'M' | 'L' | 'T' => 2,
+                'H' | 'V' => 1,
+                'S' | 'Q' => 4,
+                'C' => 6,
+                'A' => 7,
+                'Z' => 0,
+                _ => return Err(self.error(PathParseErrorKind::ExpectedCommand)),
+            };
+            self.position += 1;
+            self.skip_whitespace();
+
+            if arity == 0 {
+                segments.push(PathSegment::Close { abs: command.is_ascii_uppercase() });
+                continue;
+            }
+
+            let mut first = true;
+            loop {
+                if !first {
+                    // Another argument set repeats the command
+                    if self.peek() == Some(b',') {
+                        self.position += 1;
+                        self.skip_whitespace();
+                    } else if !self.at_number() {
+                        break;
+                    }
+                }
+                let mut coords = Vec::with_capacity(arity);
+                for index in 0..arity {
+                    if index > 0 {
+                        self.skip_comma_whitespace();
+                    }
+                    let value = if arity == 7 && (index == 3 || index == 4) { self.flag()? } else { self.number()? };
+                    coords.push(value);
+                }
+                let repeated = match command {
+                    'M' if !first => 'L',
+                    'm' if !first => 'l',
+                    c => c,
+                };
+                segments.extend(PathSegment::from_command(repeated, &coords));
+                first = false;
+                self.skip_whitespace();
+            }
+        }
+        Ok(())
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.position).copied()
+    }
+
+    fn error(&self, kind: PathParseErrorKind) -> PathParseError {
+        PathParseError { kind, position: self.position, found: self.d[self.position..].chars().next() }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r' | b'\x0C')) {
+            self.position += 1;
+        }
+    }
+
+    fn skip_comma_whitespace(&mut self) {
+        self.skip_whitespace();
+        if self.peek() == Some(b',') {
+            self.position += 1;
+            self.skip_whitespace();
+        }
+    }
+
+    fn at_number(&self) -> bool {
+        matches!(self.peek(), Some(b'0'..=b'9' | b'.' | b'-' | b'+'))
+    }
+
+    fn digits(&mut self) -> usize {
+        let start = self.position;
+        while matches!(self.peek(), Some(b'0'..=b'9')) {
+            self.position += 1;
+        }
+        self.position - start
+    }
+
+    fn number(&mut self) -> Result<f64, PathParseError> {
+        if !self.at_number() {
+            return Err(self.error(PathParseErrorKind::ExpectedNumber));
+        }
+        let start = self.position;
+        if matches!(self.peek(), Some(b'-' | b'+')) {
+            self.position += 1;
+        }
+        let mut count = self.digits();
+        if self.peek() == Some(b'.') {
+            self.position += 1;
+            count += self.digits();
+        }
+        if count == 0 {
+            return Err(self.error(PathParseErrorKind::InvalidNumber));
+        }
+        if matches!(self.peek(), Some(b'e' | b'E')) {
+            self.position += 1;
+            if matches!(self.peek(), Some(b'-' | b'+')) {
+                self.position += 1;
+            }
+            if self.digits() == 0 {
+                return Err(self.error(PathParseErrorKind::InvalidNumber));
+            }
+        }
+        self.d[start..self.position]
+            .parse()
+            .map_err(|_| PathParseError { kind: PathParseErrorKind::InvalidNumber, position: start, found: self.d[start..].chars().next() })
+    }
+
+    fn flag(&mut self) -> Result<f64, PathParseError> {
+        match self.peek() {
+            Some(b'0') => {
+                self.position += 1;
+                Ok(0.0)
+            }
+            Some(b'1') => {
+                self.position += 1;
+                Ok(1.0)
+            }
+            _ => Err(self.error(PathParseErrorKind::InvalidFlag)),
+        }
+    }
+}
+
+impl Element {
+    /// Replace this path's segments with parsed path data
+    pub fn set_path_data(&mut self, d: &str) -> Result<&mut Self, PathParseError> {
+        let segments = parse_path(d)?;
+        self.path_segments = Some(segments);
+        Ok(self.set_attr("d", d))
+    }
+
+    /// Fill `path_segments` from path data given at creation, keeping the
+    /// valid part of malformed data like a browser would
+    pub(crate) fn load_path_data(&mut self, d: &str) {
+        if !d.trim().is_empty() {
+            self.path_segments = Some(parse_prefix(d).0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(d: &str) -> Vec<(char, Vec<f64>)> {
+        parse_path(d).unwrap().iter().map(|s| (s.command(), s.coords())).collect()
+    }
+
+    #[test]
+    fn test_parse_compact_syntax() {
+        assert_eq!(summary("M1.5.5-2e1,3E-1"), vec![
+            ('M', vec![1.5, 0.5]),
+            ('L', vec![-20.0, 0.3]),
+        ]);
+        assert_eq!(summary("m10 10 5 5 h10v-5 c1 2 3 4 5 6 7 8 9 10 11 12z"), vec![
+            ('m', vec![10.0, 10.0]),
+            ('l', vec![5.0, 5.0]),
+            ('h', vec![10.0]),
+            ('v', vec![-5.0]),
+            ('c', vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]),
+            ('c', vec![7.0, 8.0, 9.0, 10.0, 11.0, 12.0]),
+            ('z', vec![]),
+        ]);
+        // Flags need no separators
+        assert_eq!(summary("M0 0a10 10 0 1120 20"), vec![
+            ('M', vec![0.0, 0.0]),
+            ('a', vec![10.0, 10.0, 0.0, 1.0, 1.0, 20.0, 20.0]),
+        ]);
+        assert!(parse_path("  ").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = |d: &str| parse_path(d).unwrap_err();
+        assert_eq!(error("L 10 10").kind, PathParseErrorKind::MissingMoveTo);
+        assert_eq!(error("M 10,10 L 20").position, 12);
+        assert_eq!(error("M 10,10 L 20").kind, PathParseErrorKind::ExpectedNumber);
+        assert_eq!(error("M 0 0 A 5 5 0 2 0 10 10").position, 14);
+        assert_eq!(error("M 0 0 A 5 5 0 2 0 10 10").kind, PathParseErrorKind::InvalidFlag);
+        assert_eq!(error("M 1e 2").kind, PathParseErrorKind::InvalidNumber);
+        assert_eq!(error("M 1 2 X 3").found, Some('X'));
+        assert_eq!(error("M 1 2 L, 3 4").position, 7);
+        assert_eq!(error("M 0 0 L 5 5,").to_string(), "expected a number, found end of input at position 12");
+    }
+
+    #[test]
+    fn test_paths_from_strings() {
+        use crate::path::PathBuilder;
+        use crate::svg::Svg;
+
+        let mut svg = Svg::new(100, 100);
+        let path = svg.path("M 10 10 L 20 20 30 10 Z");
+        assert_eq!(path.get_segment_count(), 4);
+        path.remove_segment(2);
+        assert_eq!(path.attributes.get("d").unwrap(), "M10 10L20 20Z");
+
+        // Invalid data keeps the segments before the error
+        let path = svg.path("M 0 0 L 10 10 L 20");
+        assert_eq!(path.get_segment_count(), 2);
+        assert!(path.set_path_data("M 0 0 Q").is_err());
+        assert_eq!(path.set_path_data("M0 0H5").unwrap().get_segment_count(), 2);
+    }
+}
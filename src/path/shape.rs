@@ -0,0 +1,133 @@
+//! Basic shapes as path data
+//!
+//! `rect`, `circle`, `ellipse`, `line`, `polyline` and `polygon` elements are
+//! converted to the equivalent path following the SVG specification, so that
+//! path operations also work on shapes. The `transform` attribute is not
+//! applied.
+
+use super::parse::parse_prefix;
+use super::PathSegment;
+use crate::svg::Element;
+
+impl Element {
+    /// The outline of this element as path segments, or `None` if it is not
+    /// a shape; shapes that do not render, such as a zero-size rect, give no segments
+    pub fn to_path_segments(&self) -> Option<Vec<PathSegment>> {
+        let number = |name: &str| {
+            self.attributes.get(name).and_then(|value| value.trim().trim_end_matches("px").parse::<f64>().ok())
+        };
+        let length = |name: &str| number(name).unwrap_or(0.0);
+        let segments = match self.tag.as_str() {
+            "path" => match &self.path_segments {
+                Some(segments) => segments.clone(),
+                None => parse_prefix(self.attributes.get("d").map_or("", |d| d.as_str())).0,
+            },
+            "rect" => {
+                let (x, y, width, height) = (length("x"), length("y"), length("width"), length("height"));
+                // A missing corner radius takes the value of the other one
+                let (rx, ry) = match (number("rx"), number("ry")) {
+                    (Some(rx), Some(ry)) => (rx, ry),
+                    (Some(r), None) | (None, Some(r)) => (r, r),
+                    (None, None) => (0.0, 0.0),
+                };
+                let (rx, ry) = (rx.max(0.0).min(width / 2.0), ry.max(0.0).min(height / 2.0));
+                rect(x, y, width, height, rx, ry)
+            }
+            "circle" => {
+                let r = length("r");
+                ellipse(length("cx"), length("cy"), r, r)
+            }
+            "ellipse" => ellipse(length("cx"), length("cy"), length("rx"), length("ry")),
+            "line" => vec![
+                PathSegment::MoveTo { abs: true, x: length("x1"), y: length("y1") },
+                PathSegment::LineTo { abs: true, x: length("x2"), y: length("y2") },
+            ],
+            tag @ ("polyline" | "polygon") => {
+                let numbers: Vec<f64> = self.attributes.get("points").map_or("", |p| p.as_str())
+                    .split(|c: char| c == ',' || c.is_whitespace())
+                    .filter_map(|s| s.parse().ok())
+                    .collect();
+                let mut segments: Vec<PathSegment> = numbers
+                    .chunks_exact(2)
+                    .enumerate()
+                    .map(|(index, p)| match index {
+                        0 => PathSegment::MoveTo { abs: true, x: p[0], y: p[1] },
+                        _ => PathSegment::LineTo { abs: true, x: p[0], y: p[1] },
+                    })
+                    .collect();
+                if tag == "polygon" && !segments.is_empty() {
+                    segments.push(PathSegment::Close { abs: true });
+                }
+                segments
+            }
+            _ => return None,
+        };
+        Some(segments)
+    }
+}
+
+fn rect(x: f64, y: f64, width: f64, height: f64, rx: f64, ry: f64) -> Vec<PathSegment> {
+    if width <= 0.0 || height <= 0.0 {
+        return Vec::new();
+    }
+    let (right, bottom) = (x + width, y + height);
+    if rx == 0.0 || ry == 0.0 {
+        return vec![
+            PathSegment::MoveTo { abs: true, x, y },
+            PathSegment::Horizontal { abs: true, x: right },
+            PathSegment::Vertical { abs: true, y: bottom },
+            PathSegment::Horizontal { abs: true, x },
+            PathSegment::Close { abs: true },
+        ];
+    }
+    let corner = |x: f64, y: f64| PathSegment::ArcTo { abs: true, rx, ry, x_axis_rotation: 0.0, large_arc: false, sweep: true, x, y };
+    vec![
+        PathSegment::MoveTo { abs: true, x: x + rx, y },
+        PathSegment::Horizontal { abs: true, x: right - rx },
+        corner(right, y + ry),
+        PathSegment::Vertical { abs: true, y: bottom - ry },
+        corner(right - rx, bottom),
+        PathSegment::Horizontal { abs: true, x: x + rx },
+        corner(x, bottom - ry),
+        PathSegment::Vertical { abs: true, y: y + ry },
+        corner(x + rx, y),
+        PathSegment::Close { abs: true },
+    ]
+}
+
+fn ellipse(cx: f64, cy: f64, rx: f64, ry: f64) -> Vec<PathSegment> {
+    if rx <= 0.0 || ry <= 0.0 {
+        return Vec::new();
+    }
+    let quarter = |x: f64, y: f64| PathSegment::ArcTo { abs: true, rx, ry, x_axis_rotation: 0.0, large_arc: false, sweep: true, x, y };
+    vec![
+        PathSegment::MoveTo { abs: true, x: cx + rx, y: cy },
+        quarter(cx, cy + ry),
+        quarter(cx - rx, cy),
+        quarter(cx, cy - ry),
+        quarter(cx + rx, cy),
+        PathSegment::Close { abs: true },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::path::{path_bounds, path_data};
+    use crate::svg::Svg;
+
+    #[test]
+    fn test_shapes_to_path_segments() {
+        let mut svg = Svg::new(100, 100);
+        let rect = svg.rect(20, 10).move_to(5, 5).clone();
+        assert_eq!(path_data(&rect.to_path_segments().unwrap()), "M5 5H25V15H5Z");
+        let rounded = svg.rect(20, 10).set_attr("rx", "8").clone();
+        assert_eq!(path_data(&rounded.to_path_segments().unwrap()), "M8 0H12A8 5 0 0120 5V5A8 5 0 0112 10H8A8 5 0 010 5V5A8 5 0 018 0Z");
+        let circle = svg.circle(10).center(50, 50).clone();
+        let bounds = path_bounds(&circle.to_path_segments().unwrap()).unwrap();
+        assert_eq!((bounds.x, bounds.y, bounds.width, bounds.height), (40.0, 40.0, 20.0, 20.0));
+        let polygon = svg.polygon("0,0 10,0 10,10").clone();
+        assert_eq!(path_data(&polygon.to_path_segments().unwrap()), "M0 0L10 0 10 10Z");
+        assert!(svg.text("hi").to_path_segments().is_none());
+        assert!(svg.rect(0, 10).to_path_segments().unwrap().is_empty());
+    }
+}
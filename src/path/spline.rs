@@ -0,0 +1,277 @@
+//! Smooth curves through points
+//!
+//! Each interpolator turns a list of points into one cubic Bézier per pair of
+//! neighbours, passing through every point. Cardinal and Catmull-Rom splines
+//! take their tangents from the neighbouring points, the natural spline
+//! solves for continuous curvature, and monotone-X (Steffen's method) limits
+//! the tangents so the curve never overshoots between points.
+
+use super::geometry::lerp;
+use super::PathBuilder;
+use crate::animation::round_value;
+use crate::math::Point;
+use crate::svg::Element;
+
+/// How a smooth curve is fitted through points
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Spline {
+    /// Catmull-Rom with parameterization `alpha`: 0 is uniform, 0.5
+    /// centripetal (no cusps or loops within a segment), 1 chordal; `tension`
+    /// shortens the tangents as for `Cardinal`
+    CatmullRom { alpha: f64, tension: f64 },
+    /// Cardinal spline; `tension` 0 is the uniform Catmull-Rom, 1 gives straight lines
+    Cardinal { tension: f64 },
+    /// Natural cubic spline with continuous curvature and no curvature at open ends
+    Natural,
+    /// Monotone in y for points sorted by x; peaks only occur at the points
+    MonotoneX,
+}
+
+/// Cubic Bézier control points of `spline` through `points`, closing back to
+/// the first point when `closed`
+pub fn spline_cubics(points: &[Point], spline: Spline, closed: bool) -> Vec<[Point; 4]> {
+    let mut points = points.to_vec();
+    points.dedup();
+    if closed && points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+    let count = points.len();
+    if count < 2 {
+        return Vec::new();
+    }
+    if count == 2 {
+        let line = |a: Point, b: Point| [a, lerp(a, b, 1.0 / 3.0), lerp(a, b, 2.0 / 3.0), b];
+        return if closed { vec![line(points[0], points[1]), line(points[1], points[0])] } else { vec![line(points[0], points[1])] };
+    }
+
+    // Neighbours of the segment from `i` to `i + 1`; open ends repeat the end point
+    let at = |i: isize| -> Point {
+        if closed {
+            points[i.rem_euclid(count as isize) as usize]
+        } else {
+            points[i.clamp(0, count as isize - 1) as usize]
+        }
+    };
+    let segment_count = if closed { count } else { count - 1 };
+    match spline {
+        Spline::Cardinal { tension } => {
+            let k = (1.0 - tension) / 6.0;
+            (0..segment_count as isize)
+                .map(|i| {
+                    let (p0, p1, p2, p3) = (at(i - 1), at(i), at(i + 1), at(i + 2));
+                    let c1 = Point::new(p1.x + k * (p2.x - p0.x), p1.y + k * (p2.y - p0.y));
+                    let c2 = Point::new(p2.x - k * (p3.x - p1.x), p2.y - k * (p3.y - p1.y));
+                    [p1, c1, c2, p2]
+                })
+                .collect()
+        }
+        Spline::CatmullRom { alpha, tension } => (0..segment_count as isize)
+            .map(|i| {
+                let [p1, c1, c2, p2] = catmull_rom(at(i - 1), at(i), at(i + 1), at(i + 2), alpha);
+                [p1, lerp(p1, c1, 1.0 - tension), lerp(p2, c2, 1.0 - tension), p2]
+            })
+            .collect(),
+        Spline::Natural => {
+            let xs: Vec<f64> = points.iter().map(|p| p.x).collect();
+            let ys: Vec<f64> = points.iter().map(|p| p.y).collect();
+            let (dx, dy) = (natural_derivatives(&xs, closed), natural_derivatives(&ys, closed));
+            (0..segment_count)
+                .map(|i| {
+                    let j = (i + 1) % count;
+                    let (p1, p2) = (points[i], points[j]);
+                    [p1, Point::new(p1.x + dx[i] / 3.0, p1.y + dy[i] / 3.0), Point::new(p2.x - dx[j] / 3.0, p2.y - dy[j] / 3.0), p2]
+                })
+                .collect()
+        }
+        Spline::MonotoneX => monotone_x(&points, closed),
+    }
+}
+
+/// One Catmull-Rom segment from `p1` to `p2` (Yuksel et al., as in d3)
+fn catmull_rom(p0: Point, p1: Point, p2: Point, p3: Point, alpha: f64) -> [Point; 4] {
+    let (l01, l12, l23) = (p0.distance_to(&p1).powf(alpha), p1.distance_to(&p2).powf(alpha), p2.distance_to(&p3).powf(alpha));
+    // Repeated end points have no length and leave the tangent along the segment
+    let c1 = if p0 == p1 {
+        p1
+    } else {
+        let a = 2.0 * l01 * l01 + 3.0 * l01 * l12 + l12 * l12;
+        let n = 3.0 * l01 * (l01 + l12);
+        Point::new((p1.x * a - p0.x * l12 * l12 + p2.x * l01 * l01) / n, (p1.y * a - p0.y * l12 * l12 + p2.y * l01 * l01) / n)
+    };
+    let c2 = if p2 == p3 {
+        p2
+    } else {
+        let b = 2.0 * l23 * l23 + 3.0 * l23 * l12 + l12 * l12;
+        let m = 3.0 * l23 * (l23 + l12);
+        Point::new((p2.x * b + p1.x * l23 * l23 - p3.x * l12 * l12) / m, (p2.y * b + p1.y * l23 * l23 - p3.y * l12 * l12) / m)
+    };
+    [p1, c1, c2, p2]
+}
+
+/// Derivatives at each value of a uniform cubic spline with continuous second
+/// derivative, periodic when `closed`
+fn natural_derivatives(values: &[f64], closed: bool) -> Vec<f64> {
+    let n = values.len();
+    let at = |i: usize| values[i % n];
+    // D[i-1] + 4 D[i] + D[i+1] = 3 (v[i+1] - v[i-1]), with 2 D + D' = 3 Δv at open ends
+    let mut diagonal = vec![4.0; n];
+    let mut rhs: Vec<f64> = (0..n).map(|i| 3.0 * (at(i + 1) - at(i + n - 1))).collect();
+    if !closed {
+        diagonal[0] = 2.0;
+        diagonal[n - 1] = 2.0;
+        rhs[0] = 3.0 * (values[1] - values[0]);
+        rhs[n - 1] = 3.0 * (values[n - 1] - values[n - 2]);
+        return solve_tridiagonal(&diagonal, rhs);
+    }
+    // The corner entries of the cyclic system are handled with Sherman–Morrison
+    let gamma = -diagonal[0];
+    diagonal[0] -= gamma;
+    diagonal[n - 1] -= 1.0 / gamma;
+    let x = solve_tridiagonal(&diagonal, rhs);
+    let mut u = vec![0.0; n];
+    u[0] = gamma;
+    u[n - 1] = 1.0;
+    let z = solve_tridiagonal(&diagonal, u);
+    let factor = (x[0] + x[n - 1] / gamma) / (1.0 + z[0] + z[n - 1] / gamma);
+    x.iter().zip(z).map(|(x, z)| x - factor * z).collect()
+}
+
+/// Solve a tridiagonal system with ones beside the diagonal (Thomas algorithm)
+fn solve_tridiagonal(diagonal: &[f64], mut rhs: Vec<f64>) -> Vec<f64> {
+    let n = diagonal.len();
+    let mut pivots = diagonal.to_vec();
+    for i in 1..n {
+        let m = 1.0 / pivots[i - 1];
+        pivots[i] -= m;
+        rhs[i] -= m * rhs[i - 1];
+    }
+    rhs[n - 1] /= pivots[n - 1];
+    for i in (0..n - 1).rev() {
+        rhs[i] = (rhs[i] - rhs[i + 1]) / pivots[i];
+    }
+    rhs
+}
+
+/// Steffen's monotone interpolation; a closed curve returns to the start with a straight segment
+fn monotone_x(points: &[Point], closed: bool) -> Vec<[Point; 4]> {
+    let n = points.len();
+    let slope = |i: usize| {
+        let h = points[i + 1].x - points[i].x;
+        if h == 0.0 { 0.0 } else { (points[i + 1].y - points[i].y) / h }
+    };
+    let mut tangents = vec![0.0; n];
+    for i in 1..n - 1 {
+        let (h0, h1) = (points[i].x - points[i - 1].x, points[i + 1].x - points[i].x);
+        let (s0, s1) = (slope(i - 1), slope(i));
+        if s0 * s1 > 0.0 && h0 + h1 != 0.0 {
+            let p = (s0 * h1 + s1 * h0) / (h0 + h1);
+            tangents[i] = s0.signum() * (2.0 * s0.abs()).min(2.0 * s1.abs()).min(p.abs());
+        }
+    }
+    // One-sided tangents at the ends
+    tangents[0] = (3.0 * slope(0) - tangents[1]) / 2.0;
+    tangents[n - 1] = (3.0 * slope(n - 2) - tangents[n - 2]) / 2.0;
+
+    let mut cubics: Vec<[Point; 4]> = (0..n - 1)
+        .map(|i| {
+            let (p1, p2) = (points[i], points[i + 1]);
+            let third = (p2.x - p1.x) / 3.0;
+            [p1, Point::new(p1.x + third, p1.y + third * tangents[i]), Point::new(p2.x - third, p2.y - third * tangents[i + 1]), p2]
+        })
+        .collect();
+    if closed {
+        let (last, first) = (points[n - 1], points[0]);
+        cubics.push([last, lerp(last, first, 1.0 / 3.0), lerp(last, first, 2.0 / 3.0), first]);
+    }
+    cubics
+}
+
+impl Element {
+    /// Append a smooth curve through `points` drawn with `M` and `C`, ending
+    /// with `Z` when `closed`
+    pub fn draw_spline(&mut self, points: &[Point], spline: Spline, closed: bool) -> &mut Self {
+        let cubics = spline_cubics(points, spline, closed);
+        self.draw_segments(|path| {
+            let r = round_value;
+            if let Some(start) = cubics.first().map(|cubic| cubic[0]) {
+                path.M(r(start.x), r(start.y));
+            }
+            for [_, c1, c2, end] in &cubics {
+                path.C(r(c1.x), r(c1.y), r(c2.x), r(c2.y), r(end.x), r(end.y));
+            }
+            if closed && !cubics.is_empty() {
+                path.Z();
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::tests::points;
+    use crate::path::geometry::Curve;
+    use crate::svg::Svg;
+
+    #[test]
+    fn test_cardinal_and_catmull_rom() {
+        let zigzag = points(&[(0.0, 0.0), (10.0, 10.0), (20.0, 0.0), (30.0, 10.0)]);
+        let cardinal = spline_cubics(&zigzag, Spline::Cardinal { tension: 0.0 }, false);
+        assert_eq!(cardinal.len(), 3);
+        let expected = points(&[(10.0, 10.0), (10.0 + 20.0 / 6.0, 10.0), (20.0 - 20.0 / 6.0, 0.0), (20.0, 0.0)]);
+        assert!(cardinal[1].iter().zip(&expected).all(|(a, b)| a.distance_to(b) < 1e-9));
+        // Between interior points uniform Catmull-Rom is the cardinal spline without tension
+        let uniform = spline_cubics(&zigzag, Spline::CatmullRom { alpha: 0.0, tension: 0.0 }, false);
+        assert!(uniform[1].iter().zip(&cardinal[1]).all(|(a, b)| a.distance_to(b) < 1e-9));
+        assert_eq!(uniform[0][1], zigzag[0]);
+        // Tension shortens the tangents the same way for both
+        let loose = spline_cubics(&zigzag, Spline::Cardinal { tension: 0.5 }, false);
+        let tense = spline_cubics(&zigzag, Spline::CatmullRom { alpha: 0.0, tension: 0.5 }, false);
+        assert!(tense[1].iter().zip(&loose[1]).all(|(a, b)| a.distance_to(b) < 1e-9));
+        // Full tension gives straight lines
+        let tight = spline_cubics(&zigzag, Spline::Cardinal { tension: 1.0 }, true);
+        assert_eq!(tight.len(), 4);
+        assert_eq!(tight[3][1], zigzag[3]);
+        let centripetal = spline_cubics(&zigzag, Spline::CatmullRom { alpha: 0.5, tension: 0.0 }, true);
+        assert_eq!(centripetal[3][3], zigzag[0]);
+    }
+
+    #[test]
+    fn test_natural_and_monotone() {
+        // Points on a line give a straight spline
+        let line = points(&[(0.0, 0.0), (10.0, 10.0), (20.0, 20.0), (30.0, 30.0)]);
+        for cubic in spline_cubics(&line, Spline::Natural, false) {
+            for p in cubic {
+                assert!((p.x - p.y).abs() < 1e-9);
+            }
+        }
+        // The closed natural spline through a square's corners is symmetric
+        let square = points(&[(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)]);
+        let closed = spline_cubics(&square, Spline::Natural, true);
+        assert_eq!(closed.len(), 4);
+        assert!((closed[0][1].distance_to(&closed[0][0]) - closed[2][1].distance_to(&closed[2][0])).abs() < 1e-9);
+
+        // Monotone data stays within each step, unlike the natural spline
+        let steps = points(&[(0.0, 0.0), (1.0, 0.0), (2.0, 10.0), (3.0, 10.0), (4.0, 10.0)]);
+        let within = |cubics: &[[Point; 4]]| {
+            cubics.iter().all(|c| (0..=20).all(|k| {
+                let y = Curve::Cubic(c[0], c[1], c[2], c[3]).point(k as f64 / 20.0).y;
+                (-1e-9..=10.0 + 1e-9).contains(&y)
+            }))
+        };
+        assert!(within(&spline_cubics(&steps, Spline::MonotoneX, false)));
+        assert!(!within(&spline_cubics(&steps, Spline::Natural, false)));
+    }
+
+    #[test]
+    fn test_draw_spline() {
+        let mut svg = Svg::new(100, 100);
+        let path = svg.path("");
+        path.draw_spline(&points(&[(0.0, 0.0), (10.0, 10.0), (20.0, 0.0)]), Spline::Cardinal { tension: 0.5 }, true);
+        assert_eq!(path.get_segment_count(), 5);
+        assert_eq!(path.get_segment(4).unwrap().command(), 'Z');
+        let path = svg.path("");
+        path.draw_spline(&points(&[(5.0, 5.0)]), Spline::Natural, false);
+        assert_eq!(path.get_segment_count(), 0);
+    }
+}
@@ -0,0 +1,388 @@
+//! Stroke outlines and offset contours
+//!
+//! A stroke is assembled from simple polygons: a quadrilateral along every
+//! flattened segment, a join at every vertex and a cap at each open end. A
+//! boolean union merges them into clean outlines. Offsetting grows a shape by
+//! such a stroke or cuts it away.
+
+use std::f64::consts::PI;
+
+use super::boolean::{combine_rings, rings, BooleanOp, FillRule, TOLERANCE};
+use super::geometry::{resolve, Curve};
+use super::PathSegment;
+use crate::css::{ComputedStyle, Node, Paint, Stylesheet};
+use crate::math::Point;
+use crate::svg::{Element, Svg};
+
+/// Shape at the open ends of a stroke (`stroke-linecap`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineCap {
+    #[default]
+    Butt,
+    Round,
+    Square,
+}
+
+/// Shape at the corners of a stroke (`stroke-linejoin`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineJoin {
+    #[default]
+    Miter,
+    Round,
+    Bevel,
+}
+
+/// Stroke geometry; the default matches SVG's initial values
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StrokeStyle {
+    pub width: f64,
+    pub cap: LineCap,
+    pub join: LineJoin,
+    /// Longest miter, as a multiple of the width, before falling back to a bevel
+    pub miter_limit: f64,
+}
+
+impl Default for StrokeStyle {
+    fn default() -> Self {
+        Self { width: 1.0, cap: LineCap::Butt, join: LineJoin::Miter, miter_limit: 4.0 }
+    }
+}
+
+impl From<&ComputedStyle> for StrokeStyle {
+    fn from(style: &ComputedStyle) -> Self {
+        StrokeStyle {
+            width: style.stroke_width,
+            cap: match style.stroke_linecap.as_str() {
+                "round" => LineCap::Round,
+                "square" => LineCap::Square,
+                _ => LineCap::Butt,
+            },
+            // SVG 2's miter-clip and arcs fall back to miter, as in browsers without them
+            join: match style.stroke_linejoin.as_str() {
+                "round" => LineJoin::Round,
+                "bevel" => LineJoin::Bevel,
+                _ => LineJoin::Miter,
+            },
+            miter_limit: if style.stroke_miterlimit >= 1.0 { style.stroke_miterlimit } else { StrokeStyle::default().miter_limit },
+        }
+    }
+}
+
+/// A flattened subpath; `corners` marks the vertices where path segments meet
+struct Polyline {
+    points: Vec<Point>,
+    corners: Vec<bool>,
+    closed: bool,
+}
+
+fn polylines(segments: &[PathSegment], close_all: bool) -> Vec<Polyline> {
+    resolve(segments)
+        .iter()
+        .map(|subpath| {
+            let closed = subpath.closed || close_all;
+            let mut flattened = vec![subpath.start];
+            let mut ends = vec![true];
+            let mut curves = subpath.curves.clone();
+            curves.extend(closed.then(|| Curve::Line(subpath.end(), subpath.start)));
+            for curve in curves {
+                curve.flatten(TOLERANCE, &mut flattened);
+                ends.resize(flattened.len() - 1, false);
+                ends.push(true);
+            }
+            // Drop repeated points, keeping their corner marks
+            let mut polyline = Polyline { points: Vec::new(), corners: Vec::new(), closed };
+            for (point, corner) in flattened.into_iter().zip(ends) {
+                if polyline.points.last() == Some(&point) {
+                    *polyline.corners.last_mut().unwrap() |= corner;
+                } else {
+                    polyline.points.push(point);
+                    polyline.corners.push(corner);
+                }
+            }
+            if closed && polyline.points.len() > 1 && polyline.points.last() == polyline.points.first() {
+                polyline.points.pop();
+                polyline.corners.pop();
+                polyline.corners[0] = true;
+            }
+            polyline
+        })
+        .collect()
+}
+
+/// Perpendicular of a unit direction
+fn normal(direction: Point) -> Point {
+    Point::new(-direction.y, direction.x)
+}
+
+fn disc(center: Point, radius: f64) -> Vec<Point> {
+    let sides = (PI / (1.0 - TOLERANCE / radius).max(-1.0).acos()).ceil().max(8.0) as usize;
+    (0..sides)
+        .map(|k| {
+            let angle = 2.0 * PI * k as f64 / sides as f64;
+            Point::new(center.x + radius * angle.cos(), center.y + radius * angle.sin())
+        })
+        .collect()
+}
+
+/// The polygon filling the outer side of the corner at `p`, if any
+fn join(previous: Point, p: Point, next: Point, half: f64, style: &StrokeStyle, kind: LineJoin) -> Option<Vec<Point>> {
+    let (d1, d2) = (p.sub(&previous).normalized(), next.sub(&p).normalized());
+    let (turn, cos) = (d1.cross(&d2), d1.dot(&d2));
+    if turn.abs() < 1e-12 && cos > 0.0 {
+        return None;
+    }
+    // The outer side is opposite to the turn
+    let side = if turn > 0.0 { -half } else { half };
+    let (n1, n2) = (normal(d1), normal(d2));
+    let (o1, o2) = (p.add(&n1.scale(side)), p.add(&n2.scale(side)));
+    match kind {
+        LineJoin::Round => Some(disc(p, half)),
+        LineJoin::Bevel => Some(vec![p, o1, o2]),
+        LineJoin::Miter => {
+            // Miter length over stroke width is 1 / sin(θ/2) for the angle θ between the segments
+            let ratio = 1.0 / ((1.0 + cos) / 2.0).sqrt();
+            if ratio <= style.miter_limit {
+                let bisector = n1.add(&n2).normalized();
+                Some(vec![p, o1, p.add(&bisector.scale(side * ratio)), o2])
+            } else {
+                Some(vec![p, o1, o2])
+            }
+        }
+    }
+}
+
+/// The cap at the open end `end`, heading away from the stroke in `direction`
+fn cap(end: Point, direction: Point, half: f64, cap: LineCap) -> Option<Vec<Point>> {
+    let n = normal(direction);
+    match cap {
+        LineCap::Butt => None,
+        LineCap::Round => Some(disc(end, half)),
+        LineCap::Square => {
+            let (left, right) = (end.add(&n.scale(half)), end.add(&n.scale(-half)));
+            Some(vec![left, left.add(&direction.scale(half)), right.add(&direction.scale(half)), right])
+        }
+    }
+}
+
+/// Polygons whose union is the stroke of `polyline`
+fn stroke_pieces(polyline: &Polyline, style: &StrokeStyle) -> Vec<Vec<Point>> {
+    let half = style.width / 2.0;
+    let points = &polyline.points;
+    let count = points.len();
+    let mut pieces = Vec::new();
+    if half <= 0.0 || count == 0 {
+        return pieces;
+    }
+    if count == 1 {
+        // Zero-length subpaths only show their caps, squares aligned with the axes
+        let p = points[0];
+        match style.cap {
+            LineCap::Butt => {}
+            LineCap::Round => pieces.push(disc(p, half)),
+            LineCap::Square => pieces.push(vec![
+                Point::new(p.x - half, p.y - half),
+                Point::new(p.x + half, p.y - half),
+                Point::new(p.x + half, p.y + half),
+                Point::new(p.x - half, p.y + half),
+            ]),
+        }
+        return pieces;
+    }
+
+    let segment_count = if polyline.closed { count } else { count - 1 };
+    for i in 0..segment_count {
+        let (a, b) = (points[i], points[(i + 1) % count]);
+        let n = normal(b.sub(&a).normalized());
+        pieces.push(vec![a.add(&n.scale(half)), b.add(&n.scale(half)), b.add(&n.scale(-half)), a.add(&n.scale(-half))]);
+    }
+    let joints = if polyline.closed { 0..count } else { 1..count - 1 };
+    for i in joints {
+        // Between the pieces of a flattened curve a bevel closes the gap
+        let kind = if polyline.corners[i] { style.join } else { LineJoin::Bevel };
+        pieces.extend(join(points[(i + count - 1) % count], points[i], points[(i + 1) % count], half, style, kind));
+    }
+    if !polyline.closed {
+        pieces.extend(cap(points[0], points[0].sub(&points[1]).normalized(), half, style.cap));
+        pieces.extend(cap(points[count - 1], points[count - 1].sub(&points[count - 2]).normalized(), half, style.cap));
+    }
+    pieces
+}
+
+/// Orient every polygon the same way so that their nonzero union covers each of them
+fn oriented(mut pieces: Vec<Vec<Point>>) -> Vec<Vec<Point>> {
+    pieces.retain_mut(|piece| {
+        let area: f64 = (0..piece.len()).map(|i| piece[i].cross(&piece[(i + 1) % piece.len()])).sum();
+        if area < 0.0 {
+            piece.reverse();
+        }
+        area.abs() > 1e-12
+    });
+    pieces
+}
+
+/// The outline of the stroke `style` draws along `segments`, as closed
+/// polygons to fill with the nonzero rule
+pub fn stroke_segments(segments: &[PathSegment], style: &StrokeStyle) -> Vec<PathSegment> {
+    let pieces = polylines(segments, false).iter().flat_map(|polyline| stroke_pieces(polyline, style)).collect();
+    combine_rings(&oriented(pieces), FillRule::NonZero, &[], FillRule::NonZero, BooleanOp::Union)
+}
+
+/// The area filled by `segments` grown by `distance` (shrunk when negative),
+/// with `join` shaping the convex corners of the new outline
+pub fn offset_segments(segments: &[PathSegment], fill_rule: FillRule, distance: f64, join: LineJoin) -> Vec<PathSegment> {
+    let style = StrokeStyle { width: 2.0 * distance.abs(), join, ..StrokeStyle::default() };
+    let band = oriented(polylines(segments, true).iter().flat_map(|polyline| stroke_pieces(polyline, &style)).collect());
+    let op = if distance < 0.0 { BooleanOp::Difference } else { BooleanOp::Union };
+    combine_rings(&rings(segments), fill_rule, &band, FillRule::NonZero, op)
+}
+
+impl Element {
+    /// The style of this element on its own, from its presentation attributes
+    /// and `style` attribute; stylesheet rules, inherited values and
+    /// percentages need the document
+    fn own_style(&self) -> ComputedStyle {
+        let node = Node { tag: &self.tag, attributes: &self.attributes };
+        ComputedStyle::compute(&Stylesheet::default().cascade(&node, &[]), None, (0.0, 0.0))
+    }
+
+    /// Stroke geometry from this element's `stroke-*` presentation attributes
+    /// and `style` attribute; for stylesheet rules and inherited values use
+    /// `Svg::computed_style`
+    pub fn stroke_style(&self) -> StrokeStyle {
+        StrokeStyle::from(&self.own_style())
+    }
+
+    /// A new path element whose fill covers this shape's stroke, filled with
+    /// the stroke paint; `None` if this is not a shape. Only the element's own
+    /// attributes are used; see `Svg::stroke_to_path` for stylesheet rules
+    pub fn stroke_to_path(&self) -> Option<Element> {
+        self.stroke_to_path_with(&self.own_style())
+    }
+
+    /// Like `stroke_to_path`, with the stroke taken from `style`
+    pub fn stroke_to_path_with(&self, style: &ComputedStyle) -> Option<Element> {
+        let mut path = Element::from_segments(stroke_segments(&self.to_path_segments()?, &StrokeStyle::from(style)));
+        match &style.stroke {
+            Paint::Color(color) => {
+                path.attributes.insert("fill".to_string(), color.to_hex());
+            }
+            Paint::Url(iri) => {
+                path.attributes.insert("fill".to_string(), format!("url({iri})"));
+            }
+            Paint::None => {}
+        }
+        if style.stroke_opacity != 1.0 {
+            path.attributes.insert("fill-opacity".to_string(), style.stroke_opacity.to_string());
+        }
+        Some(path)
+    }
+
+    /// A new path element outlining this shape grown by `distance`, or inset
+    /// when negative, with mitered corners; `None` if this is not a shape
+    pub fn offset_path(&self, distance: f64) -> Option<Element> {
+        let segments = offset_segments(&self.to_path_segments()?, self.fill_rule(), distance, LineJoin::Miter);
+        Some(Element::from_segments(segments))
+    }
+}
+
+impl Svg {
+    /// `Element::stroke_to_path` for the element with the given id, with its
+    /// stroke resolved through the full cascade as in `computed_style`
+    pub fn stroke_to_path(&self, id: &str) -> Option<Element> {
+        let (element, style) = self.find_computed_style(|node, _| node.attributes.get("id").is_some_and(|v| v == id))?;
+        element?.stroke_to_path_with(&style)
+    }
+
+    /// `Svg::stroke_to_path` for the element at `path`, the child indices
+    /// from the document root down, as in `computed_style_at`
+    pub fn stroke_to_path_at(&self, path: &[usize]) -> Option<Element> {
+        let target = self.element_at(path)?;
+        let (_, style) = self.find_computed_style(|_, element| element.is_some_and(|element| std::ptr::eq(element, target)))?;
+        target.stroke_to_path_with(&style)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::{parse_path, path_bounds, path_data};
+    use crate::svg::Svg;
+
+    fn stroke(d: &str, style: StrokeStyle) -> String {
+        path_data(&stroke_segments(&parse_path(d).unwrap(), &style))
+    }
+
+    #[test]
+    fn test_stroke_caps_and_joins() {
+        let style = StrokeStyle { width: 4.0, ..StrokeStyle::default() };
+        assert_eq!(stroke("M0 0H10", style), "M0-2L10-2 10 2 0 2Z");
+        assert_eq!(stroke("M0 0H10", StrokeStyle { cap: LineCap::Square, ..style }), "M-2-2L12-2 12 2-2 2Z");
+        assert_eq!(stroke("M0 0H10V10", style), "M0-2L12-2 12 10 8 10 8 2 0 2Z");
+        assert_eq!(stroke("M0 0H10V10", StrokeStyle { join: LineJoin::Bevel, ..style }), "M0-2L10-2 12 0 12 10 8 10 8 2 0 2Z");
+        // A closed square strokes to a frame with a hole
+        assert_eq!(stroke("M0 0H10V10H0Z", style), "M-2-2L12-2 12 12-2 12ZM2 2L2 8 8 8 8 2Z");
+        // A sharp corner beyond the miter limit is beveled
+        let sharp = path_bounds(&stroke_segments(&parse_path("M0 0L100 5 0 10").unwrap(), &style)).unwrap();
+        assert!(sharp.right() < 103.0);
+        let round = stroke_segments(&parse_path("M5 5Z").unwrap(), &StrokeStyle { cap: LineCap::Round, ..style });
+        let bounds = path_bounds(&round).unwrap();
+        assert!((bounds.x - 3.0).abs() < 1e-9 && (bounds.width - 4.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_offset_and_elements() {
+        let square = parse_path("M0 0H10V10H0Z").unwrap();
+        assert_eq!(path_data(&offset_segments(&square, FillRule::NonZero, 2.0, LineJoin::Miter)), "M-2-2L12-2 12 12-2 12Z");
+        assert_eq!(path_data(&offset_segments(&square, FillRule::NonZero, -2.0, LineJoin::Miter)), "M2 2L8 2 8 8 2 8Z");
+        assert_eq!(offset_segments(&square, FillRule::NonZero, -6.0, LineJoin::Miter), vec![]);
+
+        let mut svg = Svg::new(100, 100);
+        let circle = svg.circle(10).center(50, 50).set_attr("stroke", "red").set_attr("stroke-width", "2").clone();
+        let ring = circle.stroke_to_path().unwrap();
+        assert_eq!(ring.attributes.get("fill").map(String::as_str), Some("#ff0000"));
+        let bounds = ring.path_bbox().unwrap();
+        assert!((bounds.x - 39.0).abs() < 0.02 && (bounds.width - 22.0).abs() < 0.04);
+        let grown = circle.offset_path(5.0).unwrap().path_bbox().unwrap();
+        assert!((grown.width - 30.0).abs() < 0.04);
+    }
+
+    #[test]
+    fn test_stroke_large_circles() {
+        let style = StrokeStyle { width: 4.0, ..StrokeStyle::default() };
+        for radius in [150.0_f64, 200.0] {
+            let circle = parse_path(&format!("M{radius} 0A{radius} {radius} 0 0 1-{radius} 0A{radius} {radius} 0 0 1 {radius} 0Z")).unwrap();
+            let ring = stroke_segments(&circle, &style);
+            // One outer loop and one hole
+            assert_eq!(ring.iter().filter(|s| matches!(s, PathSegment::MoveTo { .. })).count(), 2);
+            let bounds = path_bounds(&ring).unwrap();
+            assert!((bounds.x + radius + 2.0).abs() < 0.02 && (bounds.width - 2.0 * radius - 4.0).abs() < 0.04);
+        }
+    }
+
+    #[test]
+    fn test_stroke_style_cascade() {
+        let mut svg = Svg::new(400, 400);
+        svg.style_element(".thick { stroke-width: 8; stroke-linejoin: round }");
+        let styled = svg.circle(50).center(200, 200).id("styled").style("stroke: blue; stroke-width: 6px").clone();
+        svg.circle(50).center(200, 200).id("classed").class("thick").stroke("red");
+
+        assert_eq!(styled.stroke_style().width, 6.0);
+        assert_eq!(StrokeStyle::from(&svg.computed_style("styled").unwrap()).width, 6.0);
+        let outline = svg.stroke_to_path("styled").unwrap();
+        assert_eq!(outline.attributes.get("fill").map(String::as_str), Some("#0000ff"));
+        assert!((outline.path_bbox().unwrap().width - 106.0).abs() < 0.04);
+
+        // Stylesheet rules only apply through the document
+        let classed = svg.stroke_to_path("classed").unwrap();
+        assert!((classed.path_bbox().unwrap().width - 108.0).abs() < 0.04);
+        assert_eq!(StrokeStyle::from(&svg.computed_style("classed").unwrap()).join, LineJoin::Round);
+        assert!(svg.stroke_to_path("missing").is_none());
+
+        // Shapes without an id inherit their stroke from the group
+        svg.group().stroke("green").set_attr("stroke-width", "4").circle(50).center(200, 200);
+        let grouped = svg.stroke_to_path_at(&[3, 0]).unwrap();
+        assert_eq!(grouped.attributes.get("fill").map(String::as_str), Some("#008000"));
+        assert!((grouped.path_bbox().unwrap().width - 104.0).abs() < 0.04);
+        assert!(svg.stroke_to_path_at(&[3, 1]).is_none());
+    }
+}
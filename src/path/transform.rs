@@ -0,0 +1,163 @@
+//! Baking affine transforms into path data
+//!
+//! Absolute coordinates are mapped as points and relative ones as vectors,
+//! which keeps every segment in its original form. `H`/`V` only survive when
+//! the matrix keeps them axis-aligned, and arcs get new radii, rotation and
+//! sweep from the transformed ellipse.
+
+use super::normalize::Cursor;
+use super::PathSegment;
+use crate::animation::round_value;
+use crate::math::{Matrix, Point};
+
+/// The same path with `matrix` applied to every segment
+pub fn transform_segments(segments: &[PathSegment], matrix: &Matrix) -> Vec<PathSegment> {
+    let mut cursor = Cursor::new();
+    segments
+        .iter()
+        .enumerate()
+        .map(|(index, segment)| {
+            let absolute = cursor.absolute(segment);
+            // A leading relative move is relative to the untransformed origin,
+            // so it needs the translation as well
+            let abs = segment.is_absolute();
+            let map = |x: f64, y: f64| {
+                let p = if abs || index == 0 { matrix.apply(&Point::new(x, y)) } else { matrix.apply_vector(&Point::new(x, y)) };
+                (round_value(p.x), round_value(p.y))
+            };
+            let transformed = match *segment {
+                PathSegment::MoveTo { x, y, .. } => {
+                    let (x, y) = map(x, y);
+                    PathSegment::MoveTo { abs, x, y }
+                }
+                PathSegment::LineTo { x, y, .. } => {
+                    let (x, y) = map(x, y);
+                    PathSegment::LineTo { abs, x, y }
+                }
+                PathSegment::Horizontal { x, .. } => {
+                    let from = if abs { cursor.position.y } else { 0.0 };
+                    let (x, y) = map(x, from);
+                    if matrix.b == 0.0 {
+                        PathSegment::Horizontal { abs, x }
+                    } else {
+                        PathSegment::LineTo { abs, x, y }
+                    }
+                }
+                PathSegment::Vertical { y, .. } => {
+                    let from = if abs { cursor.position.x } else { 0.0 };
+                    let (x, y) = map(from, y);
+                    if matrix.c == 0.0 {
+                        PathSegment::Vertical { abs, y }
+                    } else {
+                        PathSegment::LineTo { abs, x, y }
+                    }
+                }
+                PathSegment::CubicTo { x1, y1, x2, y2, x, y, .. } => {
+                    let ((x1, y1), (x2, y2), (x, y)) = (map(x1, y1), map(x2, y2), map(x, y));
+                    PathSegment::CubicTo { abs, x1, y1, x2, y2, x, y }
+                }
+                // Reflected control points stay reflections under affine maps
+                PathSegment::SmoothCubicTo { x2, y2, x, y, .. } => {
+                    let ((x2, y2), (x, y)) = (map(x2, y2), map(x, y));
+                    PathSegment::SmoothCubicTo { abs, x2, y2, x, y }
+                }
+                PathSegment::QuadTo { x1, y1, x, y, .. } => {
+                    let ((x1, y1), (x, y)) = (map(x1, y1), map(x, y));
+                    PathSegment::QuadTo { abs, x1, y1, x, y }
+                }
+                PathSegment::SmoothQuadTo { x, y, .. } => {
+                    let (x, y) = map(x, y);
+                    PathSegment::SmoothQuadTo { abs, x, y }
+                }
+                PathSegment::ArcTo { rx, ry, x_axis_rotation, large_arc, sweep, x, y, .. } => {
+                    let (rx, ry, x_axis_rotation) = transform_ellipse(matrix, rx, ry, x_axis_rotation);
+                    let (x, y) = map(x, y);
+                    // A mirroring transform reverses the direction of travel
+                    let sweep = if matrix.determinant() < 0.0 { !sweep } else { sweep };
+                    PathSegment::ArcTo { abs, rx, ry, x_axis_rotation, large_arc, sweep, x, y }
+                }
+                PathSegment::Close { .. } => *segment,
+            };
+            cursor.advance(&absolute);
+            transformed
+        })
+        .collect()
+}
+
+/// Radii and rotation (degrees) of the image of an ellipse under `matrix`
+fn transform_ellipse(matrix: &Matrix, rx: f64, ry: f64, rotation: f64) -> (f64, f64, f64) {
+    let (sin, cos) = rotation.to_radians().sin_cos();
+    // Columns of matrix × rotate(rotation) × scale(rx, ry) are the images of the semi-axes
+    let u = matrix.apply_vector(&Point::new(cos * rx.abs(), sin * rx.abs()));
+    let v = matrix.apply_vector(&Point::new(-sin * ry.abs(), cos * ry.abs()));
+    // The new axes are the eigenvectors of M Mᵀ, the radii the square roots of its eigenvalues
+    let p = u.x * u.x + v.x * v.x;
+    let q = u.x * u.y + v.x * v.y;
+    let r = u.y * u.y + v.y * v.y;
+    let middle = (p + r) / 2.0;
+    let spread = ((p - r) / 2.0).hypot(q);
+    let angle = if spread == 0.0 { 0.0 } else { 0.5 * (2.0 * q).atan2(p - r) };
+    (
+        round_value((middle + spread).sqrt()),
+        round_value((middle - spread).max(0.0).sqrt()),
+        round_value(angle.to_degrees()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Math;
+    use crate::path::{parse_path, path_bounds, path_data, PathBuilder};
+
+    fn transformed(d: &str, matrix: Matrix) -> String {
+        path_data(&transform_segments(&parse_path(d).unwrap(), &matrix))
+    }
+
+    #[test]
+    fn test_transform_lines() {
+        assert_eq!(transformed("m10 10 h10 v10 H0 z", Matrix::translate(5.0, 5.0)), "m15 15h10v10H5z");
+        assert_eq!(transformed("M10 10 H20 v10", Matrix::scale(2.0, 3.0)), "M20 30H40v30");
+        // Under a rotation H and V become lines
+        assert_eq!(transformed("M10 0 H20 v10", Matrix::rotate(Math::rad(90.0))), "M0 10L0 20l-10 0");
+        assert_eq!(transformed("M0 0 c10 0 10 10 0 10 s-10 10 0 10", Matrix::skew_x(Math::rad(45.0))), "M0 0c10 0 20 10 10 10s0 10 10 10");
+    }
+
+    #[test]
+    fn test_transform_arcs() {
+        // A circle scaled non-uniformly becomes an axis-aligned ellipse
+        assert_eq!(transformed("M0 0 A10 10 0 0 1 20 0", Matrix::scale(2.0, 1.0)), "M0 0A20 10 0 0140 0");
+        // Mirroring flips the sweep
+        assert_eq!(transformed("M0 0 a10 10 0 0 1 20 0", Matrix::scale(1.0, -1.0)), "M0 0a10 10 0 0020 0");
+        // A rotated ellipse keeps its radii and turns with the matrix
+        assert_eq!(transformed("M0 0 A20 10 0 1 1 40 0", Matrix::rotate(Math::rad(30.0))), {
+            let end = Matrix::rotate(Math::rad(30.0)).apply(&Point::new(40.0, 0.0));
+            path_data(&[
+                PathSegment::MoveTo { abs: true, x: 0.0, y: 0.0 },
+                PathSegment::ArcTo {
+                    abs: true,
+                    rx: 20.0,
+                    ry: 10.0,
+                    x_axis_rotation: 30.0,
+                    large_arc: true,
+                    sweep: true,
+                    x: round_value(end.x),
+                    y: round_value(end.y),
+                },
+            ])
+        });
+
+        // Skewing an arc matches skewing its cubic approximation
+        let matrix = Matrix::skew_x(Math::rad(30.0)).multiply(&Matrix::scale(1.5, 0.5));
+        let segments = parse_path("M0 0 A10 20 15 0 1 30 10").unwrap();
+        let direct = path_bounds(&transform_segments(&segments, &matrix)).unwrap();
+        let via_cubics = path_bounds(&transform_segments(&crate::path::arcs_to_cubics(&segments, 1e-6), &matrix)).unwrap();
+        assert!((direct.x - via_cubics.x).abs() < 1e-3 && (direct.y - via_cubics.y).abs() < 1e-3);
+        assert!((direct.width - via_cubics.width).abs() < 1e-3 && (direct.height - via_cubics.height).abs() < 1e-3);
+
+        let mut svg = crate::svg::Svg::new(100, 100);
+        let path = svg.path("M0 0 L10 0");
+        path.transform_path(&Matrix::translate(1.0, 2.0));
+        assert_eq!(path.get_segment(1), Some(&PathSegment::LineTo { abs: true, x: 11.0, y: 2.0 }));
+    }
+}
@@ -0,0 +1,638 @@
+//! Still frames of animated documents
+//!
+//! `Svg::snapshot_at` evaluates the SMIL animations of a document at a given
+//! time and returns a copy without animation elements, with the animated
+//! values written into the attributes of their targets.
+//!
+//! Animations begin at their first resolvable begin time; event-based begins
+//! (`click`, ...) never fire and later restarts are ignored. Numbers, lists of
+//! numbers (points, transforms, path data with matching commands) and colors
+//! are interpolated; other values change discretely half way. Motion paths
+//! are followed at constant speed unless `keyPoints` are given; they need the
+//! `path` feature and are left out without it.
+
+use std::collections::HashMap;
+
+use crate::animation::{parse_clock, round_value};
+use crate::css::Color;
+use crate::easing::cubic_bezier;
+use crate::svg::{Element, Svg};
+
+const ANIMATION_TAGS: &[&str] = &["animate", "set", "animateTransform", "animateMotion"];
+
+/// Resolved timing of an animation
+#[derive(Debug, Clone, Copy)]
+struct Interval {
+    begin: f64,
+    /// Active duration, `None` if indefinite
+    active: Option<f64>,
+    /// Simple duration, `None` if indefinite
+    simple: Option<f64>,
+}
+
+/// A parsed `begin` or `end` value
+enum TimeSpec {
+    Offset(f64),
+    Syncbase { id: String, end: bool, offset: f64 },
+    Never,
+}
+
+impl Svg {
+    /// A static copy of the document as it looks `time` seconds after it starts
+    pub fn snapshot_at(&self, time: f64) -> Svg {
+        let mut animations = Vec::new();
+        collect_animations(&self.elements, &mut animations);
+        let intervals = resolve_intervals(&animations);
+
+        let mut paths = HashMap::new();
+        collect_paths(&self.elements, &mut paths);
+
+        let mut document = self.clone();
+        let mut next = 0;
+        bake(&mut document.elements, &intervals, &mut next, time, &paths);
+        document
+    }
+}
+
+fn is_animation(element: &Element) -> bool {
+    ANIMATION_TAGS.contains(&element.tag.as_str())
+}
+
+fn collect_animations<'a>(elements: &'a [Element], animations: &mut Vec<&'a Element>) {
+    for element in elements {
+        animations.extend(element.children.iter().filter(|c| is_animation(c)));
+        collect_animations(&element.children, animations);
+    }
+}
+
+fn collect_paths(elements: &[Element], paths: &mut HashMap<String, String>) {
+    for element in elements {
+        if let (Some(id), Some(d)) = (element.attributes.get("id"), element.attributes.get("d")) {
+            paths.insert(id.clone(), d.clone());
+        }
+        collect_paths(&element.children, paths);
+    }
+}
+
+/// Resolve begin and end times, following syncbase references between animations
+fn resolve_intervals(animations: &[&Element]) -> Vec<Option<Interval>> {
+    let ids: HashMap<&str, usize> = animations.iter()
+        .enumerate()
+        .filter_map(|(i, a)| a.attributes.get("id").map(|id| (id.as_str(), i)))
+        .collect();
+    let mut resolved: Vec<Option<Option<Interval>>> = vec![None; animations.len()];
+    loop {
+        let mut progress = false;
+        for (index, animation) in animations.iter().enumerate() {
+            if resolved[index].is_none() {
+                if let Some(interval) = interval(animation, &ids, &resolved) {
+                    resolved[index] = Some(interval);
+                    progress = true;
+                }
+            }
+        }
+        if !progress {
+            break;
+        }
+    }
+    // Cyclic references never begin
+    resolved.into_iter().map(|r| r.flatten()).collect()
+}
+
+/// `None` while a referenced animation is unresolved, `Some(None)` if it never begins
+fn interval(
+    animation: &Element,
+    ids: &HashMap<&str, usize>,
+    resolved: &[Option<Option<Interval>>],
+) -> Option<Option<Interval>> {
+    let attr = |name: &str| animation.attributes.get(name).map(|v| v.trim());
+    let times = |value: Option<&str>| -> Option<Vec<f64>> {
+        let mut times = Vec::new();
+        for spec in value.unwrap_or("").split(';').filter(|s| !s.trim().is_empty()).map(parse_timing) {
+            match spec {
+                TimeSpec::Offset(t) => times.push(t),
+                TimeSpec::Never => {}
+                TimeSpec::Syncbase { id, end, offset } => {
+                    let Some(&index) = ids.get(id.as_str()) else { continue };
+                    match resolved[index]? {
+                        Some(other) if !end => times.push(other.begin + offset),
+                        Some(Interval { begin, active: Some(active), .. }) => times.push(begin + active + offset),
+                        _ => {}
+                    }
+                }
+            }
+        }
+        Some(times)
+    };
+
+    let begins = match attr("begin") {
+        Some(_) => times(attr("begin"))?,
+        None => vec![0.0],
+    };
+    let ends = times(attr("end"))?;
+    let Some(begin) = begins.into_iter().reduce(f64::min) else {
+        return Some(None);
+    };
+    let end = ends.into_iter().filter(|e| *e >= begin).reduce(f64::min);
+
+    let simple = attr("dur").and_then(parse_clock).filter(|d| *d > 0.0);
+    let repeat_count = attr("repeatCount").map(|c| c.parse::<f64>().unwrap_or(f64::INFINITY));
+    let repeat_dur = attr("repeatDur").map(|d| parse_clock(d).unwrap_or(f64::INFINITY));
+    let mut active = match (simple, repeat_count, repeat_dur) {
+        (None, _, _) => f64::INFINITY,
+        (Some(d), None, None) => d,
+        (Some(d), count, total) => (d * count.unwrap_or(f64::INFINITY)).min(total.unwrap_or(f64::INFINITY)),
+    };
+    if let Some(end) = end {
+        active = active.min(end - begin);
+    }
+    Some(Some(Interval { begin, active: active.is_finite().then_some(active), simple }))
+}
+
+fn parse_timing(value: &str) -> TimeSpec {
+    let value = value.trim();
+    if value == "indefinite" {
+        return TimeSpec::Never;
+    }
+    if let Some(offset) = parse_clock(value) {
+        return TimeSpec::Offset(offset);
+    }
+    for (marker, end) in [(".begin", false), (".end", true)] {
+        if let Some(position) = value.rfind(marker) {
+            let rest: String = value[position + marker.len()..].chars().filter(|c| !c.is_whitespace()).collect();
+            let offset = match rest.as_str() {
+                "" => Some(0.0),
+                r if r.starts_with('+') || r.starts_with('-') => parse_clock(r),
+                _ => None,
+            };
+            if let Some(offset) = offset {
+                return TimeSpec::Syncbase { id: value[..position].trim().to_string(), end, offset };
+            }
+        }
+    }
+    // Events, repeat events, access keys and wallclock times never fire in a snapshot
+    TimeSpec::Never
+}
+
+/// Simple-duration progress and iteration at `time`, if the animation applies
+fn progress(interval: &Interval, frozen: bool, time: f64) -> Option<(f64, f64)> {
+    let elapsed = time - interval.begin;
+    if elapsed < 0.0 {
+        return None;
+    }
+    let ended = matches!(interval.active, Some(active) if elapsed >= active);
+    if ended && !frozen {
+        return None;
+    }
+    let elapsed = if ended { interval.active.unwrap() } else { elapsed };
+    match interval.simple {
+        Some(simple) => {
+            let iteration = (elapsed / simple).floor();
+            let fraction = elapsed / simple - iteration;
+            if ended && fraction < 1e-9 && iteration > 0.0 {
+                Some((1.0, iteration - 1.0))
+            } else {
+                Some((fraction, iteration))
+            }
+        }
+        None => Some((0.0, 0.0)),
+    }
+}
+
+fn bake(
+    elements: &mut [Element],
+    intervals: &[Option<Interval>],
+    next: &mut usize,
+    time: f64,
+    paths: &HashMap<String, String>,
+) {
+    for element in elements {
+        let animations: Vec<Element> = element.children.iter().filter(|c| is_animation(c)).cloned().collect();
+        element.children.retain(|c| !is_animation(c));
+
+        let mut motion = None;
+        for animation in &animations {
+            let interval = intervals[*next];
+            *next += 1;
+            let Some(interval) = interval else { continue };
+            let frozen = animation.attributes.get("fill").map(|f| f.as_str()) == Some("freeze");
+            let Some((p, iteration)) = progress(&interval, frozen, time) else { continue };
+
+            if animation.tag == "animateMotion" {
+                motion = motion_at(animation, p, paths).or(motion);
+            } else {
+                apply(element, animation, p, iteration);
+            }
+        }
+        if let Some((x, y, angle)) = motion {
+            let mut transform = format!("translate({}, {})", round_value(x), round_value(y));
+            if angle != 0.0 {
+                transform.push_str(&format!(" rotate({})", round_value(angle)));
+            }
+            if let Some(existing) = element.attributes.get("transform") {
+                transform = format!("{} {}", transform, existing);
+            }
+            element.attributes.insert("transform".to_string(), transform);
+        }
+        bake(&mut element.children, intervals, next, time, paths);
+    }
+}
+
+/// Apply an `<animate>`, `<set>` or `<animateTransform>` on top of the element's current value
+fn apply(element: &mut Element, animation: &Element, p: f64, iteration: f64) {
+    let attr = |name: &str| animation.attributes.get(name).map(|v| v.trim().to_string());
+    let transform_type = (animation.tag == "animateTransform").then(|| attr("type").unwrap_or_else(|| "translate".to_string()));
+    let Some(name) = attr("attributeName").or_else(|| transform_type.as_ref().map(|_| "transform".to_string())) else {
+        return;
+    };
+    if animation.tag == "set" {
+        if let Some(to) = attr("to") {
+            element.attributes.insert(name, to);
+        }
+        return;
+    }
+
+    let base = element.attributes.get(&name).cloned();
+    let underlying = match &transform_type {
+        Some(_) => String::new(),
+        None => base.clone().unwrap_or_else(|| initial_value(&name).to_string()),
+    };
+    let zero = || match transform_type.as_deref() {
+        Some("scale") => "1".to_string(),
+        Some(_) => "0".to_string(),
+        None => initial_value(&name).to_string(),
+    };
+
+    // Keyframe values; `to` animations start from the underlying value
+    let to_animation = attr("values").is_none() && attr("from").is_none() && attr("by").is_none();
+    let mut values: Vec<String> = match (attr("values"), attr("from"), attr("to"), attr("by")) {
+        (Some(values), ..) => values.split(';').map(|v| v.trim().to_string()).filter(|v| !v.is_empty()).collect(),
+        (None, from, Some(to), _) => vec![from.unwrap_or_else(|| underlying.clone()), to],
+        (None, from, None, Some(by)) => {
+            let from = from.unwrap_or_else(zero);
+            let to = combine(&from, &by, 1.0, 1.0).unwrap_or(by);
+            vec![from, to]
+        }
+        _ => return,
+    };
+    if let Some(kind) = &transform_type {
+        values = values.iter().map(|v| transform_params(kind, v)).collect();
+    }
+    if values.is_empty() {
+        return;
+    }
+
+    let mut value = value_at(animation, &values, p);
+    if attr("accumulate").as_deref() == Some("sum") && iteration > 0.0 {
+        let last = values.last().unwrap();
+        value = combine(&value, last, 1.0, iteration).unwrap_or(value);
+    }
+    if let Some(kind) = &transform_type {
+        value = format!("{}({})", kind, value);
+    }
+    let additive = attr("additive").as_deref() == Some("sum") && !to_animation;
+    if additive {
+        value = match (&transform_type, &base) {
+            (_, None) => value,
+            (Some(_), Some(base)) => format!("{} {}", base, value),
+            (None, Some(base)) if name == "transform" => format!("{} {}", base, value),
+            (None, Some(base)) => combine(base, &value, 1.0, 1.0).unwrap_or(value),
+        };
+    }
+    element.attributes.insert(name, value);
+}
+
+/// Initial value used when an animated attribute is not set
+fn initial_value(name: &str) -> &'static str {
+    match name {
+        "opacity" | "fill-opacity" | "stroke-opacity" | "stop-opacity" => "1",
+        "fill" => "black",
+        "stroke" => "none",
+        "transform" | "d" | "points" => "",
+        _ => "0",
+    }
+}
+
+/// Normalize `<animateTransform>` parameters so that all values have the same arity
+fn transform_params(kind: &str, value: &str) -> String {
+    let mut n: Vec<f64> = value.split(|c: char| c.is_whitespace() || c == ',')
+        .filter_map(|p| p.parse().ok())
+        .collect();
+    match kind {
+        "translate" => n.resize(2, 0.0),
+        "scale" => {
+            let sx = n.first().copied().unwrap_or(1.0);
+            n.resize(2, sx);
+        }
+        "rotate" => n.resize(3, 0.0),
+        _ => n.resize(1, 0.0),
+    }
+    n.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(" ")
+}
+
+/// Value of a keyframe list at simple progress `p`, honoring calcMode, keyTimes and keySplines
+fn value_at(animation: &Element, values: &[String], p: f64) -> String {
+    let attr = |name: &str| animation.attributes.get(name).map(|v| v.trim());
+    let n = values.len();
+    if n == 1 {
+        return values[0].clone();
+    }
+    let calc_mode = attr("calcMode").unwrap_or("linear");
+    let key_times: Option<Vec<f64>> = attr("keyTimes")
+        .and_then(|t| t.split(';').map(|v| v.trim().parse().ok()).collect::<Option<Vec<f64>>>())
+        .filter(|t| t.len() == n);
+
+    if calc_mode == "discrete" {
+        let times = key_times.unwrap_or_else(|| (0..n).map(|i| i as f64 / n as f64).collect());
+        let index = times.iter().rposition(|t| *t <= p).unwrap_or(0);
+        return values[index].clone();
+    }
+
+    let times = key_times.unwrap_or_else(|| (0..n).map(|i| i as f64 / (n - 1) as f64).collect());
+    let index = (0..n - 1).find(|&i| p <= times[i + 1]).unwrap_or(n - 2);
+    let span = times[index + 1] - times[index];
+    let mut local = if span > 0.0 { ((p - times[index]) / span).clamp(0.0, 1.0) } else { 1.0 };
+    if calc_mode == "spline" {
+        let spline = attr("keySplines").and_then(|s| s.split(';').nth(index)).map(|s| {
+            s.split(|c: char| c.is_whitespace() || c == ',')
+                .filter_map(|v| v.parse::<f64>().ok())
+                .collect::<Vec<_>>()
+        });
+        if let Some([x1, y1, x2, y2]) = spline.as_deref() {
+            local = cubic_bezier(*x1, *y1, *x2, *y2, local);
+        }
+    }
+    combine(&values[index], &values[index + 1], 1.0 - local, local)
+        .unwrap_or_else(|| if local < 0.5 { values[index].clone() } else { values[index + 1].clone() })
+}
+
+/// `fa * a + fb * b` for colors and for values with the same structure of numbers
+fn combine(a: &str, b: &str, fa: f64, fb: f64) -> Option<String> {
+    if let (Some(ca), Some(cb)) = (Color::parse(a), Color::parse(b)) {
+        let mix = |x: u8, y: u8| (x as f64 * fa + y as f64 * fb).round().clamp(0.0, 255.0) as u8;
+        let alpha = (ca.a * fa + cb.a * fb).clamp(0.0, 1.0);
+        return Some(Color { r: mix(ca.r, cb.r), g: mix(ca.g, cb.g), b: mix(ca.b, cb.b), a: alpha }.to_hex());
+    }
+
+    let (ta, tb) = (tokenize(a), tokenize(b));
+    let numbers = |tokens: &[Token]| tokens.iter().filter(|t| matches!(t, Token::Number(_))).count();
+    let skeleton = |tokens: &[Token]| tokens.iter()
+        .filter_map(|t| match t {
+            Token::Text(text) => Some(text.chars().filter(|c| !c.is_whitespace() && *c != ',').collect::<String>()),
+            Token::Number(_) => None,
+        })
+        .collect::<String>();
+    if numbers(&ta) == 0 || numbers(&ta) != numbers(&tb) || skeleton(&ta) != skeleton(&tb) {
+        return None;
+    }
+
+    let mut values = tb.iter().filter_map(|t| match t {
+        Token::Number(n) => Some(*n),
+        Token::Text(_) => None,
+    });
+    let mut output = String::new();
+    let mut previous_number = false;
+    for token in &ta {
+        match token {
+            Token::Number(x) => {
+                if previous_number {
+                    output.push(' ');
+                }
+                let y = values.next()?;
+                output.push_str(&round_value(x * fa + y * fb).to_string());
+                previous_number = true;
+            }
+            Token::Text(text) => {
+                output.push_str(text);
+                previous_number = false;
+            }
+        }
+    }
+    Some(output)
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Number(f64),
+    Text(String),
+}
+
+/// Split a value into numbers and the text around them; digits inside
+/// identifiers (`grad1`, `#0f0`) are kept as text
+fn tokenize(value: &str) -> Vec<Token> {
+    let chars: Vec<char> = value.chars().collect();
+    let mut tokens = Vec::new();
+    let mut text = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let word_start = c == '#' || (c.is_ascii_alphabetic() && chars.get(i + 1).is_some_and(|n| n.is_ascii_alphabetic()));
+        if word_start {
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || matches!(chars[i], '#' | '-' | '_')) {
+                text.push(chars[i]);
+                i += 1;
+            }
+            continue;
+        }
+        if let Some(end) = number_end(&chars, i) {
+            if !text.is_empty() {
+                tokens.push(Token::Text(std::mem::take(&mut text)));
+            }
+            let number: String = chars[i..end].iter().collect();
+            tokens.push(Token::Number(number.parse().unwrap_or(0.0)));
+            i = end;
+            continue;
+        }
+        text.push(c);
+        i += 1;
+    }
+    if !text.is_empty() {
+        tokens.push(Token::Text(text));
+    }
+    tokens
+}
+
+/// End of a number starting at `start`, if one starts there
+fn number_end(chars: &[char], start: usize) -> Option<usize> {
+    let mut i = start;
+    if matches!(chars.get(i), Some('+') | Some('-')) {
+        i += 1;
+    }
+    let digits = |i: &mut usize| {
+        let from = *i;
+        while chars.get(*i).is_some_and(|c| c.is_ascii_digit()) {
+            *i += 1;
+        }
+        *i > from
+    };
+    let mut any = digits(&mut i);
+    if chars.get(i) == Some(&'.') && chars.get(i + 1).is_some_and(|c| c.is_ascii_digit()) {
+        i += 1;
+        any |= digits(&mut i);
+    }
+    if !any {
+        return None;
+    }
+    if matches!(chars.get(i), Some('e') | Some('E')) {
+        let mut j = i + 1;
+        if matches!(chars.get(j), Some('+') | Some('-')) {
+            j += 1;
+        }
+        if digits(&mut j) {
+            i = j;
+        }
+    }
+    Some(i)
+}
+
+/// Position and rotation of an `<animateMotion>` at simple progress `p`
+#[cfg(feature = "path")]
+fn motion_at(animation: &Element, p: f64, paths: &HashMap<String, String>) -> Option<(f64, f64, f64)> {
+    let attr = |name: &str| animation.attributes.get(name).map(|v| v.trim());
+    let mpath = animation.children.iter()
+        .find(|c| c.tag == "mpath")
+        .and_then(|m| m.attributes.get("href").or_else(|| m.attributes.get("xlink:href")))
+        .and_then(|href| paths.get(href.trim_start_matches('#')));
+
+    let lines = if let Some(d) = mpath.map(|d| d.as_str()).or(attr("path")) {
+        flatten_path(d)
+    } else {
+        let points: Vec<String> = match attr("values") {
+            Some(values) => values.split(';').map(|v| v.trim().to_string()).collect(),
+            None => [attr("from").unwrap_or("0,0"), attr("to")?].iter().map(|v| v.to_string()).collect(),
+        };
+        let d = points.iter().enumerate()
+            .map(|(i, point)| format!("{}{}", if i == 0 { "M" } else { "L" }, point))
+            .collect::<String>();
+        flatten_path(&d)
+    };
+    if lines.is_empty() {
+        return None;
+    }
+
+    // Map time progress to distance progress through keyPoints
+    let mut distance = p;
+    if let (Some(points), Some(times)) = (attr("keyPoints"), attr("keyTimes")) {
+        let values: Vec<String> = points.split(';').map(|v| v.trim().to_string()).collect();
+        let mut timing = animation.clone();
+        timing.attributes.insert("keyTimes".to_string(), times.to_string());
+        if timing.attributes.get("calcMode").map(|m| m.as_str()) == Some("paced") {
+            timing.attributes.remove("calcMode");
+        }
+        distance = value_at(&timing, &values, p).parse().unwrap_or(p);
+    }
+
+    let total: f64 = lines.iter().map(|(a, b)| (b.0 - a.0).hypot(b.1 - a.1)).sum();
+    let mut remaining = distance.clamp(0.0, 1.0) * total;
+    let mut found = *lines.last().unwrap();
+    let mut position = found.1;
+    for &(a, b) in &lines {
+        let length = (b.0 - a.0).hypot(b.1 - a.1);
+        if remaining <= length {
+            let t = if length > 0.0 { remaining / length } else { 0.0 };
+            position = (a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t);
+            found = (a, b);
+            break;
+        }
+        remaining -= length;
+    }
+
+    let tangent = (found.1 .1 - found.0 .1).atan2(found.1 .0 - found.0 .0).to_degrees();
+    let angle = match attr("rotate") {
+        Some("auto") => tangent,
+        Some("auto-reverse") => tangent + 180.0,
+        Some(angle) => angle.parse().unwrap_or(0.0),
+        None => 0.0,
+    };
+    Some((position.0, position.1, angle))
+}
+
+#[cfg(feature = "path")]
+type Line = ((f64, f64), (f64, f64));
+
+/// Approximate path data by line segments (moves between subpaths are skipped)
+#[cfg(feature = "path")]
+fn flatten_path(d: &str) -> Vec<Line> {
+    use crate::path::{parse_prefix, resolve, Curve};
+
+    const STEPS: usize = 24;
+    let (segments, _) = parse_prefix(d);
+    let mut lines: Vec<Line> = Vec::new();
+    for curve in resolve(&segments).iter().flat_map(|subpath| subpath.outline()) {
+        let steps = if matches!(curve, Curve::Line(..)) { 1 } else { STEPS };
+        let mut previous = curve.point(0.0);
+        for step in 1..=steps {
+            let next = curve.point(step as f64 / steps as f64);
+            lines.push(((previous.x, previous.y), (next.x, next.y)));
+            previous = next;
+        }
+    }
+    lines
+}
+
+/// Motion paths are only sampled with the `path` feature's parser; without it
+/// `<animateMotion>` leaves its target in place
+#[cfg(not(feature = "path"))]
+fn motion_at(_animation: &Element, _p: f64, _paths: &HashMap<String, String>) -> Option<(f64, f64, f64)> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::animation::{Animation, Timing, TransformType, MotionRotate};
+
+    #[test]
+    fn test_interpolate_values() {
+        assert_eq!(combine("0", "10", 0.5, 0.5).unwrap(), "5");
+        assert_eq!(combine("#000000", "#ffffff", 0.5, 0.5).unwrap(), "#808080");
+        assert_eq!(combine("M0 0 L10 10", "M10,10 L20,30", 0.5, 0.5).unwrap(), "M5 5 L15 20");
+        assert_eq!(combine("rotate(0)", "rotate(360)", 0.75, 0.25).unwrap(), "rotate(90)");
+        assert!(combine("url(#grad1)", "url(#grad2)", 0.5, 0.5).is_none());
+        assert!(combine("M0 0 L10 10", "M0 0 C1 1 2 2 3 3", 0.5, 0.5).is_none());
+    }
+
+    #[test]
+    fn test_snapshot_timing() {
+        let mut svg = Svg::new(100, 100);
+        svg.rect(10, 10)
+            .id("box")
+            .animation(Animation::animate("x").id("slide").from("0").to("100").dur(2.0).freeze())
+            .animation(Animation::animate("fill").from("#000000").to("#ffffff").dur(1.0).begin(Timing::after("slide")))
+            .animation(Animation::animate("opacity").values(&["1", "0", "1"]).key_times(&[0.0, 0.8, 1.0]).dur(1.0).repeat(2.0))
+            .animation(Animation::set("visibility", "hidden").begin(Timing::on("click")));
+
+        let frame = svg.snapshot_at(0.5).to_string();
+        assert!(frame.contains("x=\"25\""));
+        assert!(frame.contains("opacity=\"0.375\""));
+        assert!(!frame.contains("<animate"));
+        assert!(!frame.contains("<set"));
+        assert!(!frame.contains("visibility"));
+
+        let frame = svg.snapshot_at(2.5).to_string();
+        assert!(frame.contains("x=\"100\""));
+        assert!(frame.contains("fill=\"#808080\""));
+        assert!(!frame.contains("opacity"));
+    }
+
+    #[test]
+    fn test_snapshot_transforms_and_motion() {
+        let mut svg = Svg::new(100, 100);
+        svg.path("M 0 0 L 100 0 L 100 100").id("route");
+        svg.rect(10, 10)
+            .id("box")
+            .animation(Animation::transform(TransformType::Rotate).from("0 5 5").to("360 5 5").dur(4.0).repeat_indefinitely())
+            .animation(Animation::motion().mpath("route").rotate(MotionRotate::Auto).dur(2.0).repeat_indefinitely());
+        svg.circle(5)
+            .id("dot")
+            .animation(Animation::animate("r").values(&["10", "20"]).key_splines(&[(0.42, 0.0, 0.58, 1.0)]).key_times(&[0.0, 1.0]).dur(11.0));
+
+        // Motion paths are only followed with the `path` feature
+        let motion = |at: &str| if cfg!(feature = "path") { at.to_string() } else { String::new() };
+        let mut frame = svg.snapshot_at(5.5);
+        assert_eq!(frame.get_element_by_id("box").unwrap().attributes["transform"], motion("translate(100, 50) rotate(90) ") + "rotate(135 5 5)");
+        assert_eq!(frame.get_element_by_id("dot").unwrap().attributes["r"], "15");
+        let frame = svg.snapshot_at(1.25);
+        assert!(frame.to_string().contains(&(motion("translate(100, 25) rotate(90) ") + "rotate(112.5 5 5)")));
+    }
+}
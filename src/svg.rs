@@ -1,4 +1,10 @@
 use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::animation::Animation;
+use crate::keyframes::AnimationMode;
+use crate::metadata::{Metadata, CC_NAMESPACE, DC_NAMESPACE, RDF_NAMESPACE};
 
 #[cfg(feature = "draggable")]
 use crate::draggable::DragHandler;
@@ -13,20 +19,26 @@ use crate::draggable::DragHandler;
 /// let mut canvas = Svg::new(800, 600);
 /// canvas.rect(100, 100).fill("#red").move_to(50, 50);
 /// ```
+#[derive(Clone)]
 pub struct Svg {
     width: u32,
     height: u32,
     viewbox: Option<(f32, f32, f32, f32)>,
-    elements: Vec<Element>,
+    pub(crate) attributes: HashMap<String, String>,
+    pub(crate) elements: Vec<Element>,
+    pub(crate) metadata: Option<Metadata>,
+    pub(crate) animation_mode: AnimationMode,
+    pub(crate) reduced_motion_guard: bool,
 }
 
 /// An SVG element that can be styled, positioned, and transformed.
 ///
 /// Elements support method chaining for fluent API usage.
+#[derive(Clone)]
 pub struct Element {
-    tag: String,
-    attributes: HashMap<String, String>,
-    children: Vec<Element>,
+    pub(crate) tag: String,
+    pub(crate) attributes: HashMap<String, String>,
+    pub(crate) children: Vec<Element>,
     #[cfg(feature = "draggable")]
     drag_handler: DragHandler,
     #[cfg(feature = "path")]
@@ -36,6 +48,7 @@ pub struct Element {
 }
 
 /// A group container for organizing related elements (currently unused).
+#[allow(dead_code)]
 pub struct Group<'a> {
     element: &'a mut Element,
     svg: &'a mut Svg,
@@ -47,7 +60,11 @@ impl Svg {
             width,
             height,
             viewbox: None,
+            attributes: HashMap::new(),
             elements: Vec::new(),
+            metadata: None,
+            animation_mode: AnimationMode::Smil,
+            reduced_motion_guard: true,
         }
     }
 
@@ -88,7 +105,10 @@ impl Svg {
         let mut attrs = HashMap::new();
         attrs.insert("d".to_string(), d.to_string());
         
-        self.add_element("path", attrs)
+        let element = self.add_element("path", attrs);
+        #[cfg(feature = "path")]
+        element.load_path_data(d);
+        element
     }
 
     pub fn text(&mut self, content: &str) -> &mut Element {
@@ -189,6 +209,28 @@ impl Svg {
         self.add_element("a", attrs)
     }
 
+    /// Set the document title, inserted as the first child of `<svg>`
+    pub fn title(&mut self, text: &str) -> &mut Self {
+        set_description(&mut self.elements, &mut self.attributes, "title", text);
+        self
+    }
+
+    /// Set the document description, placed right after the title
+    pub fn desc(&mut self, text: &str) -> &mut Self {
+        set_description(&mut self.elements, &mut self.attributes, "desc", text);
+        self
+    }
+
+    /// Set the ARIA role of the root element (usually "img" or "graphics-document")
+    pub fn role(&mut self, role: &str) -> &mut Self {
+        self.set_attr("role", role)
+    }
+
+    /// Set the language of the document
+    pub fn lang(&mut self, lang: &str) -> &mut Self {
+        self.set_attr("lang", lang)
+    }
+
     /// Create a star shape using polygon
     #[cfg(feature = "shapes")]
     pub fn star(&mut self, spikes: u32, inner: f64, outer: f64) -> &mut Element {
@@ -234,24 +276,24 @@ impl Svg {
         self.add_element("polygon", attrs)
     }
 
+    /// Add an element built elsewhere, such as the result of a path operation
+    pub fn append(&mut self, element: Element) -> &mut Element {
+        self.elements.push(element);
+        self.elements.last_mut().unwrap()
+    }
+
     fn add_element(&mut self, tag: &str, attributes: HashMap<String, String>) -> &mut Element {
-        let element = Element {
-            tag: tag.to_string(),
-            attributes,
-            children: Vec::new(),
-            #[cfg(feature = "draggable")]
-            drag_handler: DragHandler::default(),
-            #[cfg(feature = "path")]
-            path_segments: None,
-            #[cfg(feature = "path")]
-            auto_redraw: true,
-        };
+        let element = Element::new(tag, attributes);
         
         self.elements.push(element);
         self.elements.last_mut().unwrap()
     }
 
-    pub fn to_string(&self) -> String {
+    fn write_document(&self) -> String {
+        if let Some(document) = self.with_css_animations() {
+            return document.write_document();
+        }
+
         let mut svg = format!(
             r#"<svg width="{}" height="{}""#,
             self.width, self.height
@@ -260,15 +302,45 @@ impl Svg {
         if let Some((x, y, w, h)) = self.viewbox {
             svg.push_str(&format!(r#" viewBox="{} {} {} {}""#, x, y, w, h));
         }
+
+        for (key, value) in &self.attributes {
+            svg.push_str(&format!(r#" {}="{}""#, key, escape_attr(value)));
+        }
+        
+        if self.metadata.is_some() {
+            svg.push_str(&format!(
+                r#" xmlns:rdf="{}" xmlns:dc="{}" xmlns:cc="{}""#,
+                RDF_NAMESPACE, DC_NAMESPACE, CC_NAMESPACE
+            ));
+        }
         
         svg.push_str(r#" xmlns="http://www.w3.org/2000/svg">
     "#);
+
+        // Metadata follows the leading title and description
+        let metadata_index = self.elements.iter()
+            .position(|e| e.tag != "title" && e.tag != "desc")
+            .unwrap_or(self.elements.len());
         
-        for element in &self.elements {
+        for (index, element) in self.elements.iter().enumerate() {
+            if index == metadata_index {
+                if let Some(metadata) = &self.metadata {
+                    svg.push_str("  ");
+                    svg.push_str(&metadata.to_markup());
+                    svg.push('\n');
+                }
+            }
             svg.push_str("  ");
-            svg.push_str(&element.to_string());
+            svg.push_str(&element.write_markup());
             svg.push('\n');
         }
+        if metadata_index == self.elements.len() {
+            if let Some(metadata) = &self.metadata {
+                svg.push_str("  ");
+                svg.push_str(&metadata.to_markup());
+                svg.push('\n');
+            }
+        }
         
         // Add drag scripts for draggable elements
         #[cfg(feature = "draggable")]
@@ -304,11 +376,53 @@ impl Svg {
         self.viewbox = Some((x, y, width, height));
         self
     }
+
+    /// Size of the user coordinate system (viewBox, or width and height)
+    pub(crate) fn viewport_size(&self) -> (f64, f64) {
+        match self.viewbox {
+            Some((_, _, w, h)) => (w as f64, h as f64),
+            None => (self.width as f64, self.height as f64),
+        }
+    }
+
+    /// Get an attribute of the root `<svg>` element
+    pub fn attr(&self, name: &str) -> Option<&String> {
+        self.attributes.get(name)
+    }
+
+    /// Set an attribute on the root `<svg>` element
+    pub fn set_attr(&mut self, name: &str, value: &str) -> &mut Self {
+        self.attributes.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    /// Find an element anywhere in the document by its `id`
+    pub fn get_element_by_id(&mut self, id: &str) -> Option<&mut Element> {
+        find_by_id(&mut self.elements, id)
+    }
+
+    /// The element at `path`, the child indices from the document root down
+    pub(crate) fn element_at(&self, path: &[usize]) -> Option<&Element> {
+        let (first, rest) = path.split_first()?;
+        rest.iter().try_fold(self.elements.get(*first)?, |element, &i| element.children.get(i))
+    }
+}
+
+fn find_by_id<'a>(elements: &'a mut [Element], id: &str) -> Option<&'a mut Element> {
+    for element in elements {
+        if element.attributes.get("id").map(|v| v.as_str()) == Some(id) {
+            return Some(element);
+        }
+        if let Some(found) = find_by_id(&mut element.children, id) {
+            return Some(found);
+        }
+    }
+    None
 }
 
 impl Element {
-    pub fn add_child(&mut self, tag: &str, attributes: HashMap<String, String>) -> &mut Element {
-        let element = Element {
+    pub(crate) fn new(tag: &str, attributes: HashMap<String, String>) -> Self {
+        Self {
             tag: tag.to_string(),
             attributes,
             children: Vec::new(),
@@ -318,7 +432,17 @@ impl Element {
             path_segments: None,
             #[cfg(feature = "path")]
             auto_redraw: true,
-        };
+        }
+    }
+
+    pub fn add_child(&mut self, tag: &str, attributes: HashMap<String, String>) -> &mut Element {
+        let element = Element::new(tag, attributes);
+        self.children.push(element);
+        self.children.last_mut().unwrap()
+    }
+
+    /// Add an element built elsewhere as the last child
+    pub fn append(&mut self, element: Element) -> &mut Element {
         self.children.push(element);
         self.children.last_mut().unwrap()
     }
@@ -368,7 +492,10 @@ impl Element {
     pub fn path(&mut self, d: &str) -> &mut Element {
         let mut attrs = HashMap::new();
         attrs.insert("d".to_string(), d.to_string());
-        self.add_child("path", attrs)
+        let element = self.add_child("path", attrs);
+        #[cfg(feature = "path")]
+        element.load_path_data(d);
+        element
     }
 
     pub fn polygon(&mut self, points: &str) -> &mut Element {
@@ -474,42 +601,19 @@ impl Element {
     pub fn animate(&mut self, duration: u32) -> &mut Self {
         let mut attrs = HashMap::new();
         attrs.insert("dur".to_string(), format!("{}s", duration));
-        let animate_elem = Element {
-            tag: "animate".to_string(),
-            attributes: attrs,
-            children: Vec::new(),
-            #[cfg(feature = "draggable")]
-            drag_handler: DragHandler::default(),
-            #[cfg(feature = "path")]
-            path_segments: None,
-            #[cfg(feature = "path")]
-            auto_redraw: true,
-        };
+        let animate_elem = Element::new("animate", attrs);
         self.children.push(animate_elem);
         self
     }
 
     pub fn animate_attr(&mut self, attr: &str, from: &str, to: &str, duration: u32) -> &mut Self {
-        let mut attrs = HashMap::new();
-        attrs.insert("attributeName".to_string(), attr.to_string());
-        attrs.insert("from".to_string(), from.to_string());
-        attrs.insert("to".to_string(), to.to_string());
-        attrs.insert("dur".to_string(), format!("{}s", duration));
-        attrs.insert("repeatCount".to_string(), "indefinite".to_string());
-        
-        let animate_elem = Element {
-            tag: "animate".to_string(),
-            attributes: attrs,
-            children: Vec::new(),
-            #[cfg(feature = "draggable")]
-            drag_handler: DragHandler::default(),
-            #[cfg(feature = "path")]
-            path_segments: None,
-            #[cfg(feature = "path")]
-            auto_redraw: true,
-        };
-        self.children.push(animate_elem);
-        self
+        self.animation(
+            Animation::animate(attr)
+                .from(from)
+                .to(to)
+                .dur(duration as f64)
+                .repeat_indefinitely(),
+        )
     }
 
     pub fn on_click(&mut self, handler: &str) -> &mut Self {
@@ -558,6 +662,55 @@ impl Element {
         self
     }
 
+    /// Add or replace the `<title>` child and reference it from `aria-labelledby`
+    pub fn title(&mut self, text: &str) -> &mut Self {
+        set_description(&mut self.children, &mut self.attributes, "title", text);
+        self
+    }
+
+    /// Add or replace the `<desc>` child and reference it from `aria-describedby`
+    pub fn desc(&mut self, text: &str) -> &mut Self {
+        set_description(&mut self.children, &mut self.attributes, "desc", text);
+        self
+    }
+
+    pub fn role(&mut self, role: &str) -> &mut Self {
+        self.attributes.insert("role".to_string(), role.to_string());
+        self
+    }
+
+    pub fn aria_label(&mut self, label: &str) -> &mut Self {
+        self.attributes.insert("aria-label".to_string(), label.to_string());
+        self
+    }
+
+    /// Reference the elements (space-separated ids) that label this element
+    pub fn aria_labelledby(&mut self, ids: &str) -> &mut Self {
+        self.attributes.insert("aria-labelledby".to_string(), ids.to_string());
+        self
+    }
+
+    /// Reference the elements (space-separated ids) that describe this element
+    pub fn aria_describedby(&mut self, ids: &str) -> &mut Self {
+        self.attributes.insert("aria-describedby".to_string(), ids.to_string());
+        self
+    }
+
+    pub fn focusable(&mut self, focusable: bool) -> &mut Self {
+        self.attributes.insert("focusable".to_string(), focusable.to_string());
+        self
+    }
+
+    pub fn tabindex(&mut self, index: i32) -> &mut Self {
+        self.attributes.insert("tabindex".to_string(), index.to_string());
+        self
+    }
+
+    pub fn lang(&mut self, lang: &str) -> &mut Self {
+        self.attributes.insert("lang".to_string(), lang.to_string());
+        self
+    }
+
     pub fn font_family(&mut self, family: &str) -> &mut Self {
         self.attributes.insert("font-family".to_string(), family.to_string());
         self
@@ -757,7 +910,7 @@ impl Element {
         self.append_transform(&transform)
     }
 
-    pub fn to_string(&self) -> String {
+    fn write_markup(&self) -> String {
         let mut attrs = String::new();
         let mut text_content = String::new();
         
@@ -774,7 +927,7 @@ impl Element {
         if has_children || has_text {
             let mut content = escape_text(&text_content);
             for child in &self.children {
-                content.push_str(&child.to_string());
+                content.push_str(&child.write_markup());
             }
             format!("<{}{}>{}</{}>", self.tag, attrs, content, self.tag)
         } else {
@@ -810,6 +963,12 @@ impl Element {
         self
     }
 
+    /// Whether dragging is enabled for this element
+    #[cfg(feature = "draggable")]
+    pub fn is_draggable(&self) -> bool {
+        self.drag_handler.enabled
+    }
+
     /// Get the drag script for this element (internal use)
     #[cfg(feature = "draggable")]
     pub fn get_drag_script(&self) -> String {
@@ -821,7 +980,61 @@ impl Element {
     }
 }
 
-fn escape_attr(value: &str) -> String {
+impl fmt::Display for Svg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.write_document())
+    }
+}
+
+impl fmt::Display for Element {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.write_markup())
+    }
+}
+
+static NEXT_AUTO_ID: AtomicUsize = AtomicUsize::new(1);
+
+/// Generate a document-unique id with the given prefix
+pub(crate) fn auto_id(prefix: &str) -> String {
+    format!("{}-{}", prefix, NEXT_AUTO_ID.fetch_add(1, Ordering::Relaxed))
+}
+
+/// Insert or update a `<title>` or `<desc>` among the first children and
+/// reference it from the owner's `aria-labelledby`/`aria-describedby`
+fn set_description(children: &mut Vec<Element>, attributes: &mut HashMap<String, String>, tag: &str, text: &str) {
+    let index = match children.iter().position(|c| c.tag == tag) {
+        Some(index) => index,
+        None => {
+            let index = if tag == "desc" {
+                children.iter().take_while(|c| c.tag == "title").count()
+            } else {
+                0
+            };
+            let id = match attributes.get("id") {
+                Some(owner) => format!("{}-{}", owner, tag),
+                None => auto_id(tag),
+            };
+            let mut attrs = HashMap::new();
+            attrs.insert("id".to_string(), id);
+            children.insert(index, Element::new(tag, attrs));
+            index
+        }
+    };
+
+    let element = &mut children[index];
+    element.attributes.insert("text-content".to_string(), text.to_string());
+
+    if let Some(id) = element.attributes.get("id") {
+        let aria = if tag == "title" { "aria-labelledby" } else { "aria-describedby" };
+        let current = attributes.get(aria).cloned().unwrap_or_default();
+        if !current.split_whitespace().any(|token| token == id) {
+            let value = if current.is_empty() { id.clone() } else { format!("{} {}", current, id) };
+            attributes.insert(aria.to_string(), value);
+        }
+    }
+}
+
+pub(crate) fn escape_attr(value: &str) -> String {
     value
         .replace('&', "&amp;")
         .replace('"', "&quot;")
@@ -829,7 +1042,7 @@ fn escape_attr(value: &str) -> String {
         .replace('>', "&gt;")
 }
 
-fn escape_text(value: &str) -> String {
+pub(crate) fn escape_text(value: &str) -> String {
     value
         .replace('&', "&amp;")
         .replace('<', "&lt;")
@@ -1035,6 +1248,42 @@ mod tests {
         assert!(output.contains("viewBox=\"0 0 400 300\""));
     }
 
+    #[test]
+    fn test_accessibility_metadata() {
+        let mut svg = Svg::new(200, 200);
+        svg.rect(10, 10);
+        svg.desc("Quarterly revenue by region").title("Revenue").role("img").lang("en");
+        svg.rect(50, 50).id("bar").title("North").desc("42 units").focusable(true).tabindex(0);
+
+        assert_eq!(svg.elements[0].tag, "title");
+        assert_eq!(svg.elements[1].tag, "desc");
+        let title_id = svg.elements[0].attr("id").unwrap().clone();
+        assert_eq!(svg.attr("aria-labelledby"), Some(&title_id));
+
+        let bar = &svg.elements[3];
+        assert_eq!(bar.children[0].attr("id").unwrap(), "bar-title");
+        assert_eq!(bar.attr("aria-labelledby").unwrap(), "bar-title");
+        assert_eq!(bar.attr("aria-describedby").unwrap(), "bar-desc");
+
+        let output = svg.to_string();
+        assert!(output.contains("role=\"img\""));
+        assert!(output.contains("lang=\"en\""));
+        assert!(output.contains(">North</title>"));
+        assert!(output.contains("tabindex=\"0\""));
+    }
+
+    #[test]
+    fn test_title_replaces_existing() {
+        let mut svg = Svg::new(100, 100);
+        let group = svg.group();
+        group.title("First").title("Second").aria_label("Chart");
+
+        assert_eq!(group.children.len(), 1);
+        assert_eq!(group.children[0].attr("text-content").unwrap(), "Second");
+        assert_eq!(group.attr("aria-labelledby").unwrap().split_whitespace().count(), 1);
+        assert_eq!(group.attr("aria-label").unwrap(), "Chart");
+    }
+
     #[cfg(feature = "draggable")]
     #[test]
     fn test_draggable() {